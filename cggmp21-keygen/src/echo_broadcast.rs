@@ -0,0 +1,742 @@
+//! Echo broadcast over point-to-point channels
+//!
+//! All protocols in this library require a reliable broadcast channel: when a party broadcasts a
+//! message, all honest parties must receive exactly the same message. [`enforce_reliable_broadcast`]
+//! option only detects that broadcast was unreliable after the first round. If the transport
+//! provides point-to-point channels only, [`EchoBroadcast`] can be used instead: it's a
+//! [`Delivery`] wrapper that turns any point-to-point transport into a reliable broadcast.
+//!
+//! Broadcast message is delivered as follows:
+//! 1. Sender sends the message to every other party over point-to-point channel
+//! 2. Each receiver echoes hash of received message to all other parties (except the sender)
+//! 3. Receiver outputs the message only when it got the same hash from every other party
+//!
+//! If sender equivocated (sent different messages to different parties), or any of parties
+//! echoed a different hash, honest parties abort with [`EchoBroadcastError`] identifying
+//! the sender and the parties that disagree. P2P messages are passed through without changes.
+//!
+//! ## Signed echoes
+//! Echo broadcast alone can not tell whether the sender equivocated or the parties that echoed
+//! a different hash lied about it. If parties have signing keys known to each other, use
+//! [`EchoBroadcast::with_signatures`]: sender signs the hash of each broadcast message and
+//! receivers forward the signature along with the echo. Any echo with an invalid signature is
+//! attributed to the party who echoed it, and two validly signed different hashes prove that
+//! the sender equivocated, see [`EchoBroadcastError::is_equivocation_proven`].
+//!
+//! The wrapper works with any protocol message, so it applies uniformly to keygen, aux info
+//! generation, key refresh and signing. Note that each broadcast message costs $n-2$ additional
+//! p2p messages per receiver.
+//!
+//! Wrapper needs to know how to hash protocol messages, so you need to provide a hash function.
+//! Usually, it's hash of serialized message.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+//! use cggmp21_keygen::echo_broadcast::{EchoBroadcast, EchoMsg};
+//! use sha2::{Digest, Sha256};
+//!
+//! # type E = generic_ec::curves::Secp256k1;
+//! # type Msg = cggmp21_keygen::msg::non_threshold::Msg<E, cggmp21_keygen::security_level::SecurityLevel128, Sha256>;
+//! # let incoming = futures::stream::pending::<Result<round_based::Incoming<EchoMsg<Msg, Sha256>>, std::convert::Infallible>>();
+//! # let outgoing = futures::sink::drain::<round_based::Outgoing<EchoMsg<Msg, Sha256>>>();
+//! # let (i, n) = (0, 3);
+//! # fn serialize(_: &Msg) -> Vec<u8> { unimplemented!() }
+//! // Point-to-point only transport delivering `EchoMsg<Msg, Sha256>`
+//! let p2p_delivery = (incoming, outgoing);
+//! let delivery = EchoBroadcast::new(i, n, p2p_delivery, |msg: &Msg| {
+//!     Sha256::digest(serialize(msg))
+//! });
+//! let party = round_based::MpcParty::connected(delivery);
+//!
+//! let eid = cggmp21_keygen::ExecutionId::new(b"execution id, unique per protocol execution");
+//! let key_share = cggmp21_keygen::keygen::<E>(eid, i, n)
+//!     .start(&mut rand_core::OsRng, party)
+//!     .await?;
+//! # Ok(()) }
+//! ```
+//!
+//! [`enforce_reliable_broadcast`]: crate::GenericKeygenBuilder::enforce_reliable_broadcast
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::task::{Context, Poll};
+
+use digest::Digest;
+use futures::{Sink, Stream};
+use round_based::{
+    Delivery, Incoming, MessageDestination, MessageType, MsgId, Outgoing, PartyIndex,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{utils, ExecutionId};
+
+/// Message sent over point-to-point transport by [`EchoBroadcast`]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "M: Serialize, S: Serialize",
+    deserialize = "M: Deserialize<'de>, S: Deserialize<'de>"
+))]
+pub enum EchoMsg<M, D: Digest, S = ()> {
+    /// Broadcast message sent by its author
+    Send {
+        /// Sequence number of broadcast message sent by the author
+        seq: u64,
+        /// Protocol message
+        msg: M,
+        /// Signature of the author (empty if echoes are not signed)
+        signature: S,
+    },
+    /// Hash of broadcast message echoed by receiver
+    Echo {
+        /// Index of party who's the author of the message
+        origin: PartyIndex,
+        /// Sequence number of broadcast message sent by the author
+        seq: u64,
+        /// Hash of the message
+        hash: digest::Output<D>,
+        /// Signature of the author forwarded by receiver (empty if echoes are not signed)
+        signature: S,
+    },
+    /// P2P message
+    Direct(M),
+}
+
+/// Signs broadcast messages and verifies signatures of other parties
+///
+/// Used by [`EchoBroadcast::with_signatures`] to make equivocation identifiable. Data being
+/// signed is already bound to the execution ID, the author and the sequence number of the message.
+pub trait Authenticator {
+    /// Signature
+    type Signature: Clone;
+
+    /// Signs `data` on behalf of local party
+    fn sign(&self, data: &[u8]) -> Self::Signature;
+    /// Verifies that `signature` of `data` was produced by party `signer`
+    fn verify(&self, signer: PartyIndex, data: &[u8], signature: &Self::Signature) -> bool;
+}
+
+/// Echoes are not signed
+///
+/// Used by [`EchoBroadcast::new`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Unsigned;
+
+impl Authenticator for Unsigned {
+    type Signature = ();
+
+    fn sign(&self, _data: &[u8]) -> Self::Signature {}
+    fn verify(&self, _signer: PartyIndex, _data: &[u8], _signature: &()) -> bool {
+        true
+    }
+}
+
+/// Delivery wrapper that provides reliable broadcast on top of point-to-point transport
+///
+/// See [module level docs](self) for details.
+pub struct EchoBroadcast<M, Dl, D, H, A = Unsigned> {
+    i: PartyIndex,
+    n: u16,
+    delivery: Dl,
+    params: Params<H, A>,
+    _ph: std::marker::PhantomData<fn(M) -> D>,
+}
+
+impl<M, Dl, D, H> EchoBroadcast<M, Dl, D, H>
+where
+    Dl: Delivery<EchoMsg<M, D>>,
+    D: Digest,
+    H: Fn(&M) -> digest::Output<D>,
+{
+    /// Wraps point-to-point delivery
+    ///
+    /// Takes index of local party $i$, number of parties $n$ participating in the protocol,
+    /// underlying delivery, and a function that hashes protocol messages.
+    pub fn new(i: PartyIndex, n: u16, delivery: Dl, hash: H) -> Self {
+        Self {
+            i,
+            n,
+            delivery,
+            params: Params {
+                hash,
+                auth: Unsigned,
+                eid: Vec::new(),
+                signed: false,
+            },
+            _ph: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<M, Dl, D, H, A> EchoBroadcast<M, Dl, D, H, A>
+where
+    Dl: Delivery<EchoMsg<M, D, A::Signature>>,
+    D: Digest,
+    H: Fn(&M) -> digest::Output<D>,
+    A: Authenticator,
+{
+    /// Wraps point-to-point delivery, broadcast messages are signed by their authors
+    ///
+    /// Same as [`EchoBroadcast::new`], but also takes execution ID and `auth` that signs
+    /// messages of local party and verifies signatures of other parties. Execution ID must
+    /// be the same as the one used by the protocol.
+    pub fn with_signatures(
+        eid: ExecutionId,
+        i: PartyIndex,
+        n: u16,
+        delivery: Dl,
+        hash: H,
+        auth: A,
+    ) -> Self {
+        Self {
+            i,
+            n,
+            delivery,
+            params: Params {
+                hash,
+                auth,
+                eid: eid.as_bytes().to_vec(),
+                signed: true,
+            },
+            _ph: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<M, Dl, D, H, A> Delivery<M> for EchoBroadcast<M, Dl, D, H, A>
+where
+    M: Clone,
+    Dl: Delivery<EchoMsg<M, D, A::Signature>>,
+    D: Digest,
+    H: Fn(&M) -> digest::Output<D>,
+    A: Authenticator,
+{
+    type Send = Outgoings<M, Dl::Send, D, H, A>;
+    type Receive = Incomings<M, Dl::Receive, Dl::Send, D, H, A>;
+    type SendError = EchoBroadcastError<Dl::SendError>;
+    type ReceiveError = EchoBroadcastError<Dl::ReceiveError, Dl::SendError>;
+
+    fn split(self) -> (Self::Receive, Self::Send) {
+        let (incomings, outgoings) = self.delivery.split();
+        let shared = Arc::new(Mutex::new(SharedSink {
+            sink: outgoings,
+            queue: VecDeque::new(),
+        }));
+        let params = Arc::new(self.params);
+        (
+            Incomings {
+                i: self.i,
+                n: self.n,
+                incomings,
+                shared: shared.clone(),
+                params: params.clone(),
+                pending: HashMap::new(),
+                delivered: vec![0; usize::from(self.n)],
+                ready: VecDeque::new(),
+                next_id: 0,
+                terminated: false,
+            },
+            Outgoings {
+                i: self.i,
+                n: self.n,
+                shared,
+                params,
+                next_seq: 0,
+            },
+        )
+    }
+}
+
+/// Parameters shared between incoming and outgoing halves
+struct Params<H, A> {
+    hash: H,
+    auth: A,
+    eid: Vec<u8>,
+    signed: bool,
+}
+
+impl<H, A: Authenticator> Params<H, A> {
+    /// Data signed by author of broadcast message
+    fn signed_data<D: Digest>(
+        &self,
+        origin: PartyIndex,
+        seq: u64,
+        hash: &digest::Output<D>,
+    ) -> Vec<u8> {
+        const TAG: &[u8] = b"dfns.cggmp21.echo_broadcast";
+        let eid_len = u64::try_from(self.eid.len()).unwrap_or(u64::MAX);
+        [
+            TAG,
+            &eid_len.to_be_bytes(),
+            &self.eid,
+            &origin.to_be_bytes(),
+            &seq.to_be_bytes(),
+            hash,
+        ]
+        .concat()
+    }
+
+    fn sign<D: Digest>(
+        &self,
+        origin: PartyIndex,
+        seq: u64,
+        hash: &digest::Output<D>,
+    ) -> A::Signature {
+        self.auth.sign(&self.signed_data::<D>(origin, seq, hash))
+    }
+
+    fn verify<D: Digest>(
+        &self,
+        origin: PartyIndex,
+        seq: u64,
+        hash: &digest::Output<D>,
+        signature: &A::Signature,
+    ) -> bool {
+        self.auth
+            .verify(origin, &self.signed_data::<D>(origin, seq, hash), signature)
+    }
+}
+
+/// Outgoing sink shared between incoming and outgoing halves
+///
+/// Incoming half needs to send echoes, so both halves push messages into the queue, and
+/// whichever half is polled flushes it. When both halves are dropped, remaining messages
+/// are flushed on the best-effort basis.
+struct SharedSink<M, S, D, A>
+where
+    S: Sink<Outgoing<EchoMsg<M, D, A::Signature>>> + Unpin,
+    D: Digest,
+    A: Authenticator,
+{
+    sink: S,
+    queue: VecDeque<Outgoing<EchoMsg<M, D, A::Signature>>>,
+}
+
+impl<M, S, D, A> SharedSink<M, S, D, A>
+where
+    S: Sink<Outgoing<EchoMsg<M, D, A::Signature>>> + Unpin,
+    D: Digest,
+    A: Authenticator,
+{
+    /// Sends all queued messages and flushes the sink
+    fn poll_flush(&mut self, cx: &mut Context) -> Poll<Result<(), S::Error>> {
+        while !self.queue.is_empty() {
+            futures::ready!(Pin::new(&mut self.sink).poll_ready(cx))?;
+            if let Some(msg) = self.queue.pop_front() {
+                Pin::new(&mut self.sink).start_send(msg)?;
+            }
+        }
+        Pin::new(&mut self.sink).poll_flush(cx)
+    }
+}
+
+impl<M, S, D, A> Drop for SharedSink<M, S, D, A>
+where
+    S: Sink<Outgoing<EchoMsg<M, D, A::Signature>>> + Unpin,
+    D: Digest,
+    A: Authenticator,
+{
+    fn drop(&mut self) {
+        // Echoes queued after the last poll would never reach other parties otherwise. We can't
+        // block in drop, so messages are only sent if the sink accepts them right away.
+        let waker = futures::task::noop_waker();
+        let _ = self.poll_flush(&mut Context::from_waker(&waker));
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // Lock can only be poisoned if sink panicked, state of the queue remains consistent anyway
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// Outgoing half of [`EchoBroadcast`]
+pub struct Outgoings<M, S, D, H, A = Unsigned>
+where
+    S: Sink<Outgoing<EchoMsg<M, D, A::Signature>>> + Unpin,
+    D: Digest,
+    A: Authenticator,
+{
+    i: PartyIndex,
+    n: u16,
+    shared: Arc<Mutex<SharedSink<M, S, D, A>>>,
+    params: Arc<Params<H, A>>,
+    next_seq: u64,
+}
+
+impl<M, S, D, H, A> Sink<Outgoing<M>> for Outgoings<M, S, D, H, A>
+where
+    M: Clone,
+    S: Sink<Outgoing<EchoMsg<M, D, A::Signature>>> + Unpin,
+    D: Digest,
+    H: Fn(&M) -> digest::Output<D>,
+    A: Authenticator,
+{
+    type Error = EchoBroadcastError<S::Error>;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        // Messages are queued, queue is unbounded
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Outgoing<M>) -> Result<(), Self::Error> {
+        let this = self.get_mut();
+        let mut shared = lock(&this.shared);
+        match item.recipient {
+            MessageDestination::AllParties => {
+                let seq = this.next_seq;
+                this.next_seq += 1;
+                let hash = (this.params.hash)(&item.msg);
+                let signature = this.params.sign::<D>(this.i, seq, &hash);
+                for j in utils::iter_peers(this.i, this.n) {
+                    shared.queue.push_back(Outgoing::p2p(
+                        j,
+                        EchoMsg::Send {
+                            seq,
+                            msg: item.msg.clone(),
+                            signature: signature.clone(),
+                        },
+                    ));
+                }
+            }
+            MessageDestination::OneParty(j) => shared
+                .queue
+                .push_back(Outgoing::p2p(j, EchoMsg::Direct(item.msg))),
+        }
+        Ok(())
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        lock(&self.shared)
+            .poll_flush(cx)
+            .map_err(|err| Reason::Send(err).into())
+    }
+
+    fn poll_close(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        let mut shared = lock(&self.shared);
+        futures::ready!(shared.poll_flush(cx)).map_err(Reason::Send)?;
+        Pin::new(&mut shared.sink)
+            .poll_close(cx)
+            .map_err(|err| Reason::Send(err).into())
+    }
+}
+
+/// Maximum number of broadcast messages of a single party that can be pending at the same time
+///
+/// A party can't get far ahead of others as each round requires messages from all parties, so
+/// honest parties never exceed this limit. It prevents malicious parties from making us store
+/// arbitrary many messages.
+const SEQ_WINDOW: u64 = 16;
+
+/// Broadcast message that's not delivered yet
+struct PendingMsg<M, D: Digest> {
+    msg: Option<(M, digest::Output<D>)>,
+    echoes: HashMap<PartyIndex, digest::Output<D>>,
+}
+
+/// Incoming half of [`EchoBroadcast`]
+pub struct Incomings<M, R, S, D, H, A = Unsigned>
+where
+    S: Sink<Outgoing<EchoMsg<M, D, A::Signature>>> + Unpin,
+    D: Digest,
+    A: Authenticator,
+{
+    i: PartyIndex,
+    n: u16,
+    incomings: R,
+    shared: Arc<Mutex<SharedSink<M, S, D, A>>>,
+    params: Arc<Params<H, A>>,
+    pending: HashMap<(PartyIndex, u64), PendingMsg<M, D>>,
+    /// Number of delivered broadcast messages, for each party
+    delivered: Vec<u64>,
+    ready: VecDeque<Incoming<M>>,
+    next_id: MsgId,
+    terminated: bool,
+}
+
+// Pinning is never structural: incoming stream is required to be `Unpin` and all the other fields
+// are never pinned
+impl<M, R, S, D, H, A> Unpin for Incomings<M, R, S, D, H, A>
+where
+    R: Unpin,
+    S: Sink<Outgoing<EchoMsg<M, D, A::Signature>>> + Unpin,
+    D: Digest,
+    A: Authenticator,
+{
+}
+
+impl<M, R, S, D, H, A, RErr> Incomings<M, R, S, D, H, A>
+where
+    M: Clone,
+    R: Stream<Item = Result<Incoming<EchoMsg<M, D, A::Signature>>, RErr>> + Unpin,
+    S: Sink<Outgoing<EchoMsg<M, D, A::Signature>>> + Unpin,
+    D: Digest,
+    H: Fn(&M) -> digest::Output<D>,
+    A: Authenticator,
+{
+    fn process(
+        &mut self,
+        incoming: Incoming<EchoMsg<M, D, A::Signature>>,
+    ) -> Result<(), Reason<RErr, S::Error>> {
+        let sender = incoming.sender;
+        if sender >= self.n || sender == self.i {
+            return Err(Reason::UnexpectedMessage { sender });
+        }
+        match incoming.msg {
+            EchoMsg::Direct(msg) => {
+                self.deliver(sender, MessageType::P2P, msg);
+                Ok(())
+            }
+            EchoMsg::Send {
+                seq,
+                msg,
+                signature,
+            } => {
+                let hash = (self.params.hash)(&msg);
+                if !self.params.verify::<D>(sender, seq, &hash, &signature) {
+                    return Err(Reason::InvalidSignature { sender });
+                }
+                let pending = self.pending_msg(sender, sender, seq)?;
+                if pending.msg.is_some() {
+                    return Err(Reason::UnexpectedMessage { sender });
+                }
+                pending.msg = Some((msg, hash.clone()));
+
+                let mut shared = lock(&self.shared);
+                for j in utils::iter_peers(self.i, self.n).filter(|j| *j != sender) {
+                    shared.queue.push_back(Outgoing::p2p(
+                        j,
+                        EchoMsg::Echo {
+                            origin: sender,
+                            seq,
+                            hash: hash.clone(),
+                            signature: signature.clone(),
+                        },
+                    ))
+                }
+                drop(shared);
+
+                self.try_deliver(sender)
+            }
+            EchoMsg::Echo {
+                origin,
+                seq,
+                hash,
+                signature,
+            } => {
+                if origin >= self.n || origin == self.i || origin == sender {
+                    return Err(Reason::UnexpectedMessage { sender });
+                }
+                // Echo must carry signature of the author, otherwise the party who echoed
+                // it is to blame
+                if !self.params.verify::<D>(origin, seq, &hash, &signature) {
+                    return Err(Reason::InvalidSignature { sender });
+                }
+                let pending = self.pending_msg(sender, origin, seq)?;
+                if pending.echoes.insert(sender, hash).is_some() {
+                    return Err(Reason::UnexpectedMessage { sender });
+                }
+                self.try_deliver(origin)
+            }
+        }
+    }
+
+    /// Returns pending message `seq` of party `origin`, `sender` is the party who sent it or echoed it
+    ///
+    /// `origin` must be an index of other party. Returns error if `seq` is out of [`SEQ_WINDOW`].
+    fn pending_msg(
+        &mut self,
+        sender: PartyIndex,
+        origin: PartyIndex,
+        seq: u64,
+    ) -> Result<&mut PendingMsg<M, D>, Reason<RErr, S::Error>> {
+        let delivered = self.delivered[usize::from(origin)];
+        if seq < delivered || seq - delivered >= SEQ_WINDOW {
+            return Err(Reason::UnexpectedMessage { sender });
+        }
+        Ok(self
+            .pending
+            .entry((origin, seq))
+            .or_insert_with(|| PendingMsg {
+                msg: None,
+                echoes: HashMap::new(),
+            }))
+    }
+
+    /// Delivers broadcast messages of `origin` that have been echoed by all other parties
+    ///
+    /// Messages of the same party are delivered in the order they were sent.
+    fn try_deliver(&mut self, origin: PartyIndex) -> Result<(), Reason<RErr, S::Error>> {
+        loop {
+            let seq = self.delivered[usize::from(origin)];
+            let Some(pending) = self.pending.get(&(origin, seq)) else {
+                return Ok(());
+            };
+            // We expect an echo from every party except us and the sender
+            if pending.msg.is_none() || pending.echoes.len() + 2 < usize::from(self.n) {
+                return Ok(());
+            }
+            let Some(PendingMsg {
+                msg: Some((msg, hash)),
+                echoes,
+            }) = self.pending.remove(&(origin, seq))
+            else {
+                return Ok(());
+            };
+
+            let mut disagree = echoes
+                .into_iter()
+                .filter(|(_j, hash_j)| *hash_j != hash)
+                .map(|(j, _)| j)
+                .collect::<Vec<_>>();
+            if !disagree.is_empty() && self.params.signed {
+                // All echoes carry valid signatures of the author, so it signed different hashes
+                return Err(Reason::SignedEquivocation { sender: origin });
+            }
+            if !disagree.is_empty() {
+                disagree.sort_unstable();
+                return Err(Reason::Equivocation {
+                    sender: origin,
+                    disagree,
+                });
+            }
+
+            self.delivered[usize::from(origin)] += 1;
+            self.deliver(origin, MessageType::Broadcast, msg);
+        }
+    }
+
+    fn deliver(&mut self, sender: PartyIndex, msg_type: MessageType, msg: M) {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.ready.push_back(Incoming {
+            id,
+            sender,
+            msg_type,
+            msg,
+        })
+    }
+}
+
+impl<M, R, S, D, H, A, RErr> Stream for Incomings<M, R, S, D, H, A>
+where
+    M: Clone,
+    R: Stream<Item = Result<Incoming<EchoMsg<M, D, A::Signature>>, RErr>> + Unpin,
+    S: Sink<Outgoing<EchoMsg<M, D, A::Signature>>> + Unpin,
+    D: Digest,
+    H: Fn(&M) -> digest::Output<D>,
+    A: Authenticator,
+{
+    type Item = Result<Incoming<M>, EchoBroadcastError<RErr, S::Error>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            // Send echoes (if there are any). It must be done before handing out received
+            // messages: if the protocol stops polling incomings after that, echoes would
+            // never be sent and other parties would get stuck.
+            if this.terminated {
+                futures::ready!(lock(&this.shared).poll_flush(cx)).map_err(Reason::Send)?;
+                return Poll::Ready(None);
+            }
+            if let Poll::Ready(Err(err)) = lock(&this.shared).poll_flush(cx) {
+                return Poll::Ready(Some(Err(Reason::Send(err).into())));
+            }
+
+            if let Some(incoming) = this.ready.pop_front() {
+                return Poll::Ready(Some(Ok(incoming)));
+            }
+
+            match futures::ready!(Pin::new(&mut this.incomings).poll_next(cx)) {
+                Some(Ok(incoming)) => {
+                    if let Err(err) = this.process(incoming) {
+                        return Poll::Ready(Some(Err(err.into())));
+                    }
+                }
+                Some(Err(err)) => return Poll::Ready(Some(Err(Reason::Receive(err).into()))),
+                None => this.terminated = true,
+            }
+        }
+    }
+}
+
+/// Echo broadcast error
+///
+/// Error either originates from underlying delivery or indicates that some party
+/// misbehaved (e.g. equivocated when broadcasting a message).
+#[derive(Error)]
+#[error(transparent)]
+pub struct EchoBroadcastError<RErr, SErr = RErr>(#[from] Reason<RErr, SErr>);
+
+impl<RErr, SErr> EchoBroadcastError<RErr, SErr> {
+    /// Returns index of party who's suspected in equivocation
+    ///
+    /// Returns `None` if error is not related to equivocation. Note that unless echoes are
+    /// [signed](EchoBroadcast::with_signatures), echo broadcast can not distinguish whether sender
+    /// sent different messages or parties that echoed different hash lied about it, see
+    /// [`disagreeing_parties`](Self::disagreeing_parties).
+    pub fn equivocated_party(&self) -> Option<PartyIndex> {
+        match &self.0 {
+            Reason::Equivocation { sender, .. } | Reason::SignedEquivocation { sender } => {
+                Some(*sender)
+            }
+            _ => None,
+        }
+    }
+
+    /// Indicates that [equivocated party](Self::equivocated_party) signed different messages
+    ///
+    /// Only possible if echoes are [signed](EchoBroadcast::with_signatures). In this case, the
+    /// equivocated party is certainly malicious.
+    pub fn is_equivocation_proven(&self) -> bool {
+        matches!(&self.0, Reason::SignedEquivocation { .. })
+    }
+
+    /// Returns list of parties that echoed a hash which doesn't match the received message
+    ///
+    /// Returns empty list if error is not related to equivocation, or if equivocation is
+    /// [proven](Self::is_equivocation_proven)
+    pub fn disagreeing_parties(&self) -> &[PartyIndex] {
+        match &self.0 {
+            Reason::Equivocation { disagree, .. } => disagree,
+            _ => &[],
+        }
+    }
+
+    /// Returns index of party who sent a message with invalid signature
+    ///
+    /// Returns `None` if error is not related to invalid signature
+    pub fn party_with_invalid_signature(&self) -> Option<PartyIndex> {
+        match &self.0 {
+            Reason::InvalidSignature { sender } => Some(*sender),
+            _ => None,
+        }
+    }
+}
+
+impl<RErr: fmt::Debug, SErr: fmt::Debug> fmt::Debug for EchoBroadcastError<RErr, SErr> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EchoBroadcastError").field(&self.0).finish()
+    }
+}
+
+#[derive(Debug, Error)]
+enum Reason<RErr, SErr> {
+    #[error("receive message")]
+    Receive(#[source] RErr),
+    #[error("send message")]
+    Send(#[source] SErr),
+    #[error("party {sender} sent unexpected message")]
+    UnexpectedMessage { sender: PartyIndex },
+    #[error("party {sender} equivocated: parties {disagree:?} received a different message")]
+    Equivocation {
+        sender: PartyIndex,
+        disagree: Vec<PartyIndex>,
+    },
+    #[error("party {sender} equivocated: it signed different messages")]
+    SignedEquivocation { sender: PartyIndex },
+    #[error("party {sender} sent a message with invalid signature")]
+    InvalidSignature { sender: PartyIndex },
+}
@@ -1,8 +1,11 @@
 //! Threshold and non-threshold CGGMP21 DKG
 #![allow(non_snake_case, clippy::too_many_arguments)]
 
+pub mod echo_broadcast;
+pub mod non_interactive;
 pub mod progress;
 pub mod security_level;
+pub mod transcript;
 
 /// Non-threshold DKG specific types
 mod non_threshold;
@@ -24,6 +27,7 @@ use thiserror::Error;
 pub use key_share;
 
 use crate::progress::Tracer;
+use crate::transcript::KeygenTranscript;
 use crate::{
     errors::IoError,
     key_share::{CoreKeyShare, InvalidCoreShare},
@@ -82,8 +86,11 @@ pub struct GenericKeygenBuilder<'a, E: Curve, M, L: SecurityLevel, D: Digest> {
     optional_t: M,
     execution_id: ExecutionId<'a>,
     tracer: Option<&'a mut dyn Tracer>,
+    transcript: Option<&'a mut KeygenTranscript<E>>,
     #[cfg(feature = "hd-wallets")]
     hd_enabled: bool,
+    #[cfg(feature = "hd-wallets")]
+    fixed_chain_code: Option<FixedChainCode>,
     _params: std::marker::PhantomData<(E, L, D)>,
 }
 
@@ -109,8 +116,11 @@ where
             reliable_broadcast_enforced: true,
             execution_id: eid,
             tracer: None,
+            transcript: None,
             #[cfg(feature = "hd-wallets")]
             hd_enabled: true,
+            #[cfg(feature = "hd-wallets")]
+            fixed_chain_code: None,
             _params: std::marker::PhantomData,
         }
     }
@@ -131,8 +141,11 @@ where
             reliable_broadcast_enforced: self.reliable_broadcast_enforced,
             execution_id: self.execution_id,
             tracer: self.tracer,
+            transcript: self.transcript,
             #[cfg(feature = "hd-wallets")]
             hd_enabled: self.hd_enabled,
+            #[cfg(feature = "hd-wallets")]
+            fixed_chain_code: self.fixed_chain_code,
             _params: std::marker::PhantomData,
         }
     }
@@ -148,8 +161,11 @@ where
             reliable_broadcast_enforced: self.reliable_broadcast_enforced,
             execution_id: self.execution_id,
             tracer: self.tracer,
+            transcript: self.transcript,
             #[cfg(feature = "hd-wallets")]
             hd_enabled: self.hd_enabled,
+            #[cfg(feature = "hd-wallets")]
+            fixed_chain_code: self.fixed_chain_code,
             _params: std::marker::PhantomData,
         }
     }
@@ -166,8 +182,11 @@ where
             reliable_broadcast_enforced: self.reliable_broadcast_enforced,
             execution_id: self.execution_id,
             tracer: self.tracer,
+            transcript: self.transcript,
             #[cfg(feature = "hd-wallets")]
             hd_enabled: self.hd_enabled,
+            #[cfg(feature = "hd-wallets")]
+            fixed_chain_code: self.fixed_chain_code,
             _params: std::marker::PhantomData,
         }
     }
@@ -178,6 +197,16 @@ where
         self
     }
 
+    /// Sets a transcript recorder
+    ///
+    /// Every broadcast message observed during protocol execution will be recorded into the
+    /// transcript. Recorded transcript can be verified later via
+    /// [`verify_keygen_transcript`](transcript::verify_keygen_transcript).
+    pub fn set_transcript_recorder(mut self, transcript: &'a mut KeygenTranscript<E>) -> Self {
+        self.transcript = Some(transcript);
+        self
+    }
+
     #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
     pub fn enforce_reliable_broadcast(self, enforce: bool) -> Self {
         Self {
@@ -192,6 +221,72 @@ where
         self.hd_enabled = v;
         self
     }
+
+    #[cfg(feature = "hd-wallets")]
+    /// Uses a fixed chain code instead of generating a random one
+    ///
+    /// By default, chain code is XOR of random contributions of all parties. With this option,
+    /// every party commits to the provided chain code, and the protocol aborts if any party
+    /// contributed a different one. All parties must specify the same chain code.
+    ///
+    /// Useful for migrating existing BIP32 wallets which extended public key is already known.
+    ///
+    /// Implies [`hd_wallet(true)`](Self::hd_wallet).
+    pub fn set_chain_code(mut self, chain_code: slip_10::ChainCode) -> Self {
+        self.hd_enabled = true;
+        self.fixed_chain_code = Some(FixedChainCode::Value(chain_code));
+        self
+    }
+
+    #[cfg(feature = "hd-wallets")]
+    /// Derives chain code deterministically from execution ID
+    ///
+    /// Chain code is computed as hash of execution ID, so every execution with the same
+    /// execution ID yields the same chain code. All parties must enable this option, otherwise
+    /// the protocol aborts.
+    ///
+    /// Implies [`hd_wallet(true)`](Self::hd_wallet).
+    pub fn derive_chain_code_from_execution_id(mut self) -> Self {
+        self.hd_enabled = true;
+        self.fixed_chain_code = Some(FixedChainCode::FromExecutionId);
+        self
+    }
+
+    /// Returns fixed chain code if it was specified and HD derivation is enabled
+    #[cfg(feature = "hd-wallets")]
+    fn resolve_chain_code(&self) -> Option<slip_10::ChainCode> {
+        if !self.hd_enabled {
+            return None;
+        }
+        match self.fixed_chain_code? {
+            FixedChainCode::Value(chain_code) => Some(chain_code),
+            FixedChainCode::FromExecutionId => {
+                Some(derive_chain_code::<D>(self.execution_id.as_bytes()))
+            }
+        }
+    }
+}
+
+/// Chain code set via [`GenericKeygenBuilder::set_chain_code`] or
+/// [`GenericKeygenBuilder::derive_chain_code_from_execution_id`]
+#[cfg(feature = "hd-wallets")]
+#[derive(Clone, Copy)]
+enum FixedChainCode {
+    Value(slip_10::ChainCode),
+    FromExecutionId,
+}
+
+/// Derives chain code from execution ID
+#[cfg(feature = "hd-wallets")]
+fn derive_chain_code<D: Digest>(eid: &[u8]) -> slip_10::ChainCode {
+    let mut rng = rng::HashRng::new(|d: D| {
+        d.chain_update(b"dfns.cggmp21.keygen.chain_code")
+            .chain_update(eid)
+            .finalize()
+    });
+    let mut chain_code = slip_10::ChainCode::default();
+    rng.fill_bytes(&mut chain_code);
+    chain_code
 }
 
 impl<'a, E, L, D> GenericKeygenBuilder<'a, E, NonThreshold, L, D>
@@ -206,8 +301,11 @@ where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = non_threshold::Msg<E, L, D>>,
     {
+        #[cfg(feature = "hd-wallets")]
+        let fixed_chain_code = self.resolve_chain_code();
         non_threshold::run_keygen(
             self.tracer,
+            self.transcript,
             self.i,
             self.n,
             self.reliable_broadcast_enforced,
@@ -216,6 +314,8 @@ where
             party,
             #[cfg(feature = "hd-wallets")]
             self.hd_enabled,
+            #[cfg(feature = "hd-wallets")]
+            fixed_chain_code,
         )
         .await
     }
@@ -233,8 +333,11 @@ where
         R: RngCore + CryptoRng,
         M: Mpc<ProtocolMessage = threshold::Msg<E, L, D>>,
     {
+        #[cfg(feature = "hd-wallets")]
+        let fixed_chain_code = self.resolve_chain_code();
         threshold::run_threshold_keygen(
             self.tracer,
+            self.transcript,
             self.i,
             self.optional_t.0,
             self.n,
@@ -244,6 +347,8 @@ where
             party,
             #[cfg(feature = "hd-wallets")]
             self.hd_enabled,
+            #[cfg(feature = "hd-wallets")]
+            fixed_chain_code,
         )
         .await
     }
@@ -296,6 +401,9 @@ enum KeygenAborted {
     #[cfg(feature = "hd-wallets")]
     #[error("party did not generate chain code: {0:?}")]
     MissingChainCode(Vec<utils::AbortBlame>),
+    #[cfg(feature = "hd-wallets")]
+    #[error("party contributed chain code that doesn't match the fixed one: {0:?}")]
+    ChainCodeMismatch(Vec<utils::AbortBlame>),
 }
 
 #[derive(Debug, Error)]
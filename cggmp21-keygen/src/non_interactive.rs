@@ -0,0 +1,744 @@
+//! Non-interactive threshold DKG
+//!
+//! Interactive [threshold DKG](crate::GenericKeygenBuilder::set_threshold) requires all parties
+//! to be online at the same time. This module provides a DKG variant that's suitable for parties
+//! that communicate through a public bulletin board and may not be online simultaneously.
+//!
+//! Before DKG, each party $i$ generates a [`DecryptionKey`] and publishes corresponding
+//! [`EncryptionKey`]. Then protocol is carried out as follows:
+//! 1. Every party $i$ posts a single [`Dealing`] on the bulletin board: it contains Feldman
+//!    commitment to a random polynomial $f_i$ of degree $t-1$, Schnorr proof of knowledge of
+//!    $f_i(0)$, and shares $f_i(j)$ encrypted to every party $j$ along with proofs that
+//!    each encrypted share matches Feldman commitment (see [`EncryptedShare`]).
+//! 2. Any party can finalize its key share at any time via [`NonInteractiveKeygen::finalize`]
+//!    given the dealings from the bulletin board. Dealings are publicly verifiable via
+//!    [`NonInteractiveKeygen::verify_dealing`], so every party excludes the same invalid
+//!    dealings, and no complaints are needed. At least $t$ dealings must be valid.
+//!
+//! All parties must finalize the key share with the same set of dealings, otherwise they will
+//! end up with different keys. Usually, the bulletin board defines a deadline after which no
+//! dealings are accepted.
+//!
+//! Shares are encrypted bit by bit, so size of the dealing and time of its verification are
+//! proportional to $n \cdot \ell$, where $\ell$ is bit size of the scalar. E.g. for secp256k1,
+//! each encrypted share takes about 50KB.
+//!
+//! ## Threat model
+//! The protocol doesn't have a commitment round: dealings are revealed as soon as they are
+//! posted. Security holds against up to $t-1$ corrupted parties under the following assumptions:
+//! * Bulletin board is reliable: every party observes the same dealings, in the same order,
+//!   and the set of dealings is fixed once the deadline has passed.
+//! * Encryption keys are published and authenticated before any dealing is posted.
+//!
+//! **Output key is not uniformly random.** A party that posts its dealing last sees the
+//! dealings of everyone else, and may choose its own polynomial depending on them. It can't
+//! choose the resulting public key (it still needs to prove knowledge of $f_i(0)$), but it can
+//! bias it: e.g. by trying different polynomials until the public key satisfies some predicate,
+//! or by choosing between posting and withholding its dealing. The secret key remains unknown to
+//! any set of fewer than $t$ parties, so the key is safe to use with signing protocols that
+//! don't rely on it being uniform (such as ECDSA and Schnorr signatures). If the application
+//! requires uniformly random key, use interactive [threshold DKG](crate::GenericKeygenBuilder::set_threshold)
+//! which commits to the polynomials before revealing them.
+//!
+//! ## Example
+//! ```rust
+//! # fn main() -> Result<(), cggmp21_keygen::non_interactive::NonInteractiveKeygenError> {
+//! use cggmp21_keygen::non_interactive::{DecryptionKey, NonInteractiveKeygen};
+//! use cggmp21_keygen::ExecutionId;
+//! # type E = generic_ec::curves::Secp256k1;
+//! # let mut rng = rand_core::OsRng;
+//! let (t, n) = (2, 3);
+//!
+//! // Each party generates and publishes its encryption key
+//! let decryption_keys = (0..n)
+//!     .map(|_| DecryptionKey::<E>::generate(&mut rng))
+//!     .collect::<Vec<_>>();
+//! let encryption_keys = decryption_keys
+//!     .iter()
+//!     .map(|dk| dk.encryption_key())
+//!     .collect::<Vec<_>>();
+//!
+//! let eid = ExecutionId::new(b"execution id, unique per protocol execution");
+//! let keygen = NonInteractiveKeygen::new(eid, t, &encryption_keys)?;
+//!
+//! // Each party posts its dealing on the bulletin board
+//! let dealings = (0..n)
+//!     .map(|i| keygen.deal(&mut rng, i).map(Some))
+//!     .collect::<Result<Vec<_>, _>>()?;
+//!
+//! // Once bulletin board is closed, party finalizes its key share
+//! let i = 0;
+//! let key_share = keygen.finalize(i, &decryption_keys[0], &dealings)?;
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::{polynomial::Polynomial, schnorr_pok};
+use rand_core::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::key_share::{
+    CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, InvalidCoreShare, Validate, VssSetup,
+};
+use crate::ExecutionId;
+
+/// Decryption key of the party
+///
+/// Used to decrypt shares addressed to the party
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct DecryptionKey<E: Curve>(NonZero<SecretScalar<E>>);
+
+/// Encryption key of the party
+///
+/// Must be published before DKG is started
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct EncryptionKey<E: Curve>(pub NonZero<Point<E>>);
+
+impl<E: Curve> DecryptionKey<E> {
+    /// Generates a random decryption key
+    pub fn generate(rng: &mut (impl RngCore + CryptoRng)) -> Self {
+        Self(NonZero::<SecretScalar<E>>::random(rng))
+    }
+
+    /// Returns encryption key corresponding to this decryption key
+    pub fn encryption_key(&self) -> EncryptionKey<E> {
+        EncryptionKey(Point::generator() * &self.0)
+    }
+}
+
+/// Dealing posted by party on the bulletin board
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct Dealing<E: Curve> {
+    /// Feldman commitment to polynomial $F_i = f_i \cdot G$
+    pub F: Polynomial<Point<E>>,
+    /// Schnorr proof commitment $A_i$
+    pub sch_commit: schnorr_pok::Commit<E>,
+    /// Schnorr proof of knowledge of $f_i(0)$
+    pub sch_proof: schnorr_pok::Proof<E>,
+    /// Shares $f_i(j+1)$ encrypted to each party $j$
+    pub encrypted_shares: Vec<EncryptedShare<E>>,
+}
+
+/// Share $s = f_i(j+1)$ verifiably encrypted to party $j$
+///
+/// Every bit $b_k$ of the share is encrypted via ElGamal in the exponent as
+/// $(R_k, C_k) = (r_k \cdot G, b_k \cdot G + r_k \cdot \text{ek}_j)$ along with proof that
+/// $b_k \in \\{0, 1\\}$. Consistency proof shows that the bits add up to the share committed
+/// in $F_i$, i.e. that $\log_G \sum_k 2^k R_k = \log_{\text{ek}_j} (\sum_k 2^k C_k - F_i(j+1))$.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct EncryptedShare<E: Curve> {
+    /// Encrypted bits of the share, most significant bit first
+    pub bits: Vec<EncryptedBit<E>>,
+    /// Proof that encrypted bits match Feldman commitment
+    pub consistency_proof: DleqProof<E>,
+}
+
+/// Encrypted bit $(R_k, C_k)$ of the share
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct EncryptedBit<E: Curve> {
+    /// $R_k = r_k \cdot G$
+    pub R: Point<E>,
+    /// $C_k = b_k \cdot G + r_k \cdot \text{ek}_j$
+    pub C: Point<E>,
+    /// Proof that $b_k \in \\{0, 1\\}$
+    pub proof: BitProof<E>,
+}
+
+/// Proof that ElGamal ciphertext $(R, C)$ encrypts either $0$ or $1$
+///
+/// OR-composition of two proofs of discrete logarithm equality: for $\beta \in \\{0, 1\\}$,
+/// it shows that $\log_G R = \log_{\text{ek}} (C - \beta \cdot G)$ for at least one $\beta$.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct BitProof<E: Curve> {
+    /// Challenges $e_0, e_1$
+    pub challenges: [Scalar<E>; 2],
+    /// Responses $z_0, z_1$
+    pub responses: [Scalar<E>; 2],
+}
+
+/// Proof of discrete logarithm equality
+///
+/// Proves that $\log_G X = \log_H Y$ without revealing the discrete logarithm.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct DleqProof<E: Curve> {
+    /// Challenge $e$
+    pub challenge: Scalar<E>,
+    /// Response $z$
+    pub response: Scalar<E>,
+}
+
+/// Non-interactive threshold DKG
+///
+/// Holds public parameters of the protocol. All parties must use the same parameters.
+/// See [module level docs](self) for details.
+pub struct NonInteractiveKeygen<'a, E: Curve, D: Digest = crate::default_choice::Digest> {
+    execution_id: ExecutionId<'a>,
+    t: u16,
+    n: u16,
+    encryption_keys: &'a [EncryptionKey<E>],
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<'a, E: Curve> NonInteractiveKeygen<'a, E> {
+    /// Constructs non-interactive DKG
+    ///
+    /// Takes threshold $t$, and list of encryption keys of all parties. Number of parties $n$ is
+    /// determined by amount of encryption keys.
+    pub fn new(
+        eid: ExecutionId<'a>,
+        t: u16,
+        encryption_keys: &'a [EncryptionKey<E>],
+    ) -> Result<Self, NonInteractiveKeygenError> {
+        let n = u16::try_from(encryption_keys.len()).map_err(|_| Reason::InvalidParams)?;
+        if !(2 <= t && t <= n) {
+            return Err(Reason::InvalidParams.into());
+        }
+        Ok(Self {
+            execution_id: eid,
+            t,
+            n,
+            encryption_keys,
+            _digest: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<'a, E, D> NonInteractiveKeygen<'a, E, D>
+where
+    E: Curve,
+    D: Digest + Clone + 'static,
+{
+    /// Specifies another hash function to use
+    pub fn set_digest<D2>(self) -> NonInteractiveKeygen<'a, E, D2>
+    where
+        D2: Digest + Clone + 'static,
+    {
+        NonInteractiveKeygen {
+            execution_id: self.execution_id,
+            t: self.t,
+            n: self.n,
+            encryption_keys: self.encryption_keys,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    /// Number of parties $n$
+    pub fn n(&self) -> u16 {
+        self.n
+    }
+
+    /// Generates dealing of party $i$
+    pub fn deal<R>(&self, rng: &mut R, i: u16) -> Result<Dealing<E>, NonInteractiveKeygenError>
+    where
+        R: RngCore + CryptoRng,
+    {
+        if i >= self.n() {
+            return Err(Reason::InvalidParams.into());
+        }
+
+        let f = Polynomial::<SecretScalar<E>>::sample(rng, usize::from(self.t) - 1);
+        let F = &f * &Point::generator();
+
+        let encrypted_shares = (0..self.n())
+            .zip(self.encryption_keys)
+            .map(|(j, ek_j)| {
+                let mut share: Scalar<E> = f.value(&Scalar::from(j + 1));
+                let share = SecretScalar::new(&mut share);
+                self.encrypt_share(rng, i, j, &ek_j.0, &share)
+            })
+            .collect::<Vec<_>>();
+
+        let (sch_secret, sch_commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+        let challenge = self.dealing_challenge(i, &F, &sch_commit);
+        let f_0: Scalar<E> = f.value(&Scalar::zero());
+        let sch_proof = schnorr_pok::prove(&sch_secret, &challenge, f_0);
+
+        Ok(Dealing {
+            F,
+            sch_commit,
+            sch_proof,
+            encrypted_shares,
+        })
+    }
+
+    /// Verifies the dealing posted by `dealer`
+    ///
+    /// Checks that Feldman commitment has appropriate degree, Schnorr proof is valid, and
+    /// dealing contains a share for every party that matches Feldman commitment.
+    pub fn verify_dealing(
+        &self,
+        dealer: u16,
+        dealing: &Dealing<E>,
+    ) -> Result<(), NonInteractiveKeygenError> {
+        if dealer >= self.n() {
+            return Err(Reason::InvalidParams.into());
+        }
+        if dealing.F.degree() + 1 != usize::from(self.t)
+            || dealing.encrypted_shares.len() != usize::from(self.n())
+        {
+            return Err(Reason::InvalidDataSize { dealer }.into());
+        }
+
+        let challenge = self.dealing_challenge(dealer, &dealing.F, &dealing.sch_commit);
+        let F_0 = dealing.F.value::<_, Point<E>>(&Scalar::zero());
+        dealing
+            .sch_proof
+            .verify(&dealing.sch_commit, &challenge, &F_0)
+            .map_err(|_| Reason::InvalidSchnorrProof { dealer })?;
+
+        for ((j, ek_j), encrypted_share) in (0..)
+            .zip(self.encryption_keys)
+            .zip(&dealing.encrypted_shares)
+        {
+            let S_j = dealing.F.value::<_, Point<E>>(&Scalar::from(j + 1));
+            self.verify_encrypted_share(dealer, j, &ek_j.0, &S_j, encrypted_share)?;
+        }
+        Ok(())
+    }
+
+    /// Returns indexes of dealers that will be used to derive the key
+    ///
+    /// Dealer is qualified if it posted a valid dealing. `dealings[j]` must be a dealing
+    /// posted by party $j$, or `None` if party $j$ didn't post a dealing.
+    pub fn qualified_dealers(
+        &self,
+        dealings: &[Option<Dealing<E>>],
+    ) -> Result<Vec<u16>, NonInteractiveKeygenError> {
+        if dealings.len() != usize::from(self.n()) {
+            return Err(Reason::InvalidParams.into());
+        }
+        Ok((0..self.n())
+            .zip(dealings)
+            .filter_map(|(j, dealing)| Some((j, dealing.as_ref()?)))
+            .filter(|(j, dealing)| self.verify_dealing(*j, dealing).is_ok())
+            .map(|(j, _)| j)
+            .collect())
+    }
+
+    /// Finalizes key share of party $i$
+    ///
+    /// Takes all dealings posted on the bulletin board (see
+    /// [`qualified_dealers`](Self::qualified_dealers) for the format). All parties must
+    /// call this function with the same dealings.
+    ///
+    /// Returns error if there's less than $t$ qualified dealers. Otherwise, at least one
+    /// of them is honest, so the resulting key is not known to any coalition of less than
+    /// $t$ parties.
+    pub fn finalize(
+        &self,
+        i: u16,
+        decryption_key: &DecryptionKey<E>,
+        dealings: &[Option<Dealing<E>>],
+    ) -> Result<CoreKeyShare<E>, NonInteractiveKeygenError> {
+        let n = self.n();
+        if i >= n || decryption_key.encryption_key() != self.encryption_keys[usize::from(i)] {
+            return Err(Reason::InvalidParams.into());
+        }
+        let qualified = self.qualified_dealers(dealings)?;
+        if qualified.len() < usize::from(self.t) {
+            return Err(Reason::NotEnoughQualifiedDealers {
+                qualified: qualified.len(),
+            }
+            .into());
+        }
+        let qualified_dealings = qualified
+            .iter()
+            .map(|j| {
+                dealings[usize::from(*j)]
+                    .as_ref()
+                    .ok_or(Bug::DealingMissing)
+            })
+            .collect::<Result<Vec<_>, Bug>>()?;
+
+        let mut x = Scalar::<E>::zero();
+        for dealing in &qualified_dealings {
+            x += self
+                .decrypt_share(decryption_key, &dealing.encrypted_shares[usize::from(i)])
+                .ok_or(Bug::DecryptShare)?;
+        }
+        let x = NonZero::from_secret_scalar(SecretScalar::new(&mut x)).ok_or(Bug::ZeroShare)?;
+
+        let polynomial_sum = qualified_dealings
+            .iter()
+            .map(|dealing| &dealing.F)
+            .sum::<Polynomial<_>>();
+        let public_shares = (0..n)
+            .map(|j| polynomial_sum.value(&Scalar::from(j + 1)))
+            .map(|y_j: Point<E>| NonZero::from_point(y_j).ok_or(Bug::ZeroShare))
+            .collect::<Result<Vec<_>, _>>()?;
+        let shared_public_key =
+            NonZero::from_point(polynomial_sum.value(&Scalar::zero())).ok_or(Bug::ZeroPk)?;
+        let key_shares_indexes = (1..=n)
+            .map(|j| NonZero::from_scalar(Scalar::from(j)))
+            .collect::<Option<Vec<_>>>()
+            .ok_or(Bug::NonZeroScalar)?;
+
+        Ok(DirtyCoreKeyShare {
+            i,
+            key_info: DirtyKeyInfo {
+                curve: Default::default(),
+                shared_public_key,
+                public_shares,
+                vss_setup: Some(VssSetup {
+                    min_signers: self.t,
+                    I: key_shares_indexes,
+                }),
+                #[cfg(feature = "hd-wallets")]
+                chain_code: None,
+                epoch: 0,
+            },
+            x,
+        }
+        .validate()
+        .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?)
+    }
+
+    /// Encrypts share $s$ of party $j$ bit by bit, see [`EncryptedShare`]
+    fn encrypt_share<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        dealer: u16,
+        j: u16,
+        ek: &Point<E>,
+        share: &SecretScalar<E>,
+    ) -> EncryptedShare<E> {
+        let share_bits = share
+            .as_ref()
+            .to_be_bytes()
+            .iter()
+            .flat_map(|byte| (0..8).rev().map(move |k| (byte >> k) & 1 == 1))
+            .collect::<Vec<_>>();
+
+        // Randomness of all bits combined as $r = \sum_k 2^k r_k$
+        let mut r = Scalar::<E>::zero();
+        let bits = share_bits
+            .into_iter()
+            .enumerate()
+            .map(|(k, bit)| {
+                let r_k = SecretScalar::<E>::random(rng);
+                r = r + r + r_k.as_ref();
+
+                let R = Point::generator() * &r_k;
+                let mut C = ek * &r_k;
+                if bit {
+                    C += Point::generator().to_point();
+                }
+                let proof = self.prove_bit(rng, dealer, j, k, ek, bit, &r_k, &R, &C);
+                EncryptedBit { R, C, proof }
+            })
+            .collect::<Vec<_>>();
+
+        let r = SecretScalar::new(&mut r);
+        let X = Point::generator() * &r;
+        let Y = ek * &r;
+        let consistency_proof = self.prove_dleq(rng, dealer, j, &r, ek, &X, &Y);
+        EncryptedShare {
+            bits,
+            consistency_proof,
+        }
+    }
+
+    /// Verifies that `encrypted_share` of party $j$ matches the share $S = s \cdot G$
+    /// committed in Feldman commitment
+    fn verify_encrypted_share(
+        &self,
+        dealer: u16,
+        j: u16,
+        ek: &Point<E>,
+        S: &Point<E>,
+        encrypted_share: &EncryptedShare<E>,
+    ) -> Result<(), Reason> {
+        if encrypted_share.bits.len() != Scalar::<E>::serialized_len() * 8 {
+            return Err(Reason::InvalidDataSize { dealer });
+        }
+        let invalid = Reason::InvalidEncryptedShare {
+            dealer,
+            recipient: j,
+        };
+
+        let mut X = Point::<E>::zero();
+        let mut Y = Point::<E>::zero();
+        for (k, bit) in encrypted_share.bits.iter().enumerate() {
+            if self.verify_bit(dealer, j, k, ek, bit).is_err() {
+                return Err(invalid);
+            }
+            X = X + X + bit.R;
+            Y = Y + Y + bit.C;
+        }
+        let Y = Y - S;
+        self.verify_dleq(dealer, j, ek, &X, &Y, &encrypted_share.consistency_proof)
+            .map_err(|_| invalid)
+    }
+
+    /// Decrypts the share, returns `None` if any of bits doesn't decrypt to $0$ or $1$
+    fn decrypt_share(
+        &self,
+        decryption_key: &DecryptionKey<E>,
+        encrypted_share: &EncryptedShare<E>,
+    ) -> Option<Scalar<E>> {
+        let mut bytes = vec![0u8; Scalar::<E>::serialized_len()];
+        for (k, bit) in encrypted_share.bits.iter().enumerate() {
+            let M = bit.C - bit.R * &decryption_key.0;
+            if M == Point::generator().to_point() {
+                *bytes.get_mut(k / 8)? |= 0x80 >> (k % 8);
+            } else if !M.is_zero() {
+                return None;
+            }
+        }
+        let share = Scalar::from_be_bytes_mod_order(&bytes);
+        bytes.fill(0);
+        Some(share)
+    }
+
+    /// Derives challenge for schnorr proof of the dealer
+    fn dealing_challenge(
+        &self,
+        dealer: u16,
+        F: &Polynomial<Point<E>>,
+        sch_commit: &schnorr_pok::Commit<E>,
+    ) -> schnorr_pok::Challenge<E> {
+        let hash = |d: D| {
+            let mut d = d
+                .chain_update(b"dfns.cggmp21.keygen.non_interactive.dealing")
+                .chain_update(self.execution_id.as_bytes())
+                .chain_update(dealer.to_be_bytes());
+            for F_k in F.coefs() {
+                d.update(F_k.to_bytes(true));
+            }
+            d.chain_update(sch_commit.0.to_bytes(true)).finalize()
+        };
+        let mut rng = crate::rng::HashRng::new(hash);
+        schnorr_pok::Challenge {
+            nonce: Scalar::random(&mut rng),
+        }
+    }
+
+    /// Derives challenge for proof that $k$-th bit of share of party $j$ is $0$ or $1$
+    fn bit_challenge(
+        &self,
+        dealer: u16,
+        j: u16,
+        k: usize,
+        ek: &Point<E>,
+        R: &Point<E>,
+        C: &Point<E>,
+        commits: &[(Point<E>, Point<E>); 2],
+    ) -> Scalar<E> {
+        let hash = |d: D| {
+            let mut d = d
+                .chain_update(b"dfns.cggmp21.keygen.non_interactive.bit")
+                .chain_update(self.execution_id.as_bytes())
+                .chain_update(dealer.to_be_bytes())
+                .chain_update(j.to_be_bytes())
+                .chain_update((k as u64).to_be_bytes())
+                .chain_update(ek.to_bytes(true))
+                .chain_update(R.to_bytes(true))
+                .chain_update(C.to_bytes(true));
+            for (A, B) in commits {
+                d.update(A.to_bytes(true));
+                d.update(B.to_bytes(true));
+            }
+            d.finalize()
+        };
+        let mut rng = crate::rng::HashRng::new(hash);
+        Scalar::random(&mut rng)
+    }
+
+    /// Computes commitments of the bit proof for value $\beta$ from challenge $e$ and response $z$
+    fn bit_proof_commits(
+        ek: &Point<E>,
+        R: &Point<E>,
+        C: &Point<E>,
+        beta: usize,
+        e: &Scalar<E>,
+        z: &Scalar<E>,
+    ) -> (Point<E>, Point<E>) {
+        let C_beta = if beta == 1 {
+            C - Point::generator().to_point()
+        } else {
+            *C
+        };
+        (Point::generator() * z - R * e, ek * z - C_beta * e)
+    }
+
+    /// Proves that $(R, C)$ encrypts `bit`
+    fn prove_bit<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        dealer: u16,
+        j: u16,
+        k: usize,
+        ek: &Point<E>,
+        bit: bool,
+        r: &SecretScalar<E>,
+        R: &Point<E>,
+        C: &Point<E>,
+    ) -> BitProof<E> {
+        let b = usize::from(bit);
+        let other = 1 - b;
+        let mut challenges = [Scalar::zero(); 2];
+        let mut responses = [Scalar::zero(); 2];
+        let mut commits = [(Point::zero(), Point::zero()); 2];
+
+        // Proof for the other value is simulated
+        challenges[other] = Scalar::random(rng);
+        responses[other] = Scalar::random(rng);
+        commits[other] =
+            Self::bit_proof_commits(ek, R, C, other, &challenges[other], &responses[other]);
+
+        let w = SecretScalar::<E>::random(rng);
+        commits[b] = (Point::generator() * &w, ek * &w);
+        let e = self.bit_challenge(dealer, j, k, ek, R, C, &commits);
+        challenges[b] = e - challenges[other];
+        responses[b] = w.as_ref() + challenges[b] * r.as_ref();
+
+        BitProof {
+            challenges,
+            responses,
+        }
+    }
+
+    /// Verifies that $k$-th bit of share of party $j$ encrypts $0$ or $1$
+    fn verify_bit(
+        &self,
+        dealer: u16,
+        j: u16,
+        k: usize,
+        ek: &Point<E>,
+        bit: &EncryptedBit<E>,
+    ) -> Result<(), InvalidProof> {
+        let [e0, e1] = &bit.proof.challenges;
+        let [z0, z1] = &bit.proof.responses;
+        let commits = [
+            Self::bit_proof_commits(ek, &bit.R, &bit.C, 0, e0, z0),
+            Self::bit_proof_commits(ek, &bit.R, &bit.C, 1, e1, z1),
+        ];
+        if self.bit_challenge(dealer, j, k, ek, &bit.R, &bit.C, &commits) == e0 + e1 {
+            Ok(())
+        } else {
+            Err(InvalidProof)
+        }
+    }
+
+    /// Derives challenge for DLEQ proof related to share of party $j$
+    fn dleq_challenge(
+        &self,
+        dealer: u16,
+        j: u16,
+        H: &Point<E>,
+        X: &Point<E>,
+        Y: &Point<E>,
+        commits: (&Point<E>, &Point<E>),
+    ) -> Scalar<E> {
+        let hash = |d: D| {
+            d.chain_update(b"dfns.cggmp21.keygen.non_interactive.dleq")
+                .chain_update(self.execution_id.as_bytes())
+                .chain_update(dealer.to_be_bytes())
+                .chain_update(j.to_be_bytes())
+                .chain_update(H.to_bytes(true))
+                .chain_update(X.to_bytes(true))
+                .chain_update(Y.to_bytes(true))
+                .chain_update(commits.0.to_bytes(true))
+                .chain_update(commits.1.to_bytes(true))
+                .finalize()
+        };
+        let mut rng = crate::rng::HashRng::new(hash);
+        Scalar::random(&mut rng)
+    }
+
+    /// Proves that $\log_G X = \log_H Y = x$
+    fn prove_dleq<R: RngCore + CryptoRng>(
+        &self,
+        rng: &mut R,
+        dealer: u16,
+        j: u16,
+        x: &SecretScalar<E>,
+        H: &Point<E>,
+        X: &Point<E>,
+        Y: &Point<E>,
+    ) -> DleqProof<E> {
+        let k = SecretScalar::<E>::random(rng);
+        let A1 = Point::generator() * &k;
+        let A2 = H * &k;
+        let challenge = self.dleq_challenge(dealer, j, H, X, Y, (&A1, &A2));
+        let response = k.as_ref() + challenge * x.as_ref();
+        DleqProof {
+            challenge,
+            response,
+        }
+    }
+
+    /// Verifies that $\log_G X = \log_H Y$
+    fn verify_dleq(
+        &self,
+        dealer: u16,
+        j: u16,
+        H: &Point<E>,
+        X: &Point<E>,
+        Y: &Point<E>,
+        proof: &DleqProof<E>,
+    ) -> Result<(), InvalidProof> {
+        let A1 = Point::generator() * proof.response - X * proof.challenge;
+        let A2 = H * proof.response - Y * proof.challenge;
+        if self.dleq_challenge(dealer, j, H, X, Y, (&A1, &A2)) == proof.challenge {
+            Ok(())
+        } else {
+            Err(InvalidProof)
+        }
+    }
+}
+
+struct InvalidProof;
+
+/// Non-interactive keygen error
+#[derive(Debug, Error)]
+#[error("non-interactive keygen failed")]
+pub struct NonInteractiveKeygenError(#[source] Reason);
+
+crate::errors::impl_from! {
+    impl From for NonInteractiveKeygenError {
+        err: Reason => NonInteractiveKeygenError(err),
+        err: Bug => NonInteractiveKeygenError(Reason::Bug(err)),
+    }
+}
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("invalid parameters")]
+    InvalidParams,
+    #[error("dealing size is not suitable for threshold parameters (dealer: {dealer})")]
+    InvalidDataSize { dealer: u16 },
+    #[error("dealer {dealer} provided invalid schnorr proof")]
+    InvalidSchnorrProof { dealer: u16 },
+    #[error("dealer {dealer} provided invalid encrypted share of party {recipient}")]
+    InvalidEncryptedShare { dealer: u16, recipient: u16 },
+    #[error("not enough qualified dealers: {qualified} < t")]
+    NotEnoughQualifiedDealers { qualified: usize },
+    #[error("bug occurred")]
+    Bug(#[source] Bug),
+}
+
+#[derive(Debug, Error)]
+enum Bug {
+    #[error("qualified dealing is missing")]
+    DealingMissing,
+    #[error("encrypted share of qualified dealer can't be decrypted")]
+    DecryptShare,
+    #[error("resulting key share is not valid")]
+    InvalidKeyShare(#[source] InvalidCoreShare),
+    #[error("unexpected zero value")]
+    NonZeroScalar,
+    #[error("key share is zero - probability of that is negligible")]
+    ZeroShare,
+    #[error("shared public key is zero - probability of that is negligible")]
+    ZeroPk,
+}
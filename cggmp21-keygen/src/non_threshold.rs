@@ -10,6 +10,7 @@ use round_based::{
 use serde::{Deserialize, Serialize};
 
 use crate::progress::Tracer;
+use crate::transcript::{self, KeygenTranscript, TranscriptMsg};
 use crate::{
     errors::IoError,
     key_share::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, Validate},
@@ -96,8 +97,44 @@ enum Tag<'a> {
     },
 }
 
+impl<E: Curve, L: SecurityLevel> MsgRound2<E, L> {
+    /// Converts decommitment into canonical form to be recorded in the transcript
+    fn to_transcript_msg(&self) -> TranscriptMsg<E> {
+        #[cfg(feature = "hd-wallets")]
+        let chain_code = self.chain_code;
+        #[cfg(not(feature = "hd-wallets"))]
+        let chain_code = None;
+        transcript::decommitment(
+            self.rid.as_ref(),
+            vec![*self.X],
+            &self.sch_commit,
+            chain_code,
+            self.decommit.as_ref(),
+        )
+    }
+}
+
+/// Derives challenge for schnorr proof of $j$-th party
+fn schnorr_challenge<E: Curve, D: Digest>(
+    sid: &[u8],
+    j: u16,
+    rid: &[u8],
+) -> schnorr_pok::Challenge<E> {
+    let hash = |d: D| {
+        d.chain_update(sid)
+            .chain_update(j.to_be_bytes())
+            .chain_update(rid)
+            .finalize()
+    };
+    let mut rng = crate::rng::HashRng::new(hash);
+    schnorr_pok::Challenge {
+        nonce: Scalar::random(&mut rng),
+    }
+}
+
 pub async fn run_keygen<E, R, M, L, D>(
     mut tracer: Option<&mut dyn Tracer>,
+    mut transcript: Option<&mut KeygenTranscript<E>>,
     i: u16,
     n: u16,
     reliable_broadcast_enforced: bool,
@@ -105,6 +142,7 @@ pub async fn run_keygen<E, R, M, L, D>(
     rng: &mut R,
     party: M,
     #[cfg(feature = "hd-wallets")] hd_enabled: bool,
+    #[cfg(feature = "hd-wallets")] fixed_chain_code: Option<slip_10::ChainCode>,
 ) -> Result<CoreKeyShare<E>, KeygenError>
 where
     E: Curve,
@@ -114,6 +152,13 @@ where
     M: Mpc<ProtocolMessage = Msg<E, L, D>>,
 {
     tracer.protocol_begins();
+    if let Some(transcript) = transcript.as_deref_mut() {
+        transcript.begin(execution_id.as_bytes(), i, n, None);
+        #[cfg(feature = "hd-wallets")]
+        {
+            transcript.fixed_chain_code = fixed_chain_code.is_some();
+        }
+    }
 
     tracer.stage("Setup networking");
     let MpcParty { delivery, .. } = party.into_party();
@@ -147,7 +192,9 @@ where
     rng.fill_bytes(rid.as_mut());
 
     #[cfg(feature = "hd-wallets")]
-    let chain_code_local = if hd_enabled {
+    let chain_code_local = if let Some(chain_code) = fixed_chain_code {
+        Some(chain_code)
+    } else if hd_enabled {
         let mut chain_code = slip_10::ChainCode::default();
         rng.fill_bytes(&mut chain_code);
         Some(chain_code)
@@ -192,12 +239,24 @@ where
         .await
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
+    transcript::record_round::<Msg<E, L, D>, _, _>(
+        transcript.as_deref_mut(),
+        i,
+        &my_commitment,
+        &commitments,
+        |msg| TranscriptMsg::Commitment {
+            commitment: msg.commitment.to_vec(),
+        },
+    );
 
     // Optional reliability check
     if reliable_broadcast_enforced {
         tracer.stage("Hash received msgs (reliability check)");
         let h_i = udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
             .digest_iter(commitments.iter_including_me(&my_commitment));
+        if let Some(transcript) = transcript.as_deref_mut() {
+            transcript.reliability_check = Some(h_i.to_vec());
+        }
 
         tracer.send_msg();
         outgoings
@@ -216,6 +275,15 @@ where
             .await
             .map_err(IoError::receive_message)?;
         tracer.msgs_received();
+        transcript::record_round::<Msg<E, L, D>, _, _>(
+            transcript.as_deref_mut(),
+            i,
+            &MsgReliabilityCheck(h_i.clone()),
+            &round1_hashes,
+            |msg| TranscriptMsg::ReliabilityCheck {
+                hash: msg.0.to_vec(),
+            },
+        );
 
         tracer.stage("Assert other parties hashed messages (reliability check)");
         let parties_have_different_hashes = round1_hashes
@@ -244,6 +312,13 @@ where
         .await
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
+    transcript::record_round::<Msg<E, L, D>, _, _>(
+        transcript.as_deref_mut(),
+        i,
+        &my_decommitment,
+        &decommitments,
+        MsgRound2::to_transcript_msg,
+    );
 
     tracer.stage("Validate decommitments");
     let blame = utils::collect_blame(&commitments, &decommitments, |j, com, decom| {
@@ -255,7 +330,16 @@ where
     }
 
     #[cfg(feature = "hd-wallets")]
-    let chain_code = if hd_enabled {
+    let chain_code = if let Some(fixed_chain_code) = fixed_chain_code {
+        tracer.stage("Check chain_code");
+        let blame = utils::collect_simple_blame(&decommitments, |decom| {
+            decom.chain_code != Some(fixed_chain_code)
+        });
+        if !blame.is_empty() {
+            return Err(KeygenAborted::ChainCodeMismatch(blame).into());
+        }
+        Some(fixed_chain_code)
+    } else if hd_enabled {
         tracer.stage("Calculate chain_code");
         let blame = utils::collect_simple_blame(&decommitments, |decom| decom.chain_code.is_none());
         if !blame.is_empty() {
@@ -279,17 +363,7 @@ where
         .iter_including_me(&my_decommitment)
         .map(|d| &d.rid)
         .fold(L::Rid::default(), utils::xor_array);
-    let challenge = {
-        let hash = |d: D| {
-            d.chain_update(sid)
-                .chain_update(i.to_be_bytes())
-                .chain_update(rid.as_ref())
-                .finalize()
-        };
-        let mut rng = crate::rng::HashRng::new(hash);
-        Scalar::random(&mut rng)
-    };
-    let challenge = schnorr_pok::Challenge { nonce: challenge };
+    let challenge = schnorr_challenge::<E, D>(sid, i, rid.as_ref());
 
     tracer.stage("Prove knowledge of `x_i`");
     let sch_proof = schnorr_pok::prove(&sch_secret, &challenge, &x_i);
@@ -311,20 +385,19 @@ where
         .await
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
+    transcript::record_round::<Msg<E, L, D>, _, _>(
+        transcript,
+        i,
+        &my_sch_proof,
+        &sch_proofs,
+        |msg| TranscriptMsg::SchnorrProof {
+            sch_proof: msg.sch_proof.clone(),
+        },
+    );
 
     tracer.stage("Validate schnorr proofs");
     let blame = utils::collect_blame(&decommitments, &sch_proofs, |j, decom, sch_proof| {
-        let challenge = {
-            let hash = |d: D| {
-                d.chain_update(sid)
-                    .chain_update(j.to_be_bytes())
-                    .chain_update(rid.as_ref())
-                    .finalize()
-            };
-            let mut rng = crate::rng::HashRng::new(hash);
-            Scalar::random(&mut rng)
-        };
-        let challenge = schnorr_pok::Challenge { nonce: challenge };
+        let challenge = schnorr_challenge::<E, D>(sid, j, rid.as_ref());
         sch_proof
             .sch_proof
             .verify(&decom.sch_commit, &challenge, &decom.X)
@@ -354,9 +427,116 @@ where
             vss_setup: None,
             #[cfg(feature = "hd-wallets")]
             chain_code,
+            epoch: 0,
         },
         x: x_i,
     }
     .validate()
     .map_err(|e| Bug::InvalidKeyShare(e.into_error()))?)
 }
+
+/// Verifies transcript of non-threshold keygen, returns resulting key info
+pub(crate) fn verify_transcript<E, L, D>(
+    execution_id: ExecutionId<'_>,
+    i: u16,
+    n: u16,
+    reliability_check: Option<&[u8]>,
+    #[cfg_attr(not(feature = "hd-wallets"), allow(unused_variables))] fixed_chain_code: bool,
+    messages: &transcript::ByParty<'_, E>,
+) -> Result<DirtyKeyInfo<E>, transcript::Reason>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest + Clone + 'static,
+{
+    let sid = execution_id.as_bytes();
+    let tag = |j| {
+        udigest::Tag::<D>::new_structured(Tag::Indexed {
+            party_index: j,
+            sid,
+        })
+    };
+
+    let commitments = messages
+        .commitments::<D>()?
+        .into_iter()
+        .map(|commitment| MsgRound1::<D> { commitment })
+        .collect::<Vec<_>>();
+    transcript::verify_reliability_check(
+        reliability_check,
+        || {
+            udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
+                .digest_iter(&commitments)
+                .to_vec()
+        },
+        messages.reliability_checks(),
+    )?;
+
+    let decommitments = messages
+        .decommitments::<L>()?
+        .into_iter()
+        .zip(0..)
+        .map(|(decom, j)| {
+            let X = match decom.public_commitment {
+                [X] => NonZero::from_point(*X),
+                _ => None,
+            };
+            Ok(MsgRound2::<E, L> {
+                rid: decom.rid,
+                X: X.ok_or(transcript::Reason::MalformedMsg { party: j })?,
+                sch_commit: decom.sch_commit,
+                #[cfg(feature = "hd-wallets")]
+                chain_code: decom.chain_code,
+                decommit: decom.decommit,
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if decommitments.len() != usize::from(n) || usize::from(i) >= decommitments.len() {
+        return Err(transcript::Reason::InvalidParams);
+    }
+
+    let blame =
+        transcript::collect_blame(commitments.iter().zip(&decommitments), |j, (com, decom)| {
+            tag(j).digest(decom) != com.commitment
+        });
+    if !blame.is_empty() {
+        return Err(transcript::Reason::InvalidDecommitment { parties: blame });
+    }
+
+    #[cfg(feature = "hd-wallets")]
+    let chain_code = transcript::chain_code(
+        &decommitments[usize::from(i)].chain_code,
+        fixed_chain_code,
+        decommitments.iter().map(|decom| &decom.chain_code),
+    )?;
+
+    let rid = decommitments
+        .iter()
+        .map(|d| &d.rid)
+        .fold(L::Rid::default(), utils::xor_array);
+
+    let sch_proofs = messages.sch_proofs()?;
+    let blame = transcript::collect_blame(
+        decommitments.iter().zip(sch_proofs),
+        |j, (decom, sch_proof)| {
+            let challenge = schnorr_challenge::<E, D>(sid, j, rid.as_ref());
+            sch_proof
+                .verify(&decom.sch_commit, &challenge, &decom.X)
+                .is_err()
+        },
+    );
+    if !blame.is_empty() {
+        return Err(transcript::Reason::InvalidSchnorrProof { parties: blame });
+    }
+
+    Ok(DirtyKeyInfo {
+        curve: Default::default(),
+        shared_public_key: NonZero::from_point(decommitments.iter().map(|d| d.X).sum())
+            .ok_or(transcript::Reason::ZeroPoint)?,
+        public_shares: decommitments.iter().map(|d| d.X).collect(),
+        vss_setup: None,
+        #[cfg(feature = "hd-wallets")]
+        chain_code,
+        epoch: 0,
+    })
+}
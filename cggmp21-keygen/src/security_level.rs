@@ -3,7 +3,8 @@
 //! Security level is defined as set of parameters in the CGGMP paper. Higher security level gives more
 //! security but makes protocol execution slower.
 //!
-//! We provide a predefined default [SecurityLevel128].
+//! We provide a predefined default [SecurityLevel128], as well as higher security levels
+//! [SecurityLevel192] and [SecurityLevel256].
 //!
 //! You can define your own security level using macro [define_security_level]. Be sure that you properly
 //! analyzed the CGGMP paper and you understand implications. Inconsistent security level may cause unexpected
@@ -106,3 +107,21 @@ pub struct SecurityLevel128;
 define_security_level!(SecurityLevel128{
     security_bits = 384,
 });
+
+/// 192-bits security level
+///
+/// This security level is intended to provide 192 bits of security for the protocol when run with up to 128 participants.
+#[derive(Clone)]
+pub struct SecurityLevel192;
+define_security_level!(SecurityLevel192{
+    security_bits = 960,
+});
+
+/// 256-bits security level
+///
+/// This security level is intended to provide 256 bits of security for the protocol when run with up to 128 participants.
+#[derive(Clone)]
+pub struct SecurityLevel256;
+define_security_level!(SecurityLevel256{
+    security_bits = 2048,
+});
@@ -11,6 +11,7 @@ use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 
 use crate::progress::Tracer;
+use crate::transcript::{self, KeygenTranscript, TranscriptMsg};
 use crate::{
     errors::IoError,
     key_share::{CoreKeyShare, DirtyCoreKeyShare, DirtyKeyInfo, Validate, VssSetup},
@@ -106,8 +107,48 @@ enum Tag<'a> {
     },
 }
 
+impl<E: Curve, L: SecurityLevel> MsgRound2Broad<E, L> {
+    /// Converts decommitment into canonical form to be recorded in the transcript
+    fn to_transcript_msg(&self) -> TranscriptMsg<E> {
+        #[cfg(feature = "hd-wallets")]
+        let chain_code = self.chain_code;
+        #[cfg(not(feature = "hd-wallets"))]
+        let chain_code = None;
+        transcript::decommitment(
+            self.rid.as_ref(),
+            self.F.coefs().to_vec(),
+            &self.sch_commit,
+            chain_code,
+            self.decommit.as_ref(),
+        )
+    }
+}
+
+/// Derives challenge for schnorr proof of $j$-th party
+fn schnorr_challenge<E: Curve, D: Digest>(
+    sid: &[u8],
+    j: u16,
+    rid: &[u8],
+    y_j: &Point<E>,
+    h: &schnorr_pok::Commit<E>,
+) -> schnorr_pok::Challenge<E> {
+    let hash = |d: D| {
+        d.chain_update(sid)
+            .chain_update(j.to_be_bytes())
+            .chain_update(rid)
+            .chain_update(y_j.to_bytes(true)) // y_j
+            .chain_update(h.0.to_bytes(false)) // h
+            .finalize()
+    };
+    let mut rng = crate::rng::HashRng::new(hash);
+    schnorr_pok::Challenge {
+        nonce: Scalar::random(&mut rng),
+    }
+}
+
 pub async fn run_threshold_keygen<E, R, M, L, D>(
     mut tracer: Option<&mut dyn Tracer>,
+    mut transcript: Option<&mut KeygenTranscript<E>>,
     i: u16,
     t: u16,
     n: u16,
@@ -116,6 +157,7 @@ pub async fn run_threshold_keygen<E, R, M, L, D>(
     rng: &mut R,
     party: M,
     #[cfg(feature = "hd-wallets")] hd_enabled: bool,
+    #[cfg(feature = "hd-wallets")] fixed_chain_code: Option<slip_10::ChainCode>,
 ) -> Result<CoreKeyShare<E>, KeygenError>
 where
     E: Curve,
@@ -125,6 +167,13 @@ where
     M: Mpc<ProtocolMessage = Msg<E, L, D>>,
 {
     tracer.protocol_begins();
+    if let Some(transcript) = transcript.as_deref_mut() {
+        transcript.begin(execution_id.as_bytes(), i, n, Some(t));
+        #[cfg(feature = "hd-wallets")]
+        {
+            transcript.fixed_chain_code = fixed_chain_code.is_some();
+        }
+    }
 
     tracer.stage("Setup networking");
     let MpcParty { delivery, .. } = party.into_party();
@@ -168,7 +217,9 @@ where
     debug_assert_eq!(sigmas.len(), usize::from(n));
 
     #[cfg(feature = "hd-wallets")]
-    let chain_code_local = if hd_enabled {
+    let chain_code_local = if let Some(chain_code) = fixed_chain_code {
+        Some(chain_code)
+    } else if hd_enabled {
         let mut chain_code = slip_10::ChainCode::default();
         rng.fill_bytes(&mut chain_code);
         Some(chain_code)
@@ -210,12 +261,24 @@ where
         .await
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
+    transcript::record_round::<Msg<E, L, D>, _, _>(
+        transcript.as_deref_mut(),
+        i,
+        &my_commitment,
+        &commitments,
+        |msg| TranscriptMsg::Commitment {
+            commitment: msg.commitment.to_vec(),
+        },
+    );
 
     // Optional reliability check
     if reliable_broadcast_enforced {
         tracer.stage("Hash received msgs (reliability check)");
         let h_i = udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
             .digest_iter(commitments.iter_including_me(&my_commitment));
+        if let Some(transcript) = transcript.as_deref_mut() {
+            transcript.reliability_check = Some(h_i.to_vec());
+        }
 
         tracer.send_msg();
         outgoings
@@ -234,6 +297,15 @@ where
             .await
             .map_err(IoError::receive_message)?;
         tracer.msgs_received();
+        transcript::record_round::<Msg<E, L, D>, _, _>(
+            transcript.as_deref_mut(),
+            i,
+            &MsgReliabilityCheck(h_i.clone()),
+            &hashes,
+            |msg| TranscriptMsg::ReliabilityCheck {
+                hash: msg.0.to_vec(),
+            },
+        );
 
         tracer.stage("Assert other parties hashed messages (reliability check)");
         let parties_have_different_hashes = hashes
@@ -278,6 +350,13 @@ where
         .await
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
+    transcript::record_round::<Msg<E, L, D>, _, _>(
+        transcript.as_deref_mut(),
+        i,
+        &my_decommitment,
+        &decommitments,
+        MsgRound2Broad::to_transcript_msg,
+    );
 
     tracer.stage("Validate decommitments");
     let blame = utils::collect_blame(&commitments, &decommitments, |j, com, decom| {
@@ -317,7 +396,16 @@ where
         .map(|d| &d.rid)
         .fold(L::Rid::default(), utils::xor_array);
     #[cfg(feature = "hd-wallets")]
-    let chain_code = if hd_enabled {
+    let chain_code = if let Some(fixed_chain_code) = fixed_chain_code {
+        tracer.stage("Check chain_code");
+        let blame = utils::collect_simple_blame(&decommitments, |decom| {
+            decom.chain_code != Some(fixed_chain_code)
+        });
+        if !blame.is_empty() {
+            return Err(KeygenAborted::ChainCodeMismatch(blame).into());
+        }
+        Some(fixed_chain_code)
+    } else if hd_enabled {
         tracer.stage("Compute chain_code");
         let blame = utils::collect_simple_blame(&decommitments, |decom| decom.chain_code.is_none());
         if !blame.is_empty() {
@@ -351,19 +439,13 @@ where
     debug_assert_eq!(Point::generator() * &sigma, ys[usize::from(i)]);
 
     tracer.stage("Calculate challenge");
-    let challenge = {
-        let hash = |d: D| {
-            d.chain_update(sid)
-                .chain_update(i.to_be_bytes())
-                .chain_update(rid.as_ref())
-                .chain_update(&ys[usize::from(i)].to_bytes(true)) // y_i
-                .chain_update(&my_decommitment.sch_commit.0.to_bytes(false)) // h
-                .finalize()
-        };
-        let mut rng = crate::rng::HashRng::new(hash);
-        Scalar::random(&mut rng)
-    };
-    let challenge = schnorr_pok::Challenge { nonce: challenge };
+    let challenge = schnorr_challenge::<E, D>(
+        sid,
+        i,
+        rid.as_ref(),
+        &ys[usize::from(i)],
+        &my_decommitment.sch_commit,
+    );
 
     tracer.stage("Prove knowledge of `sigma_i`");
     let z = schnorr_pok::prove(&r, &challenge, &sigma);
@@ -385,22 +467,20 @@ where
         .await
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
+    transcript::record_round::<Msg<E, L, D>, _, _>(
+        transcript,
+        i,
+        &my_sch_proof,
+        &sch_proofs,
+        |msg| TranscriptMsg::SchnorrProof {
+            sch_proof: msg.sch_proof.clone(),
+        },
+    );
 
     tracer.stage("Validate schnorr proofs");
     let blame = utils::collect_blame(&decommitments, &sch_proofs, |j, decom, sch_proof| {
-        let challenge = {
-            let hash = |d: D| {
-                d.chain_update(sid)
-                    .chain_update(j.to_be_bytes())
-                    .chain_update(rid.as_ref())
-                    .chain_update(&ys[usize::from(j)].to_bytes(true)) // y_i
-                    .chain_update(&decom.sch_commit.0.to_bytes(false)) // h
-                    .finalize()
-            };
-            let mut rng = crate::rng::HashRng::new(hash);
-            Scalar::random(&mut rng)
-        };
-        let challenge = schnorr_pok::Challenge { nonce: challenge };
+        let challenge =
+            schnorr_challenge::<E, D>(sid, j, rid.as_ref(), &ys[usize::from(j)], &decom.sch_commit);
         sch_proof
             .sch_proof
             .verify(&decom.sch_commit, &challenge, &ys[usize::from(j)])
@@ -434,9 +514,133 @@ where
             }),
             #[cfg(feature = "hd-wallets")]
             chain_code,
+            epoch: 0,
         },
         x: sigma,
     }
     .validate()
     .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?)
 }
+
+/// Verifies transcript of threshold keygen, returns resulting key info
+pub(crate) fn verify_transcript<E, L, D>(
+    execution_id: ExecutionId<'_>,
+    i: u16,
+    t: u16,
+    n: u16,
+    reliability_check: Option<&[u8]>,
+    #[cfg_attr(not(feature = "hd-wallets"), allow(unused_variables))] fixed_chain_code: bool,
+    messages: &transcript::ByParty<'_, E>,
+) -> Result<DirtyKeyInfo<E>, transcript::Reason>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest + Clone + 'static,
+{
+    let sid = execution_id.as_bytes();
+    let tag = |j| {
+        udigest::Tag::<D>::new_structured(Tag::Indexed {
+            party_index: j,
+            sid,
+        })
+    };
+
+    let commitments = messages
+        .commitments::<D>()?
+        .into_iter()
+        .map(|commitment| MsgRound1::<D> { commitment })
+        .collect::<Vec<_>>();
+    transcript::verify_reliability_check(
+        reliability_check,
+        || {
+            udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
+                .digest_iter(&commitments)
+                .to_vec()
+        },
+        messages.reliability_checks(),
+    )?;
+
+    let decommitments = messages
+        .decommitments::<L>()?
+        .into_iter()
+        .map(|decom| MsgRound2Broad::<E, L> {
+            rid: decom.rid,
+            F: Polynomial::from_coefs(decom.public_commitment.to_vec()),
+            sch_commit: decom.sch_commit,
+            #[cfg(feature = "hd-wallets")]
+            chain_code: decom.chain_code,
+            decommit: decom.decommit,
+        })
+        .collect::<Vec<_>>();
+    if decommitments.len() != usize::from(n) || usize::from(i) >= decommitments.len() {
+        return Err(transcript::Reason::InvalidParams);
+    }
+
+    let blame =
+        transcript::collect_blame(commitments.iter().zip(&decommitments), |j, (com, decom)| {
+            tag(j).digest(decom) != com.commitment
+        });
+    if !blame.is_empty() {
+        return Err(transcript::Reason::InvalidDecommitment { parties: blame });
+    }
+
+    let blame =
+        transcript::collect_blame(&decommitments, |_, d| d.F.degree() + 1 != usize::from(t));
+    if !blame.is_empty() {
+        return Err(transcript::Reason::InvalidDataSize { parties: blame });
+    }
+
+    let rid = decommitments
+        .iter()
+        .map(|d| &d.rid)
+        .fold(L::Rid::default(), utils::xor_array);
+    #[cfg(feature = "hd-wallets")]
+    let chain_code = transcript::chain_code(
+        &decommitments[usize::from(i)].chain_code,
+        fixed_chain_code,
+        decommitments.iter().map(|decom| &decom.chain_code),
+    )?;
+
+    let polynomial_sum = decommitments.iter().map(|d| &d.F).sum::<Polynomial<_>>();
+    let ys = (0..n)
+        .map(|l| polynomial_sum.value(&Scalar::from(l + 1)))
+        .map(|y_j: Point<E>| NonZero::from_point(y_j).ok_or(transcript::Reason::ZeroPoint))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let sch_proofs = messages.sch_proofs()?;
+    let blame = transcript::collect_blame(
+        decommitments.iter().zip(sch_proofs),
+        |j, (decom, sch_proof)| {
+            let y_j = &ys[usize::from(j)];
+            let challenge = schnorr_challenge::<E, D>(sid, j, rid.as_ref(), y_j, &decom.sch_commit);
+            sch_proof
+                .verify(&decom.sch_commit, &challenge, y_j)
+                .is_err()
+        },
+    );
+    if !blame.is_empty() {
+        return Err(transcript::Reason::InvalidSchnorrProof { parties: blame });
+    }
+
+    let y: Point<E> = decommitments
+        .iter()
+        .map(|d| d.F.coefs().first().copied().unwrap_or(Point::zero()))
+        .sum();
+    let key_shares_indexes = (1..=n)
+        .map(|i| NonZero::from_scalar(Scalar::from(i)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(transcript::Reason::ZeroPoint)?;
+
+    Ok(DirtyKeyInfo {
+        curve: Default::default(),
+        shared_public_key: NonZero::from_point(y).ok_or(transcript::Reason::ZeroPoint)?,
+        public_shares: ys,
+        vss_setup: Some(VssSetup {
+            min_signers: t,
+            I: key_shares_indexes,
+        }),
+        #[cfg(feature = "hd-wallets")]
+        chain_code,
+        epoch: 0,
+    })
+}
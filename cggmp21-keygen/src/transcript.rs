@@ -0,0 +1,541 @@
+//! Keygen transcripts
+//!
+//! Transcript is an optional record of public messages that were observed by a party during
+//! key generation. It can be obtained by providing [`KeygenTranscript`] to
+//! [`GenericKeygenBuilder::set_transcript_recorder`](crate::GenericKeygenBuilder::set_transcript_recorder).
+//!
+//! Recorded transcript can be checked offline via [`verify_keygen_transcript`]: it re-checks all
+//! public proofs and confirms that resulting [`DirtyKeyInfo`] matches the messages. It can be used
+//! to attest that the key was generated correctly.
+//!
+//! Note that transcript contains only broadcast messages, i.e. secret shares sent over p2p channels
+//! in threshold DKG are never recorded.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+//! # type Msg = cggmp21_keygen::msg::non_threshold::Msg<E, cggmp21_keygen::security_level::SecurityLevel128, sha2::Sha256>;
+//! # let incoming = futures::stream::pending::<Result<round_based::Incoming<Msg>, std::convert::Infallible>>();
+//! # let outgoing = futures::sink::drain::<round_based::Outgoing<Msg>>();
+//! # let delivery = (incoming, outgoing);
+//! # let party = round_based::MpcParty::connected(delivery);
+//! #
+//! use cggmp21_keygen::{transcript::KeygenTranscript, ExecutionId};
+//! # use rand_core::OsRng;
+//! # type E = generic_ec::curves::Secp256k1;
+//!
+//! let eid = ExecutionId::new(b"execution id, unique per protocol execution");
+//! let mut transcript = KeygenTranscript::new();
+//!
+//! let key_share = cggmp21_keygen::keygen::<E>(eid, 0, 3)
+//!     .set_transcript_recorder(&mut transcript)
+//!     .start(&mut OsRng, party)
+//!     .await?;
+//!
+//! // Later, possibly by a different entity
+//! cggmp21_keygen::transcript::verify_keygen_transcript::<
+//!     E,
+//!     cggmp21_keygen::security_level::SecurityLevel128,
+//!     sha2::Sha256,
+//! >(&transcript, &key_share.key_info)?;
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+use generic_ec::{Curve, Point};
+use generic_ec_zkp::schnorr_pok;
+use round_based::rounds_router::{simple_store::RoundMsgs, RoundMessage};
+use round_based::{MsgId, PartyIndex};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use thiserror::Error;
+
+use crate::{key_share::DirtyKeyInfo, security_level::SecurityLevel, ExecutionId};
+
+/// Transcript of keygen protocol execution
+///
+/// Transcript is filled in by the protocol when it's set via
+/// [`GenericKeygenBuilder::set_transcript_recorder`](crate::GenericKeygenBuilder::set_transcript_recorder).
+/// All the previously recorded data is erased when protocol starts.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct KeygenTranscript<E: Curve> {
+    /// Execution ID
+    #[serde(with = "hex::serde")]
+    pub eid: Vec<u8>,
+    /// Index of party that recorded the transcript
+    pub i: u16,
+    /// Number of parties participated in keygen
+    pub n: u16,
+    /// Threshold value, `None` if non-threshold DKG was carried out
+    pub t: Option<u16>,
+    /// Hash of all round 1 messages that was sent in `MsgReliabilityCheck`
+    ///
+    /// `None` if [reliable broadcast](crate::GenericKeygenBuilder::enforce_reliable_broadcast) was
+    /// not enforced.
+    #[serde_as(as = "Option<serde_with::hex::Hex>")]
+    pub reliability_check: Option<Vec<u8>>,
+    /// Indicates that all parties were expected to contribute the same fixed chain code
+    ///
+    /// See [`GenericKeygenBuilder::set_chain_code`](crate::GenericKeygenBuilder::set_chain_code)
+    #[serde(default)]
+    pub fixed_chain_code: bool,
+    /// Recorded broadcast messages
+    ///
+    /// Messages are ordered in the same way as they were processed by the protocol. Messages
+    /// sent by the party itself are included as well.
+    pub messages: Vec<RecordedMsg<E>>,
+}
+
+/// Broadcast message recorded in the transcript
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct RecordedMsg<E: Curve> {
+    /// Index of party who sent the message
+    pub sender: PartyIndex,
+    /// Index of the round the message belongs to
+    ///
+    /// Round index corresponds to
+    /// [`ProtocolMessage::round`](round_based::ProtocolMessage::round) of the message.
+    pub round: u16,
+    /// ID of received message, `None` for messages sent by the party itself
+    pub msg_id: Option<MsgId>,
+    /// Message in canonical form
+    pub msg: TranscriptMsg<E>,
+}
+
+/// Canonical form of a broadcast message
+///
+/// All messages are curve-generic and do not depend on choice of digest or security level,
+/// so transcript can be serialized and processed without knowing exact protocol parameters.
+#[serde_as]
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub enum TranscriptMsg<E: Curve> {
+    /// Commitment from round 1
+    Commitment {
+        /// $V_j$
+        #[serde(with = "hex::serde")]
+        commitment: Vec<u8>,
+    },
+    /// Hash of round 1 messages received by the party (reliability check)
+    ReliabilityCheck {
+        /// $h_j$
+        #[serde(with = "hex::serde")]
+        hash: Vec<u8>,
+    },
+    /// Decommitment from round 2
+    Decommitment {
+        /// `rid_j`
+        #[serde(with = "hex::serde")]
+        rid: Vec<u8>,
+        /// Commitment to secret: coefficients of $F_j$ in threshold DKG, or a single $X_j$
+        /// in non-threshold DKG
+        public_commitment: Vec<Point<E>>,
+        /// $A_j$
+        sch_commit: Point<E>,
+        /// Party contribution to chain code
+        #[serde_as(as = "Option<serde_with::hex::Hex>")]
+        chain_code: Option<[u8; 32]>,
+        /// $u_j$
+        #[serde(with = "hex::serde")]
+        decommit: Vec<u8>,
+    },
+    /// Schnorr proof from round 3
+    SchnorrProof {
+        /// $\psi_j$
+        sch_proof: schnorr_pok::Proof<E>,
+    },
+}
+
+impl<E: Curve> Default for KeygenTranscript<E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<E: Curve> KeygenTranscript<E> {
+    /// Constructs an empty transcript
+    pub fn new() -> Self {
+        Self {
+            eid: Vec::new(),
+            i: 0,
+            n: 0,
+            t: None,
+            reliability_check: None,
+            fixed_chain_code: false,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Resets transcript and sets protocol parameters
+    pub(crate) fn begin(&mut self, eid: &[u8], i: u16, n: u16, t: Option<u16>) {
+        *self = Self {
+            eid: eid.to_vec(),
+            i,
+            n,
+            t,
+            reliability_check: None,
+            fixed_chain_code: false,
+            messages: Vec::new(),
+        }
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        sender: PartyIndex,
+        round: u16,
+        msg_id: Option<MsgId>,
+        msg: TranscriptMsg<E>,
+    ) {
+        self.messages.push(RecordedMsg {
+            sender,
+            round,
+            msg_id,
+            msg,
+        })
+    }
+
+    /// Returns commitment, decommitment and schnorr proof sent by each party
+    ///
+    /// Also returns reliability check hashes if they are present.
+    fn messages_by_party(&self) -> Result<ByParty<'_, E>, Reason> {
+        let n = usize::from(self.n);
+        let mut by_party = ByParty {
+            commitments: vec![None; n],
+            reliability_checks: vec![None; n],
+            decommitments: vec![None; n],
+            sch_proofs: vec![None; n],
+        };
+        for recorded in &self.messages {
+            let j = recorded.sender;
+            let (list, kind) = match &recorded.msg {
+                TranscriptMsg::Commitment { .. } => (&mut by_party.commitments, "commitment"),
+                TranscriptMsg::ReliabilityCheck { .. } => {
+                    (&mut by_party.reliability_checks, "reliability check")
+                }
+                TranscriptMsg::Decommitment { .. } => (&mut by_party.decommitments, "decommitment"),
+                TranscriptMsg::SchnorrProof { .. } => (&mut by_party.sch_proofs, "schnorr proof"),
+            };
+            let slot = list
+                .get_mut(usize::from(j))
+                .ok_or(Reason::UnknownSender { party: j })?;
+            if slot.is_some() {
+                return Err(Reason::DuplicatedMsg { party: j, kind });
+            }
+            *slot = Some(&recorded.msg);
+        }
+        Ok(by_party)
+    }
+}
+
+/// Transcript messages grouped by sender
+pub(crate) struct ByParty<'t, E: Curve> {
+    pub commitments: Vec<Option<&'t TranscriptMsg<E>>>,
+    pub reliability_checks: Vec<Option<&'t TranscriptMsg<E>>>,
+    pub decommitments: Vec<Option<&'t TranscriptMsg<E>>>,
+    pub sch_proofs: Vec<Option<&'t TranscriptMsg<E>>>,
+}
+
+/// Decommitment extracted from the transcript
+pub(crate) struct Decommitment<'t, E: Curve, L: SecurityLevel> {
+    pub rid: L::Rid,
+    pub public_commitment: &'t [Point<E>],
+    pub sch_commit: schnorr_pok::Commit<E>,
+    #[cfg_attr(not(feature = "hd-wallets"), allow(dead_code))]
+    pub chain_code: Option<[u8; 32]>,
+    pub decommit: L::Rid,
+}
+
+impl<'t, E: Curve> ByParty<'t, E> {
+    pub fn commitments<D: Digest>(&self) -> Result<Vec<digest::Output<D>>, Reason> {
+        self.commitments
+            .iter()
+            .zip(0..)
+            .map(|(msg, j)| match msg {
+                Some(TranscriptMsg::Commitment { commitment }) => {
+                    parse_bytes::<digest::Output<D>>(commitment)
+                        .ok_or(Reason::MalformedMsg { party: j })
+                }
+                _ => Err(Reason::MissingMsg {
+                    party: j,
+                    kind: "commitment",
+                }),
+            })
+            .collect()
+    }
+
+    pub fn reliability_checks(&self) -> impl Iterator<Item = (u16, &'t [u8])> + '_ {
+        self.reliability_checks
+            .iter()
+            .zip(0..)
+            .filter_map(|(msg, j)| match msg {
+                Some(TranscriptMsg::ReliabilityCheck { hash }) => Some((j, hash.as_slice())),
+                _ => None,
+            })
+    }
+
+    pub fn decommitments<L: SecurityLevel>(&self) -> Result<Vec<Decommitment<'t, E, L>>, Reason> {
+        self.decommitments
+            .iter()
+            .zip(0..)
+            .map(|(msg, j)| match msg {
+                Some(TranscriptMsg::Decommitment {
+                    rid,
+                    public_commitment,
+                    sch_commit,
+                    chain_code,
+                    decommit,
+                }) => Ok(Decommitment {
+                    rid: parse_bytes(rid).ok_or(Reason::MalformedMsg { party: j })?,
+                    public_commitment,
+                    sch_commit: schnorr_pok::Commit(*sch_commit),
+                    chain_code: *chain_code,
+                    decommit: parse_bytes(decommit).ok_or(Reason::MalformedMsg { party: j })?,
+                }),
+                _ => Err(Reason::MissingMsg {
+                    party: j,
+                    kind: "decommitment",
+                }),
+            })
+            .collect()
+    }
+
+    pub fn sch_proofs(&self) -> Result<Vec<&'t schnorr_pok::Proof<E>>, Reason> {
+        self.sch_proofs
+            .iter()
+            .zip(0..)
+            .map(|(msg, j)| match msg {
+                Some(TranscriptMsg::SchnorrProof { sch_proof }) => Ok(sch_proof),
+                _ => Err(Reason::MissingMsg {
+                    party: j,
+                    kind: "schnorr proof",
+                }),
+            })
+            .collect()
+    }
+}
+
+/// Parses fixed-size byte array
+fn parse_bytes<T: Default + AsMut<[u8]>>(bytes: &[u8]) -> Option<T> {
+    let mut out = T::default();
+    if out.as_mut().len() != bytes.len() {
+        return None;
+    }
+    out.as_mut().copy_from_slice(bytes);
+    Some(out)
+}
+
+/// Verifies keygen transcript
+///
+/// Re-checks all public proofs recorded in the transcript, and makes sure that `key_info`
+/// is exactly the one that's produced by the keygen. `L` and `D` must match the security level and
+/// digest that were used in the protocol.
+pub fn verify_keygen_transcript<E, L, D>(
+    transcript: &KeygenTranscript<E>,
+    key_info: &DirtyKeyInfo<E>,
+) -> Result<(), InvalidTranscript>
+where
+    E: Curve,
+    L: SecurityLevel,
+    D: Digest + Clone + 'static,
+{
+    if transcript.n < 2 || transcript.i >= transcript.n {
+        return Err(Reason::InvalidParams.into());
+    }
+    let eid = ExecutionId::new(&transcript.eid);
+    let messages = transcript.messages_by_party()?;
+
+    let expected_key_info = match transcript.t {
+        Some(t) => crate::threshold::verify_transcript::<E, L, D>(
+            eid,
+            transcript.i,
+            t,
+            transcript.n,
+            transcript.reliability_check.as_deref(),
+            transcript.fixed_chain_code,
+            &messages,
+        )?,
+        None => crate::non_threshold::verify_transcript::<E, L, D>(
+            eid,
+            transcript.i,
+            transcript.n,
+            transcript.reliability_check.as_deref(),
+            transcript.fixed_chain_code,
+            &messages,
+        )?,
+    };
+
+    let key_info_matches = expected_key_info.shared_public_key == key_info.shared_public_key
+        && expected_key_info.public_shares == key_info.public_shares
+        && expected_key_info.vss_setup == key_info.vss_setup;
+    #[cfg(feature = "hd-wallets")]
+    let key_info_matches = key_info_matches && expected_key_info.chain_code == key_info.chain_code;
+    if !key_info_matches {
+        return Err(Reason::KeyInfoMismatch.into());
+    }
+
+    Ok(())
+}
+
+/// Computes resulting chain code from parties contributions
+///
+/// If chain code is fixed, all contributions must be equal to each other. Otherwise, chain code
+/// is XOR of all contributions. Chain code is not computed if local party didn't contribute it.
+#[cfg(feature = "hd-wallets")]
+pub(crate) fn chain_code<'a>(
+    local_contribution: &Option<[u8; 32]>,
+    fixed: bool,
+    contributions: impl Iterator<Item = &'a Option<[u8; 32]>> + Clone,
+) -> Result<Option<[u8; 32]>, Reason> {
+    let Some(local_contribution) = local_contribution else {
+        return Ok(None);
+    };
+    if fixed {
+        let blame = collect_blame(contributions, |_, chain_code| {
+            chain_code.as_ref() != Some(local_contribution)
+        });
+        if !blame.is_empty() {
+            return Err(Reason::ChainCodeMismatch { parties: blame });
+        }
+        return Ok(Some(*local_contribution));
+    }
+
+    let blame = collect_blame(contributions.clone(), |_, chain_code| chain_code.is_none());
+    if !blame.is_empty() {
+        return Err(Reason::MissingChainCode { parties: blame });
+    }
+    Ok(Some(
+        contributions
+            .flatten()
+            .fold([0u8; 32], crate::utils::xor_array),
+    ))
+}
+
+/// Checks that reliability check hash matches the commitments and all the parties
+/// received the same commitments
+pub(crate) fn verify_reliability_check(
+    h: Option<&[u8]>,
+    expected_h: impl FnOnce() -> Vec<u8>,
+    reliability_checks: impl Iterator<Item = (u16, impl AsRef<[u8]>)>,
+) -> Result<(), Reason> {
+    let Some(h) = h else {
+        return Ok(());
+    };
+    if h != expected_h() {
+        return Err(Reason::ReliabilityCheckMismatch {
+            parties: Vec::new(),
+        });
+    }
+    let blame = reliability_checks
+        .filter(|(_j, h_j)| h_j.as_ref() != h)
+        .map(|(j, _)| j)
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(Reason::ReliabilityCheckMismatch { parties: blame });
+    }
+    Ok(())
+}
+
+/// Transcript is not valid
+#[derive(Debug, Error)]
+#[error("keygen transcript is not valid")]
+pub struct InvalidTranscript(#[source] Reason);
+
+#[derive(Debug, Error)]
+pub(crate) enum Reason {
+    #[error("invalid transcript parameters")]
+    InvalidParams,
+    #[error("message from unknown party {party}")]
+    UnknownSender { party: u16 },
+    #[error("party {party} sent more than one {kind}")]
+    DuplicatedMsg { party: u16, kind: &'static str },
+    #[error("{kind} from party {party} is missing")]
+    MissingMsg { party: u16, kind: &'static str },
+    #[error("message from party {party} is malformed")]
+    MalformedMsg { party: u16 },
+    #[error("reliability check hash doesn't match (blame: {parties:?})")]
+    ReliabilityCheckMismatch { parties: Vec<u16> },
+    #[error("party decommitment doesn't match commitment: {parties:?}")]
+    InvalidDecommitment { parties: Vec<u16> },
+    #[error("party data size is not suitable for threshold parameters: {parties:?}")]
+    InvalidDataSize { parties: Vec<u16> },
+    #[error("party provided invalid schnorr proof: {parties:?}")]
+    InvalidSchnorrProof { parties: Vec<u16> },
+    #[cfg(feature = "hd-wallets")]
+    #[error("party did not generate chain code: {parties:?}")]
+    MissingChainCode { parties: Vec<u16> },
+    #[cfg(feature = "hd-wallets")]
+    #[error("party contributed chain code that doesn't match the fixed one: {parties:?}")]
+    ChainCodeMismatch { parties: Vec<u16> },
+    #[error("resulting public key or public shares are zero")]
+    ZeroPoint,
+    #[error("key info doesn't match the transcript")]
+    KeyInfoMismatch,
+}
+
+impl From<Reason> for InvalidTranscript {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
+
+/// Converts decommitment into canonical form
+pub(crate) fn decommitment<E: Curve>(
+    rid: &[u8],
+    public_commitment: Vec<Point<E>>,
+    sch_commit: &schnorr_pok::Commit<E>,
+    chain_code: Option<[u8; 32]>,
+    decommit: &[u8],
+) -> TranscriptMsg<E> {
+    TranscriptMsg::Decommitment {
+        rid: rid.to_vec(),
+        public_commitment,
+        sch_commit: sch_commit.0,
+        chain_code,
+        decommit: decommit.to_vec(),
+    }
+}
+
+/// Collects indexes of parties for which `filter` returns `true`
+pub(crate) fn collect_blame<T>(
+    items: impl IntoIterator<Item = T>,
+    mut filter: impl FnMut(u16, T) -> bool,
+) -> Vec<u16> {
+    items
+        .into_iter()
+        .zip(0..)
+        .filter_map(|(item, j)| filter(j, item).then_some(j))
+        .collect()
+}
+
+/// Records messages received in the round along with the message sent by the party itself
+///
+/// `P` is the protocol message, it's used to determine index of the round
+pub(crate) fn record_round<P, M, E>(
+    transcript: Option<&mut KeygenTranscript<E>>,
+    i: u16,
+    my_msg: &M,
+    msgs: &RoundMsgs<M>,
+    to_canonical: impl Fn(&M) -> TranscriptMsg<E>,
+) where
+    P: RoundMessage<M>,
+    E: Curve,
+{
+    let Some(transcript) = transcript else {
+        return;
+    };
+    let mut my_msg = Some(my_msg);
+    for (j, msg_id, msg) in msgs.iter_indexed() {
+        if j > i {
+            if let Some(my_msg) = my_msg.take() {
+                transcript.record(i, P::ROUND, None, to_canonical(my_msg));
+            }
+        }
+        transcript.record(j, P::ROUND, Some(msg_id), to_canonical(msg));
+    }
+    if let Some(my_msg) = my_msg {
+        transcript.record(i, P::ROUND, None, to_canonical(my_msg));
+    }
+}
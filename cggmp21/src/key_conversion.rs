@@ -0,0 +1,573 @@
+//! Conversion between threshold and non-threshold key shares
+//!
+//! Key share is either additive n-out-of-n (its [`vss_setup`](DirtyKeyInfo::vss_setup) is `None`),
+//! or Shamir t-out-of-n (`vss_setup` is `Some(_)`). This module converts one form into another:
+//!
+//! * [`to_additive`] converts threshold key shares of a fixed group of signers into additive key
+//!   shares of that group. It's done locally, without any interaction. Signing with additive key shares
+//!   is slightly cheaper, as signers don't need to compute lagrange coefficients for every signature.
+//! * [`to_threshold`](crate::to_threshold) is an interactive protocol that converts additive n-out-of-n key
+//!   shares into Shamir n-out-of-n key shares, so that the key can be used with features that require
+//!   threshold key shares, like [share recovery](crate::share_recovery).
+//!
+//! Conversion doesn't change the shared secret key, public key, and chain code. Converted key share
+//! is [`IncompleteKeyShare`]. To complete it, aux info needs to be generated via
+//! [`aux_info_gen`](crate::aux_info_gen). After [`to_threshold`](crate::to_threshold), aux info of the
+//! original key share can be reused as is, since the set of signers and their indexes don't change.
+//!
+//! ## Threshold to additive
+//! Let $S$ be the group of signers, $|S| \ge t$. Signer $j \in S$ outputs additive share
+//! $x'_j = \lambda_j x_j$, where $\lambda_j$ is the lagrange coefficient that interpolates the
+//! polynomial at zero over indexes of $S$. Public shares are converted in the same way. Index of
+//! the signer in the resulting key share is its position in $S$.
+//!
+//! ## Additive to threshold
+//! 1. Every party $i$ samples a random polynomial $f_i(x)$ of degree $n-1$ with $f_i(0) = x_i$,
+//!    broadcasts commitment $F_i(x) = f_i(x) \cdot G$, and sends $\sigma_{i \to j} = f_i(I_j)$ to
+//!    every party $j$, where $I_j = j + 1$. Along with that, every party broadcasts a hash of key info
+//!    it holds.
+//! 2. Every party $j$ checks that $F_i(0) = X_i$ and $\sigma_{i \to j} \cdot G = F_i(I_j)$, and outputs
+//!    Shamir share $x'_j = \sum_i \sigma_{i \to j}$. Public share of party $k$ is $X'_k = \sum_i F_i(I_k)$.
+//!
+//! Protocol requires broadcast messages of step 1 to be sent over a reliable broadcast channel. By
+//! default, reliability is ensured by an extra round, in which parties exchange a hash of all
+//! broadcast messages they received and abort if hashes don't match, see
+//! [`ToThresholdBuilder::enforce_reliable_broadcast`].
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+//! # type Msg = cggmp21::key_conversion::msg::Msg<cggmp21::supported_curves::Secp256k1, sha2::Sha256>;
+//! # let incoming = futures::stream::pending::<Result<round_based::Incoming<Msg>, std::convert::Infallible>>();
+//! # let outgoing = futures::sink::drain::<round_based::Outgoing<Msg>>();
+//! # let delivery = (incoming, outgoing);
+//! # let party = round_based::MpcParty::connected(delivery);
+//! #
+//! # use cggmp21::supported_curves::Secp256k1;
+//! # let additive_key_share: cggmp21::IncompleteKeyShare<Secp256k1> = unimplemented!();
+//! # let mut rng = rand_core::OsRng;
+//! use cggmp21::key_conversion;
+//!
+//! // Every party converts its additive key share into threshold one
+//! let eid = cggmp21::ExecutionId::new(b"execution id, unique per protocol execution");
+//! let threshold_key_share = cggmp21::to_threshold(eid)
+//!     .start(&mut rng, party, &additive_key_share)
+//!     .await?;
+//!
+//! // Later on, signers 0, 2, 3 convert their key shares into additive
+//! // shares of 3-out-of-3 key
+//! let additive_key_share = key_conversion::to_additive(&threshold_key_share, &[0, 2, 3])?;
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+use futures::SinkExt;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::polynomial::{lagrange_coefficient, Polynomial};
+use rand_core::{CryptoRng, RngCore};
+use round_based::{
+    rounds_router::{simple_store::RoundInput, RoundsRouter},
+    Delivery, Mpc, MpcParty, Outgoing,
+};
+use thiserror::Error;
+
+use crate::{
+    errors::IoError,
+    key_share::{
+        AnyKeyShare, DirtyIncompleteKeyShare, DirtyKeyInfo, IncompleteKeyShare,
+        InvalidIncompleteKeyShare, Validate, VssSetup,
+    },
+    progress::Tracer,
+    share_recovery::KeyInfoDigest,
+    utils::AbortBlame,
+    ExecutionId,
+};
+
+use self::msg::*;
+
+/// Message of additive to threshold conversion protocol
+pub mod msg {
+    use digest::Digest;
+    use generic_ec::{Curve, Point, Scalar};
+    use round_based::ProtocolMessage;
+    use serde::{Deserialize, Serialize};
+
+    /// Message of additive to threshold conversion protocol
+    #[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
+    #[serde(bound = "")]
+    pub enum Msg<E: Curve, D: Digest> {
+        /// Round 1a message
+        Round1Broad(MsgRound1Broad<E, D>),
+        /// Round 1b message
+        Round1Uni(MsgRound1Uni<E>),
+        /// Reliability check message (optional additional round)
+        ReliabilityCheck(MsgReliabilityCheck<D>),
+    }
+
+    /// Message from round 1a, broadcast by every party
+    #[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+    #[udigest(tag = "dfns.cggmp21.key_conversion.round1")]
+    #[udigest(bound = "")]
+    #[serde(bound = "")]
+    pub struct MsgRound1Broad<E: Curve, D: Digest> {
+        /// Hash of key info held by the party
+        #[udigest(as_bytes)]
+        pub key_info_hash: digest::Output<D>,
+        /// $F_i$
+        ///
+        /// Commitment to coefficients of the polynomial $f_i$
+        pub commitment: Vec<Point<E>>,
+    }
+
+    /// Message from round 1b, sent by every party to each other party
+    #[derive(Clone, Serialize, Deserialize)]
+    #[serde(bound = "")]
+    pub struct MsgRound1Uni<E: Curve> {
+        /// $\sigma_{i \to j}$
+        pub sigma: Scalar<E>,
+    }
+
+    /// Message from an optional round that enforces reliability check
+    #[derive(Clone, Serialize, Deserialize)]
+    #[serde(bound = "")]
+    pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+}
+
+/// Tag used to hash key info
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.key_conversion.tag")]
+struct Tag<'a> {
+    #[udigest(as_bytes)]
+    sid: &'a [u8],
+}
+
+/// Converts threshold key share into additive key share of a fixed group of signers
+///
+/// `signers` are indexes (at keygen) of the signers in the group, it must contain at least
+/// [`min_signers`](AnyKeyShare::min_signers) distinct signers including the owner of the `key_share`.
+/// All signers of the group must call this function with the same list of signers.
+///
+/// Resulting key share is `|signers|`-out-of-`|signers|` additive key share. Index of the signer
+/// in it corresponds to the position of the signer in `signers` list.
+pub fn to_additive<E: Curve>(
+    key_share: &impl AnyKeyShare<E>,
+    signers: &[u16],
+) -> Result<IncompleteKeyShare<E>, KeyConversionError> {
+    let key_share: &IncompleteKeyShare<E> = key_share.as_ref();
+    let vss_setup = key_share
+        .vss_setup
+        .as_ref()
+        .ok_or(InvalidArgs::NonThresholdKey)?;
+    if signers.len() < usize::from(vss_setup.min_signers) {
+        return Err(InvalidArgs::TooFewSigners.into());
+    }
+    for (k, j) in signers.iter().enumerate() {
+        if signers[..k].contains(j) {
+            return Err(InvalidArgs::DuplicatedSigner.into());
+        }
+    }
+    let i = signers
+        .iter()
+        .position(|&j| j == key_share.i)
+        .ok_or(InvalidArgs::NotASigner)?;
+    let I = crate::utils::subset(signers, &vss_setup.I).ok_or(InvalidArgs::SignerOutOfBounds)?;
+    let public_shares = crate::utils::subset(signers, &key_share.public_shares)
+        .ok_or(InvalidArgs::SignerOutOfBounds)?;
+
+    let lambda = (0..I.len())
+        .map(|j| lagrange_coefficient(Scalar::zero(), j, &I))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Bug::LagrangeCoef)?;
+    let x = NonZero::from_secret_scalar(SecretScalar::new(
+        &mut (lambda[i] * &key_share.x).into_inner(),
+    ))
+    .ok_or(Bug::ZeroShare)?;
+    let public_shares = lambda
+        .iter()
+        .zip(&public_shares)
+        .map(|(lambda_j, X_j)| *lambda_j * X_j)
+        .collect();
+
+    Ok(DirtyIncompleteKeyShare {
+        i: i.try_into().map_err(|_| InvalidArgs::TooManySigners)?,
+        key_info: DirtyKeyInfo {
+            public_shares,
+            vss_setup: None,
+            ..key_share.key_info.clone()
+        },
+        x,
+    }
+    .validate()
+    .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?)
+}
+
+/// Additive to threshold conversion protocol builder
+///
+/// Build it via [`cggmp21::to_threshold`](crate::to_threshold()), and start the protocol
+/// via [`start`](Self::start).
+pub struct ToThresholdBuilder<'a, D = crate::default_choice::Digest>
+where
+    D: Digest,
+{
+    execution_id: ExecutionId<'a>,
+    tracer: Option<&'a mut dyn Tracer>,
+    enforce_reliable_broadcast: bool,
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<'a, D> ToThresholdBuilder<'a, D>
+where
+    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+{
+    /// Constructs a builder
+    pub fn new(eid: ExecutionId<'a>) -> Self {
+        Self {
+            execution_id: eid,
+            tracer: None,
+            enforce_reliable_broadcast: true,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    /// Specifies another hash function to use
+    pub fn set_digest<D2>(self) -> ToThresholdBuilder<'a, D2>
+    where
+        D2: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    {
+        ToThresholdBuilder {
+            execution_id: self.execution_id,
+            tracer: self.tracer,
+            enforce_reliable_broadcast: self.enforce_reliable_broadcast,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    /// Specifies a tracer that tracks progress of protocol execution
+    pub fn set_progress_tracer(mut self, tracer: &'a mut dyn Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
+    pub fn enforce_reliable_broadcast(self, v: bool) -> Self {
+        Self {
+            enforce_reliable_broadcast: v,
+            ..self
+        }
+    }
+
+    /// Converts additive key share into threshold n-out-of-n key share
+    ///
+    /// All parties sharing the key must participate in the protocol. Index of the party
+    /// within the protocol is [its index at keygen](DirtyIncompleteKeyShare::i).
+    pub async fn start<E, R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        key_share: &impl AnyKeyShare<E>,
+    ) -> Result<IncompleteKeyShare<E>, KeyConversionError>
+    where
+        E: Curve,
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        run_to_threshold(
+            self.tracer,
+            rng,
+            party,
+            self.execution_id,
+            self.enforce_reliable_broadcast,
+            key_share.as_ref(),
+        )
+        .await
+    }
+}
+
+async fn run_to_threshold<E, R, M, D>(
+    mut tracer: Option<&mut dyn Tracer>,
+    rng: &mut R,
+    party: M,
+    sid: ExecutionId<'_>,
+    reliable_broadcast_enforced: bool,
+    key_share: &IncompleteKeyShare<E>,
+) -> Result<IncompleteKeyShare<E>, KeyConversionError>
+where
+    E: Curve,
+    R: RngCore + CryptoRng,
+    M: Mpc<ProtocolMessage = Msg<E, D>>,
+    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+{
+    tracer.protocol_begins();
+
+    tracer.stage("Validate arguments");
+    if key_share.vss_setup.is_some() {
+        return Err(InvalidArgs::ThresholdKey.into());
+    }
+    let i = key_share.i;
+    let n = key_share.n();
+    let I = (1..=n)
+        .map(|j| NonZero::from_scalar(Scalar::from(j)))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Bug::DeriveShareIndex)?;
+
+    tracer.stage("Setup networking");
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incomings, mut outgoings) = delivery.split();
+
+    let mut rounds = RoundsRouter::<Msg<E, D>>::builder();
+    let round1_broad = rounds.add_round(RoundInput::<MsgRound1Broad<E, D>>::broadcast(i, n));
+    let round1_uni = rounds.add_round(RoundInput::<MsgRound1Uni<E>>::p2p(i, n));
+    let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
+    let mut rounds = rounds.listen(incomings);
+
+    // Round 1
+    tracer.round_begins();
+
+    tracer.stage("Hash key info");
+    let key_info_hash = udigest::Tag::<D>::new_structured(Tag {
+        sid: sid.as_bytes(),
+    })
+    .digest(KeyInfoDigest::new(&key_share.key_info));
+
+    tracer.stage("Sample polynomial");
+    let f = Polynomial::sample_with_const_term(rng, usize::from(n) - 1, key_share.x.clone());
+    let commitment = f
+        .coefs()
+        .iter()
+        .map(|a| (Point::generator() * a).into_inner())
+        .collect::<Vec<_>>();
+    let sigmas = I
+        .iter()
+        .map(|I_j| f.value(I_j))
+        .map(|mut sigma: Scalar<E>| SecretScalar::new(&mut sigma))
+        .collect::<Vec<_>>();
+
+    let my_msg = MsgRound1Broad {
+        key_info_hash,
+        commitment,
+    };
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round1Broad(my_msg.clone())))
+        .await
+        .map_err(IoError::send_message)?;
+    for j in crate::utils::iter_peers(i, n) {
+        outgoings
+            .send(Outgoing::p2p(
+                j,
+                Msg::Round1Uni(MsgRound1Uni {
+                    sigma: *sigmas[usize::from(j)].as_ref(),
+                }),
+            ))
+            .await
+            .map_err(IoError::send_message)?;
+    }
+    tracer.msg_sent();
+
+    // Round 2
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let round1_broad = rounds
+        .complete(round1_broad)
+        .await
+        .map_err(IoError::receive_message)?;
+    let round1_uni = rounds
+        .complete(round1_uni)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    // Optional reliability check
+    if reliable_broadcast_enforced {
+        tracer.stage("Hash received msgs (reliability check)");
+        let h_i = udigest::Tag::<D>::new_structured(Tag {
+            sid: sid.as_bytes(),
+        })
+        .digest_iter(round1_broad.iter_including_me(&my_msg));
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::ReliabilityCheck(
+                MsgReliabilityCheck(h_i),
+            )))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let hashes = rounds
+            .complete(round1_sync)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
+
+        tracer.stage("Assert other parties hashed messages (reliability check)");
+        let parties_have_different_hashes = hashes
+            .into_iter_indexed()
+            .filter(|(_j, _msg_id, h_j)| h_i != h_j.0)
+            .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
+            .collect::<Vec<_>>();
+        if !parties_have_different_hashes.is_empty() {
+            return Err(
+                KeyConversionAborted::Round1NotReliable(parties_have_different_hashes).into(),
+            );
+        }
+    }
+
+    tracer.stage("Validate key info hashes");
+    let blame = round1_broad
+        .iter_indexed()
+        .filter(|(_, _, msg)| msg.key_info_hash != key_info_hash)
+        .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(KeyConversionAborted::MismatchedKeyInfo(blame).into());
+    }
+
+    tracer.stage("Validate commitments");
+    let blame = round1_broad
+        .iter_indexed()
+        .filter(|(j, _, msg)| {
+            msg.commitment.len() != usize::from(n)
+                || msg.commitment[0] != key_share.public_shares[usize::from(*j)]
+        })
+        .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(KeyConversionAborted::InvalidCommitment(blame).into());
+    }
+
+    tracer.stage("Validate received shares");
+    let I_i = I[usize::from(i)];
+    let blame = round1_broad
+        .iter_indexed()
+        .zip(round1_uni.iter_indexed())
+        .filter(|((_, _, commitment_msg), (_, _, sigma_msg))| {
+            Point::generator() * sigma_msg.sigma
+                != commitment_value(&commitment_msg.commitment, &I_i)
+        })
+        .map(|((j, commitment_msg_id, _), (_, sigma_msg_id, _))| {
+            AbortBlame::new(j, commitment_msg_id, sigma_msg_id)
+        })
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(KeyConversionAborted::InvalidShare(blame).into());
+    }
+
+    tracer.stage("Compute threshold key share");
+    let mut x =
+        round1_uni.iter().map(|msg| msg.sigma).sum::<Scalar<E>>() + sigmas[usize::from(i)].as_ref();
+    let x = NonZero::from_secret_scalar(SecretScalar::new(&mut x)).ok_or(Bug::ZeroShare)?;
+    let commitments = round1_broad
+        .iter_including_me(&my_msg)
+        .map(|msg| &msg.commitment)
+        .collect::<Vec<_>>();
+    let public_shares = I
+        .iter()
+        .map(|I_k| {
+            let X_k = commitments
+                .iter()
+                .map(|F_j| commitment_value(F_j, I_k))
+                .sum::<Point<E>>();
+            NonZero::from_point(X_k).ok_or(Bug::ZeroShare)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let key_share = DirtyIncompleteKeyShare {
+        i,
+        key_info: DirtyKeyInfo {
+            public_shares,
+            vss_setup: Some(VssSetup { min_signers: n, I }),
+            ..key_share.key_info.clone()
+        },
+        x,
+    }
+    .validate()
+    .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?;
+
+    tracer.protocol_ends();
+    Ok(key_share)
+}
+
+/// Evaluates committed polynomial $F(x)$
+fn commitment_value<E: Curve>(commitment: &[Point<E>], x: &NonZero<Scalar<E>>) -> Point<E> {
+    commitment
+        .iter()
+        .rev()
+        .fold(Point::zero(), |acc, coef| acc * x + coef)
+}
+
+/// Error indicating that key share conversion failed
+#[derive(Debug, Error)]
+#[error("key share conversion failed")]
+pub struct KeyConversionError(#[source] Reason);
+
+crate::errors::impl_from! {
+    impl From for KeyConversionError {
+        err: InvalidArgs => KeyConversionError(Reason::InvalidArgs(err)),
+        err: KeyConversionAborted => KeyConversionError(Reason::Aborted(err)),
+        err: IoError => KeyConversionError(Reason::IoError(err)),
+        err: Bug => KeyConversionError(Reason::Bug(err)),
+    }
+}
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("invalid arguments")]
+    InvalidArgs(#[source] InvalidArgs),
+    /// Protocol was maliciously aborted by another party
+    #[error("protocol was aborted by malicious party")]
+    Aborted(#[source] KeyConversionAborted),
+    #[error("i/o error")]
+    IoError(#[source] IoError),
+    /// Bug occurred
+    #[error("bug occurred")]
+    Bug(#[source] Bug),
+}
+
+#[derive(Debug, Error)]
+enum InvalidArgs {
+    #[error("only threshold key shares can be converted to additive")]
+    NonThresholdKey,
+    #[error("only additive key shares can be converted to threshold")]
+    ThresholdKey,
+    #[error("at least `min_signers` signers are required")]
+    TooFewSigners,
+    #[error("too many signers")]
+    TooManySigners,
+    #[error("signer index is out of bounds (must be < n)")]
+    SignerOutOfBounds,
+    #[error("signers list contains duplicates")]
+    DuplicatedSigner,
+    #[error("key share doesn't belong to any of signers")]
+    NotASigner,
+}
+
+/// Error indicating that protocol was aborted by malicious party
+#[derive(Debug, Error)]
+enum KeyConversionAborted {
+    #[error("parties hold different key info")]
+    MismatchedKeyInfo(Vec<AbortBlame>),
+    #[error("commitment is malformed or doesn't match public share of the party")]
+    InvalidCommitment(Vec<AbortBlame>),
+    #[error("received share doesn't match the commitment")]
+    InvalidShare(Vec<AbortBlame>),
+    #[error("round 1 was not reliable")]
+    Round1NotReliable(Vec<AbortBlame>),
+}
+
+#[derive(Debug, Error)]
+enum Bug {
+    #[error("couldn't derive lagrange coefficient")]
+    LagrangeCoef,
+    #[error("couldn't derive share index")]
+    DeriveShareIndex,
+    #[error("resulting share is zero")]
+    ZeroShare,
+    #[error("resulting key share is not valid")]
+    InvalidKeyShare(#[source] InvalidIncompleteKeyShare),
+}
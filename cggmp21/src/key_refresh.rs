@@ -4,6 +4,10 @@
 mod aux_only;
 /// Non-threshold key refresh specific types
 mod non_threshold;
+/// Partial aux info refresh specific types
+mod partial_aux;
+#[cfg(feature = "prime-pool")]
+pub mod prime_pool;
 
 use digest::Digest;
 use generic_ec::Curve;
@@ -16,13 +20,16 @@ use crate::{
     key_share::{AnyKeyShare, AuxInfo, DirtyIncompleteKeyShare, KeyShare},
     progress::Tracer,
     security_level::SecurityLevel,
-    utils::AbortBlame,
+    utils::{self, AbortBlame, Parallelism},
     ExecutionId,
 };
 use crate::{fast_paillier, rug::Integer};
 
 #[doc(no_inline)]
-pub use self::msg::{aux_only::Msg as AuxOnlyMsg, non_threshold::Msg as NonThresholdMsg};
+pub use self::msg::{
+    aux_only::Msg as AuxOnlyMsg, non_threshold::Msg as NonThresholdMsg,
+    partial_aux::Msg as PartialAuxMsg,
+};
 
 #[doc = include_str!("../docs/mpc_message.md")]
 pub mod msg {
@@ -38,10 +45,18 @@ pub mod msg {
             Msg, MsgReliabilityCheck, MsgRound1, MsgRound2, MsgRound3,
         };
     }
+    /// Messages types related to partial aux info refresh protocol
+    pub mod partial_aux {
+        pub use crate::key_refresh::partial_aux::{
+            Msg, MsgReliabilityCheck, MsgRound1, MsgRound2, MsgRound3, NewAuxProofs, NewPartyAux,
+        };
+    }
 }
 
 /// To speed up computations, it's possible to supply data to the algorithm
 /// generated ahead of time
+///
+/// Primes are wiped from memory on drop.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct PregeneratedPrimes<L = crate::default_choice::SecurityLevel> {
     p: Integer,
@@ -55,7 +70,8 @@ impl<L: SecurityLevel> PregeneratedPrimes<L> {
     /// Returns `None` if big numbers are smaller than 4 * [L::SECURITY_BITS](crate::security_level::KeygenSecurityLevel::SECURITY_BITS)
     ///
     /// Function doesn't validate that provided numbers are primes. If they're not,
-    /// key refresh protocol should fail with some ZK proof error.
+    /// key refresh protocol should fail with some ZK proof error. Use [`validate`](Self::validate)
+    /// to check the primes locally.
     pub fn new(p: Integer, q: Integer) -> Option<Self> {
         if !crate::security_level::validate_secret_paillier_key_size::<L>(&p, &q) {
             None
@@ -68,9 +84,44 @@ impl<L: SecurityLevel> PregeneratedPrimes<L> {
         }
     }
 
+    /// Validates the primes
+    ///
+    /// Checks that `p` and `q` are distinct safe primes (using Miller-Rabin test), that
+    /// `N = pq` is a Blum integer, and that `p` and `q` are large enough for security level `L`.
+    ///
+    /// Takes some time, though much less than generating primes.
+    pub fn validate(&self) -> Result<(), InvalidPregeneratedPrimes> {
+        use crate::rug::integer::IsPrime;
+
+        /// Amount of Miller-Rabin rounds, same as used in primes generation
+        const REPS: u32 = 25;
+        let is_prime = |x: &Integer| !matches!(x.is_probably_prime(REPS), IsPrime::No);
+
+        if !crate::security_level::validate_secret_paillier_key_size::<L>(&self.p, &self.q) {
+            return Err(InvalidPrimesReason::TooSmall.into());
+        }
+        if self.p == self.q {
+            return Err(InvalidPrimesReason::Equal.into());
+        }
+        for x in [&self.p, &self.q] {
+            // Blum integer requires `p = q = 3 (mod 4)`
+            if x.mod_u(4) != 3 {
+                return Err(InvalidPrimesReason::NotBlum.into());
+            }
+            if !is_prime(x) {
+                return Err(InvalidPrimesReason::NotPrime.into());
+            }
+            let x_half = Integer::from(x - 1u32) >> 1u32;
+            if !is_prime(&x_half) {
+                return Err(InvalidPrimesReason::NotSafePrime.into());
+            }
+        }
+        Ok(())
+    }
+
     /// Returns `p, q`
-    pub fn split(self) -> (Integer, Integer) {
-        (self.p, self.q)
+    pub fn split(mut self) -> (Integer, Integer) {
+        (std::mem::take(&mut self.p), std::mem::take(&mut self.q))
     }
 
     /// Generates primes. Takes some time.
@@ -83,6 +134,19 @@ impl<L: SecurityLevel> PregeneratedPrimes<L> {
     }
 }
 
+impl<L> zeroize::Zeroize for PregeneratedPrimes<L> {
+    fn zeroize(&mut self) {
+        utils::zeroize_integer(&mut self.p);
+        utils::zeroize_integer(&mut self.q);
+    }
+}
+
+impl<L> Drop for PregeneratedPrimes<L> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self)
+    }
+}
+
 /// A variant of [`GenericKeyRefreshBuilder`] that performs key refresh
 pub type KeyRefreshBuilder<
     'a,
@@ -112,6 +176,8 @@ where
     enforce_reliable_broadcast: bool,
     precompute_multiexp_tables: bool,
     precompute_crt: bool,
+    parallelism: Parallelism,
+    validate_primes: bool,
     _digest: std::marker::PhantomData<D>,
 }
 
@@ -145,6 +211,8 @@ where
             enforce_reliable_broadcast: true,
             precompute_multiexp_tables: false,
             precompute_crt: false,
+            parallelism: Parallelism::Sequential,
+            validate_primes: false,
             _digest: std::marker::PhantomData,
         }
     }
@@ -158,6 +226,9 @@ where
         L: SecurityLevel,
         D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
     {
+        if self.validate_primes {
+            self.pregenerated.validate()?;
+        }
         non_threshold::run_refresh(
             rng,
             party,
@@ -167,6 +238,7 @@ where
             self.enforce_reliable_broadcast,
             self.precompute_multiexp_tables,
             self.precompute_crt,
+            &self.parallelism,
             self.target.0,
         )
         .await
@@ -195,6 +267,8 @@ where
             enforce_reliable_broadcast: true,
             precompute_multiexp_tables: false,
             precompute_crt: false,
+            parallelism: Parallelism::Sequential,
+            validate_primes: false,
             _digest: std::marker::PhantomData,
         }
     }
@@ -207,6 +281,9 @@ where
         L: SecurityLevel,
         D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
     {
+        if self.validate_primes {
+            self.pregenerated.validate()?;
+        }
         aux_only::run_aux_gen(
             self.target.i,
             self.target.n,
@@ -218,6 +295,7 @@ where
             self.enforce_reliable_broadcast,
             self.precompute_multiexp_tables,
             self.precompute_crt,
+            &self.parallelism,
         )
         .await
     }
@@ -238,6 +316,8 @@ where
             enforce_reliable_broadcast: self.enforce_reliable_broadcast,
             precompute_multiexp_tables: self.precompute_multiexp_tables,
             precompute_crt: self.precompute_crt,
+            parallelism: self.parallelism,
+            validate_primes: self.validate_primes,
             _digest: std::marker::PhantomData,
         }
     }
@@ -277,6 +357,255 @@ where
         self.precompute_crt = v;
         self
     }
+
+    /// Validates pregenerated primes before starting the protocol
+    ///
+    /// When enabled, [`PregeneratedPrimes::validate`] is called before any message is sent,
+    /// so invalid primes are reported as a local error instead of making other parties abort
+    /// the protocol. Disabled by default.
+    pub fn validate_primes(mut self, v: bool) -> Self {
+        self.validate_primes = v;
+        self
+    }
+
+    /// Computes and verifies ZK proofs in parallel
+    ///
+    /// When enabled, proofs of all the parties are verified concurrently, proofs addressed to
+    /// different parties are computed concurrently, and repetitions of ring-pedersen parameters
+    /// proof are computed and verified concurrently. Computations are carried out in the global
+    /// rayon thread pool, use [`set_thread_pool`](Self::set_thread_pool) to specify another one.
+    ///
+    /// Note that the protocol doesn't yield to async runtime while proofs are being computed.
+    /// Disabled by default.
+    #[cfg(feature = "parallel")]
+    pub fn parallel(mut self, v: bool) -> Self {
+        self.parallelism = if v {
+            Parallelism::ThreadPool(None)
+        } else {
+            Parallelism::Sequential
+        };
+        self
+    }
+
+    /// Computes and verifies ZK proofs in parallel using given thread pool
+    ///
+    /// Same as [`parallel(true)`](Self::parallel), but computations are carried out in
+    /// the provided thread pool.
+    #[cfg(feature = "parallel")]
+    pub fn set_thread_pool(mut self, pool: std::sync::Arc<rayon::ThreadPool>) -> Self {
+        self.parallelism = Parallelism::ThreadPool(Some(pool));
+        self
+    }
+}
+
+/// Entry point for partial aux info refresh
+///
+/// Refreshes Paillier and ring-Pedersen parameters only of the chosen subset of parties, for instance,
+/// when Paillier key of some party is suspected to be compromised. Refreshing parties generate new
+/// `N, s, t` and prove that they're well-formed, and all other parties verify the proofs. All
+/// parties update corresponding entries in their aux info.
+///
+/// All `n` parties sharing the aux info must take part in the protocol. Refreshing parties
+/// must provide [pregenerated primes](Self::set_pregenerated_primes).
+pub struct PartialAuxRefreshBuilder<
+    'a,
+    L = crate::default_choice::SecurityLevel,
+    D = crate::default_choice::Digest,
+> where
+    L: SecurityLevel,
+    D: Digest,
+{
+    i: u16,
+    aux_info: &'a AuxInfo<L>,
+    refreshing: &'a [u16],
+    execution_id: ExecutionId<'a>,
+    pregenerated: Option<PregeneratedPrimes<L>>,
+    tracer: Option<&'a mut dyn Tracer>,
+    enforce_reliable_broadcast: bool,
+    precompute_multiexp_tables: bool,
+    precompute_crt: bool,
+    parallelism: Parallelism,
+    validate_primes: bool,
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<'a, L, D> PartialAuxRefreshBuilder<'a, L, D>
+where
+    L: SecurityLevel,
+    D: Digest,
+{
+    /// Build partial aux info refresh operation. Start it with [`start`](Self::start).
+    ///
+    /// `i` is index of the party, `aux_info` is its current aux info (or key share), and `refreshing`
+    /// is a list of parties which aux data is being refreshed.
+    pub fn new(
+        eid: ExecutionId<'a>,
+        i: u16,
+        aux_info: &'a impl AsRef<AuxInfo<L>>,
+        refreshing: &'a [u16],
+    ) -> Self {
+        Self {
+            i,
+            aux_info: aux_info.as_ref(),
+            refreshing,
+            execution_id: eid,
+            pregenerated: None,
+            tracer: None,
+            enforce_reliable_broadcast: true,
+            precompute_multiexp_tables: false,
+            precompute_crt: false,
+            parallelism: Parallelism::Sequential,
+            validate_primes: false,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    /// Specifies primes for the new Paillier key
+    ///
+    /// Required if party is refreshing its aux data, must not be set otherwise.
+    /// PregeneratedPrimes can be obtained with [`PregeneratedPrimes::generate`]
+    pub fn set_pregenerated_primes(mut self, pregenerated: PregeneratedPrimes<L>) -> Self {
+        self.pregenerated = Some(pregenerated);
+        self
+    }
+
+    /// Specifies another hash function to use
+    pub fn set_digest<D2: Digest>(self) -> PartialAuxRefreshBuilder<'a, L, D2> {
+        PartialAuxRefreshBuilder {
+            i: self.i,
+            aux_info: self.aux_info,
+            refreshing: self.refreshing,
+            execution_id: self.execution_id,
+            pregenerated: self.pregenerated,
+            tracer: self.tracer,
+            enforce_reliable_broadcast: self.enforce_reliable_broadcast,
+            precompute_multiexp_tables: self.precompute_multiexp_tables,
+            precompute_crt: self.precompute_crt,
+            parallelism: self.parallelism,
+            validate_primes: self.validate_primes,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    /// Sets a tracer that tracks progress of protocol execution
+    pub fn set_progress_tracer(mut self, tracer: &'a mut dyn Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    #[doc = include_str!("../docs/enforce_reliable_broadcast.md")]
+    pub fn enforce_reliable_broadcast(self, v: bool) -> Self {
+        Self {
+            enforce_reliable_broadcast: v,
+            ..self
+        }
+    }
+
+    /// Precomputes multiexponentiation tables for output aux data
+    ///
+    /// See [`GenericKeyRefreshBuilder::precompute_multiexp_tables`]
+    pub fn precompute_multiexp_tables(mut self, v: bool) -> Self {
+        self.precompute_multiexp_tables = v;
+        self
+    }
+
+    /// Precomputes CRT parameters, if they're not present in the aux info yet
+    ///
+    /// See [`GenericKeyRefreshBuilder::precompute_crt`]
+    pub fn precompute_crt(mut self, v: bool) -> Self {
+        self.precompute_crt = v;
+        self
+    }
+
+    /// Validates pregenerated primes before starting the protocol
+    ///
+    /// See [`GenericKeyRefreshBuilder::validate_primes`]
+    pub fn validate_primes(mut self, v: bool) -> Self {
+        self.validate_primes = v;
+        self
+    }
+
+    /// Computes and verifies ZK proofs in parallel
+    ///
+    /// See [`GenericKeyRefreshBuilder::parallel`]
+    #[cfg(feature = "parallel")]
+    pub fn parallel(mut self, v: bool) -> Self {
+        self.parallelism = if v {
+            Parallelism::ThreadPool(None)
+        } else {
+            Parallelism::Sequential
+        };
+        self
+    }
+
+    /// Computes and verifies ZK proofs in parallel in the given thread pool
+    #[cfg(feature = "parallel")]
+    pub fn set_thread_pool(mut self, pool: std::sync::Arc<rayon::ThreadPool>) -> Self {
+        self.parallelism = Parallelism::ThreadPool(Some(pool));
+        self
+    }
+
+    /// Carry out the partial aux info refresh procedure. Takes a lot of time
+    ///
+    /// Outputs updated aux info
+    pub async fn start<R, M>(self, rng: &mut R, party: M) -> Result<AuxInfo<L>, KeyRefreshError>
+    where
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = partial_aux::Msg<D, L>>,
+        D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    {
+        let n = partial_aux::validate_args(
+            self.i,
+            self.aux_info,
+            self.refreshing,
+            self.pregenerated.as_ref(),
+        )?;
+        if let (true, Some(pregenerated)) = (self.validate_primes, &self.pregenerated) {
+            pregenerated.validate()?;
+        }
+        partial_aux::run_partial_aux_refresh(
+            self.i,
+            n,
+            rng,
+            party,
+            self.execution_id,
+            self.aux_info,
+            self.refreshing,
+            self.pregenerated,
+            self.tracer,
+            self.enforce_reliable_broadcast,
+            self.precompute_multiexp_tables,
+            self.precompute_crt,
+            &self.parallelism,
+        )
+        .await
+    }
+}
+
+/// Derives execution ID of the next key refresh
+///
+/// Output is determined by the [key ID](crate::key_share::DirtyKeyInfo::key_id) and the
+/// [epoch](AnyKeyShare::epoch) of the key share, so all signers holding shares of the same generation derive the same
+/// value without extra communication, and it's different for every subsequent refresh.
+/// Use it as `ExecutionId::new(&next_refresh_execution_id::<_, D>(&key_share))`.
+///
+/// Note that the value doesn't change until refresh succeeds, so if refresh was aborted,
+/// retrying it would reuse the same execution ID. In this case, you need to additionally
+/// bind it to the attempt number.
+pub fn next_refresh_execution_id<E: Curve, D: Digest>(
+    key_share: &impl AnyKeyShare<E>,
+) -> digest::Output<D> {
+    #[derive(udigest::Digestable)]
+    struct NextRefresh {
+        #[udigest(as_bytes)]
+        key_id: [u8; 32],
+        epoch: u64,
+    }
+
+    udigest::Tag::<D>::new("dfns.cggmp21.key_refresh.next_execution_id").digest(NextRefresh {
+        key_id: *key_share.as_ref().key_info.key_id().as_bytes(),
+        epoch: key_share.epoch(),
+    })
 }
 
 /// Error of key refresh and aux info generation protocols
@@ -288,6 +617,8 @@ crate::errors::impl_from! {
     impl From for KeyRefreshError {
         err: ProtocolAborted => KeyRefreshError(Reason::Aborted(err)),
         err: IoError => KeyRefreshError(Reason::IoError(err)),
+        err: InvalidPregeneratedPrimes => KeyRefreshError(Reason::InvalidPrimes(err)),
+        err: partial_aux::InvalidArgs => KeyRefreshError(Reason::InvalidArgs(err)),
         err: Bug => KeyRefreshError(Reason::InternalError(err)),
     }
 }
@@ -299,10 +630,35 @@ enum Reason {
     Aborted(#[source] ProtocolAborted),
     #[error("i/o error")]
     IoError(#[source] IoError),
+    #[error("pregenerated primes are invalid")]
+    InvalidPrimes(#[source] InvalidPregeneratedPrimes),
+    #[error("invalid arguments")]
+    InvalidArgs(#[source] partial_aux::InvalidArgs),
     #[error("internal error")]
     InternalError(#[from] Bug),
 }
 
+/// Error indicating that [`PregeneratedPrimes`] are invalid
+///
+/// Returned by [`PregeneratedPrimes::validate`]
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct InvalidPregeneratedPrimes(#[from] InvalidPrimesReason);
+
+#[derive(Debug, Error)]
+enum InvalidPrimesReason {
+    #[error("primes are too small for the security level")]
+    TooSmall,
+    #[error("p and q are equal")]
+    Equal,
+    #[error("number is not prime")]
+    NotPrime,
+    #[error("number is not a safe prime")]
+    NotSafePrime,
+    #[error("N = pq is not a Blum integer")]
+    NotBlum,
+}
+
 /// Unexpected error in operation not caused by other parties
 #[derive(Debug, Error)]
 enum Bug {
@@ -325,9 +681,11 @@ enum Bug {
     #[error("couldn't build multiexp tables")]
     BuildMultiexpTables(#[source] crate::key_share::InvalidKeyShare),
     #[error("couldn't build CRT")]
-    BuildCrt,
+    BuildCrt(#[source] crate::key_share::InvalidKeyShare),
     #[error("updated share is zero - probability of that is negligible")]
     ZeroShare,
+    #[error("key share epoch overflows u64")]
+    EpochOverflow,
 }
 
 /// Error indicating that protocol was aborted by malicious party
@@ -363,6 +721,10 @@ enum ProtocolAbortReason {
     PaillierDec,
     #[error("round 1 was not reliable")]
     Round1NotReliable,
+    #[error("party started the protocol with different aux info")]
+    MismatchedAuxInfo,
+    #[error("party holds key share of different epoch")]
+    MismatchedEpoch,
 }
 
 macro_rules! make_factory {
@@ -389,4 +751,6 @@ impl ProtocolAborted {
     make_factory!(invalid_data_size, InvalidDataSize);
     make_factory!(paillier_dec, PaillierDec);
     make_factory!(round1_not_reliable, Round1NotReliable);
+    make_factory!(mismatched_aux_info, MismatchedAuxInfo);
+    make_factory!(mismatched_epoch, MismatchedEpoch);
 }
@@ -2,11 +2,10 @@ use digest::Digest;
 use futures::SinkExt;
 use paillier_zk::{
     no_small_factor::non_interactive as π_fac,
-    paillier_blum_modulus as π_mod,
     rug::{Complete, Integer},
     IntegerExt,
 };
-use rand_core::{CryptoRng, RngCore};
+use rand_core::{CryptoRng, RngCore, SeedableRng};
 use round_based::{
     rounds_router::{simple_store::RoundInput, RoundsRouter},
     Delivery, Mpc, MpcParty, Outgoing, ProtocolMessage,
@@ -15,12 +14,15 @@ use serde::{Deserialize, Serialize};
 
 use crate::{
     errors::IoError,
-    key_share::{AuxInfo, DirtyAuxInfo, PartyAux, Validate},
+    key_share::{AuxInfo, CrtParams, DirtyAuxInfo, PartyAux, Validate},
     progress::Tracer,
     security_level::SecurityLevel,
     utils,
-    utils::{collect_blame, AbortBlame},
-    zk::ring_pedersen_parameters as π_prm,
+    utils::{
+        collect_blame, collect_blame_par, collect_simple_blame, collect_simple_blame_batch,
+        AbortBlame, Parallelism,
+    },
+    zk::{paillier_blum_modulus as π_mod, ring_pedersen_parameters as π_prm},
     ExecutionId,
 };
 
@@ -68,8 +70,7 @@ pub struct MsgRound2<L: SecurityLevel> {
     #[udigest(with = utils::encoding::integer)]
     pub t: Integer,
     /// $\hat \psi_i$
-    // this should be L::M instead, but no rustc support yet
-    pub params_proof: π_prm::Proof<{ crate::security_level::M }>,
+    pub params_proof: π_prm::Proof,
     /// $\rho_i$
     // ideally it would be [u8; L::SECURITY_BYTES], but no rustc support yet
     #[serde(with = "hex")]
@@ -84,11 +85,7 @@ pub struct MsgRound2<L: SecurityLevel> {
 #[derive(Clone, Serialize, Deserialize)]
 pub struct MsgRound3 {
     /// $\psi_i$
-    // this should be L::M instead, but no rustc support yet
-    pub mod_proof: (
-        π_mod::Commitment,
-        π_mod::Proof<{ crate::security_level::M }>,
-    ),
+    pub mod_proof: π_mod::Proof,
     /// $\phi_i^j$
     pub fac_proof: π_fac::Proof,
 }
@@ -125,6 +122,7 @@ pub async fn run_aux_gen<R, M, L, D>(
     reliable_broadcast_enforced: bool,
     compute_multiexp_table: bool,
     compute_crt: bool,
+    parallelism: &Parallelism,
 ) -> Result<AuxInfo<L>, KeyRefreshError>
 where
     R: RngCore + CryptoRng,
@@ -162,7 +160,7 @@ where
     tracer.round_begins();
 
     tracer.stage("Retrieve primes (p and q)");
-    let PregeneratedPrimes { p, q, .. } = pregenerated;
+    let (p, q) = pregenerated.split();
     tracer.stage("Compute paillier decryption key (N)");
     let N = (&p * &q).complete();
     let phi_N = (&p - 1u8).complete() * (&q - 1u8).complete();
@@ -179,6 +177,7 @@ where
     let hat_psi = π_prm::prove(
         parties_shared_state.clone().chain_update(i.to_be_bytes()),
         &mut rng,
+        L::M,
         π_prm::Data {
             N: &N,
             s: &s,
@@ -186,6 +185,7 @@ where
         },
         &phi_N,
         &lambda,
+        parallelism,
     )
     .map_err(Bug::PiPrm)?;
 
@@ -292,26 +292,30 @@ where
     }
     // validate parameters and param_proofs
     tracer.stage("Validate П_prm (ψ_i)");
-    let blame = collect_blame(&decommitments, &decommitments, |j, d, _| {
-        if !crate::security_level::validate_public_paillier_key_size::<L>(&d.N) {
-            true
-        } else {
-            let data = π_prm::Data {
-                N: &d.N,
-                s: &d.s,
-                t: &d.t,
-            };
-            π_prm::verify(
-                parties_shared_state.clone().chain_update(j.to_be_bytes()),
-                data,
-                &d.params_proof,
-            )
-            .is_err()
-        }
+    let blame = collect_simple_blame(&decommitments, |d| {
+        !crate::security_level::validate_public_paillier_key_size::<L>(&d.N)
     });
     if !blame.is_empty() {
         return Err(ProtocolAborted::invalid_ring_pedersen_parameters(blame).into());
     }
+    let blame = collect_simple_blame_batch(
+        &decommitments,
+        |j, d| {
+            Some(π_prm::BatchItem {
+                shared_state: D::new_with_prefix(D::digest(sid)).chain_update(j.to_be_bytes()),
+                data: π_prm::Data {
+                    N: &d.N,
+                    s: &d.s,
+                    t: &d.t,
+                },
+                proof: &d.params_proof,
+            })
+        },
+        |items| π_prm::verify_batch(L::M, items, parallelism),
+    );
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::invalid_ring_pedersen_parameters(blame).into());
+    }
 
     tracer.stage("Add together shared random bytes");
     // rho in paper, collective random bytes
@@ -321,13 +325,15 @@ where
         .fold(rho_bytes, utils::xor_array);
 
     // common data for messages
-    let my_shared_state = parties_shared_state
-        .clone()
-        .chain_update(i.to_be_bytes())
-        .chain_update(&rho_bytes);
+    let shared_state = |j: u16| {
+        D::new_with_prefix(D::digest(sid))
+            .chain_update(j.to_be_bytes())
+            .chain_update(&rho_bytes)
+    };
     tracer.stage("Compute П_mod (ψ_i)");
-    let psi = π_mod::non_interactive::prove(
-        my_shared_state.clone(),
+    let psi = π_mod::prove(
+        shared_state(i),
+        L::M,
         &π_mod::Data { n: N.clone() },
         &π_mod::PrivateData {
             p: p.clone(),
@@ -344,34 +350,45 @@ where
     };
     let n_sqrt = utils::sqrt(&N);
 
-    // message to each party
-    for (j, _, d) in decommitments.iter_indexed() {
-        tracer.send_msg();
-
-        tracer.stage("Compute П_fac (ф_i^j)");
-        let phi = π_fac::prove(
-            my_shared_state.clone(),
-            &π_fac::Aux {
-                s: d.s.clone(),
-                t: d.t.clone(),
-                rsa_modulo: d.N.clone(),
-                multiexp: None,
-                crt: None,
-            },
-            π_fac::Data {
-                n: &N,
-                n_root: &n_sqrt,
-            },
-            π_fac::PrivateData { p: &p, q: &q },
-            &π_fac_security,
-            &mut rng,
-        )
+    tracer.stage("Compute П_fac (ф_i^j)");
+    let fac_proofs_data = decommitments
+        .iter()
+        .map(|d| {
+            let mut seed = [0u8; 32];
+            rng.fill_bytes(&mut seed);
+            (d, seed)
+        })
+        .collect::<Vec<_>>();
+    let phis = parallelism
+        .map(&fac_proofs_data, |(d, seed)| {
+            π_fac::prove(
+                shared_state(i),
+                &π_fac::Aux {
+                    s: d.s.clone(),
+                    t: d.t.clone(),
+                    rsa_modulo: d.N.clone(),
+                    multiexp: None,
+                    crt: None,
+                },
+                π_fac::Data {
+                    n: &N,
+                    n_root: &n_sqrt,
+                },
+                π_fac::PrivateData { p: &p, q: &q },
+                &π_fac_security,
+                &mut rand_chacha::ChaCha20Rng::from_seed(*seed),
+            )
+        })
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
         .map_err(Bug::PiFac)?;
 
+    // message to each party
+    for ((j, _, _), phi) in decommitments.iter_indexed().zip(phis) {
         tracer.send_msg();
         let msg = MsgRound3 {
             mod_proof: psi.clone(),
-            fac_proof: phi.clone(),
+            fac_proof: phi,
         };
         outgoings
             .send(Outgoing::p2p(j, Msg::Round3(msg)))
@@ -392,24 +409,15 @@ where
 
     tracer.stage("Validate ψ_j (П_mod)");
     // verify mod proofs
-    let blame = collect_blame(
+    let blame = collect_blame_par(
+        parallelism,
         &decommitments,
         &shares_msg_b,
         |j, decommitment, proof_msg| {
             let data = π_mod::Data {
                 n: decommitment.N.clone(),
             };
-            let (comm, proof) = &proof_msg.mod_proof;
-            π_mod::non_interactive::verify(
-                parties_shared_state
-                    .clone()
-                    .chain_update(j.to_be_bytes())
-                    .chain_update(&rho_bytes),
-                &data,
-                comm,
-                proof,
-            )
-            .is_err()
+            π_mod::verify(shared_state(j), L::M, &data, &proof_msg.mod_proof).is_err()
         },
     );
     if !blame.is_empty() {
@@ -421,7 +429,7 @@ where
 
     let crt = if compute_crt {
         // note: `crt` contains private information
-        Some(paillier_zk::fast_paillier::utils::CrtExp::build_n(&p, &q).ok_or(Bug::BuildCrt)?)
+        Some(CrtParams::new(&p, &q).map_err(Bug::BuildCrt)?)
     } else {
         None
     };
@@ -430,17 +438,15 @@ where
         t: t.clone(),
         rsa_modulo: N.clone(),
         multiexp: None,
-        crt: crt.clone(),
+        crt: crt.as_ref().and_then(CrtParams::crt_exp).cloned(),
     };
-    let blame = collect_blame(
+    let blame = collect_blame_par(
+        parallelism,
         &decommitments,
         &shares_msg_b,
         |j, decommitment, proof_msg| {
             π_fac::verify(
-                parties_shared_state
-                    .clone()
-                    .chain_update(j.to_be_bytes())
-                    .chain_update(&rho_bytes),
+                shared_state(j),
                 &phi_common_aux,
                 π_fac::Data {
                     n: &decommitment.N,
@@ -5,11 +5,10 @@ use generic_ec_zkp::schnorr_pok;
 use paillier_zk::{
     fast_paillier,
     no_small_factor::non_interactive as π_fac,
-    paillier_blum_modulus as π_mod,
     rug::{Complete, Integer},
     IntegerExt,
 };
-use rand_core::{CryptoRng, RngCore};
+use rand_core::{CryptoRng, RngCore, SeedableRng};
 use round_based::ProtocolMessage;
 use round_based::{
     rounds_router::{simple_store::RoundInput, RoundsRouter},
@@ -21,16 +20,18 @@ use super::{Bug, KeyRefreshError, PregeneratedPrimes, ProtocolAborted};
 use crate::{
     errors::IoError,
     key_share::{
-        DirtyAuxInfo, DirtyIncompleteKeyShare, DirtyKeyInfo, KeyShare, PartyAux, Validate,
+        CrtParams, DirtyAuxInfo, DirtyIncompleteKeyShare, DirtyKeyInfo, KeyShare, PartyAux,
+        Validate,
     },
     progress::Tracer,
     security_level::SecurityLevel,
     utils,
     utils::{
-        but_nth, collect_blame, collect_simple_blame, iter_peers, scalar_to_bignumber, xor_array,
-        AbortBlame,
+        but_nth, collect_blame, collect_blame_par, collect_simple_blame,
+        collect_simple_blame_batch, iter_peers, scalar_to_bignumber, xor_array, AbortBlame,
+        Parallelism,
     },
-    zk::ring_pedersen_parameters as π_prm,
+    zk::{paillier_blum_modulus as π_mod, ring_pedersen_parameters as π_prm},
     ExecutionId, IncompleteKeyShare,
 };
 
@@ -59,6 +60,8 @@ pub struct MsgRound1<D: Digest> {
     /// $V_i$
     #[udigest(as_bytes)]
     pub commitment: digest::Output<D>,
+    /// Epoch of the signer's key share
+    pub epoch: u64,
 }
 /// Message from round 2
 #[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
@@ -80,8 +83,7 @@ pub struct MsgRound2<E: Curve, L: SecurityLevel> {
     #[udigest(with = utils::encoding::integer)]
     pub t: Integer,
     /// $\hat \psi_i$
-    // this should be L::M instead, but no rustc support yet
-    pub params_proof: π_prm::Proof<{ crate::security_level::M }>,
+    pub params_proof: π_prm::Proof,
     /// $\rho_i$
     // ideally it would be [u8; L::SECURITY_BYTES], but no rustc support yet
     #[serde(with = "hex")]
@@ -97,11 +99,7 @@ pub struct MsgRound2<E: Curve, L: SecurityLevel> {
 #[serde(bound = "")]
 pub struct MsgRound3<E: Curve> {
     /// $\psi_i$
-    // this should be L::M instead, but no rustc support yet
-    pub mod_proof: (
-        π_mod::Commitment,
-        π_mod::Proof<{ crate::security_level::M }>,
-    ),
+    pub mod_proof: π_mod::Proof,
     /// $\phi_i^j$
     pub fac_proof: π_fac::Proof,
     /// $C_i^j$
@@ -144,6 +142,7 @@ pub async fn run_refresh<R, M, E, L, D>(
     reliable_broadcast_enforced: bool,
     build_multiexp_tables: bool,
     build_crt: bool,
+    parallelism: &Parallelism,
     core_share: &DirtyIncompleteKeyShare<E>,
 ) -> Result<KeyShare<E, L>, KeyRefreshError>
 where
@@ -185,7 +184,7 @@ where
     tracer.round_begins();
 
     tracer.stage("Retrieve primes (p and q)");
-    let PregeneratedPrimes { p, q, .. } = pregenerated;
+    let (p, q) = pregenerated.split();
     tracer.stage("Compute paillier decryption key (N)");
     let N = (&p * &q).complete();
     let phi_N = (&p - 1u8).complete() * (&q - 1u8).complete();
@@ -221,6 +220,7 @@ where
     let hat_psi = π_prm::prove(
         parties_shared_state.clone().chain_update(i.to_be_bytes()),
         &mut rng,
+        L::M,
         π_prm::Data {
             N: &N,
             s: &s,
@@ -228,6 +228,7 @@ where
         },
         &phi_N,
         &lambda,
+        parallelism,
     )
     .map_err(Bug::PiPrm)?;
 
@@ -263,6 +264,7 @@ where
     tracer.send_msg();
     let commitment = MsgRound1 {
         commitment: hash_commit,
+        epoch: core_share.epoch,
     };
     outgoings
         .send(Outgoing::broadcast(Msg::Round1(commitment.clone())))
@@ -280,6 +282,14 @@ where
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
+    tracer.stage("Assert other parties hold key shares of the same epoch");
+    let blame = collect_simple_blame(&commitments, |commitment| {
+        commitment.epoch != core_share.epoch
+    });
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::mismatched_epoch(blame).into());
+    }
+
     // Optional reliability check
     if reliable_broadcast_enforced {
         tracer.stage("Hash received msgs (reliability check)");
@@ -351,26 +361,30 @@ where
     }
     // validate parameters and param_proofs
     tracer.stage("Validate П_prm (ψ_i)");
-    let blame = collect_blame(&decommitments, &decommitments, |j, d, _| {
-        if !crate::security_level::validate_public_paillier_key_size::<L>(&d.N) {
-            true
-        } else {
-            let data = π_prm::Data {
-                N: &d.N,
-                s: &d.s,
-                t: &d.t,
-            };
-            π_prm::verify(
-                parties_shared_state.clone().chain_update(j.to_be_bytes()),
-                data,
-                &d.params_proof,
-            )
-            .is_err()
-        }
+    let blame = collect_simple_blame(&decommitments, |d| {
+        !crate::security_level::validate_public_paillier_key_size::<L>(&d.N)
     });
     if !blame.is_empty() {
         return Err(ProtocolAborted::invalid_ring_pedersen_parameters(blame).into());
     }
+    let blame = collect_simple_blame_batch(
+        &decommitments,
+        |j, d| {
+            Some(π_prm::BatchItem {
+                shared_state: D::new_with_prefix(D::digest(sid)).chain_update(j.to_be_bytes()),
+                data: π_prm::Data {
+                    N: &d.N,
+                    s: &d.s,
+                    t: &d.t,
+                },
+                proof: &d.params_proof,
+            })
+        },
+        |items| π_prm::verify_batch(L::M, items, parallelism),
+    );
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::invalid_ring_pedersen_parameters(blame).into());
+    }
     // validate Xs add to zero
     tracer.stage("Validate X_i");
     let blame = collect_simple_blame(&decommitments, |d| {
@@ -395,13 +409,15 @@ where
         .fold(rho_bytes, xor_array);
 
     // common data for messages
-    let my_shared_state = parties_shared_state
-        .clone()
-        .chain_update(i.to_be_bytes())
-        .chain_update(&rho_bytes);
+    let shared_state = |j: u16| {
+        D::new_with_prefix(D::digest(sid))
+            .chain_update(j.to_be_bytes())
+            .chain_update(&rho_bytes)
+    };
     tracer.stage("Compute П_mod (ψ_i)");
-    let psi = π_mod::non_interactive::prove(
-        my_shared_state.clone(),
+    let psi = π_mod::prove(
+        shared_state(i),
+        L::M,
         &π_mod::Data { n: N.clone() },
         &π_mod::PrivateData {
             p: p.clone(),
@@ -434,43 +450,57 @@ where
         .zip(taus.iter())
         .map(|(x_j, secret_j)| schnorr_pok::prove(secret_j, &challenge, x_j))
         .collect::<Vec<_>>();
+    tracer.stage("Compute П_fac (ф_i^j)");
+    let fac_proofs_data = decommitments
+        .iter()
+        .map(|d| {
+            let mut seed = [0u8; 32];
+            rng.fill_bytes(&mut seed);
+            (d, seed)
+        })
+        .collect::<Vec<_>>();
+    let phis = parallelism
+        .map(&fac_proofs_data, |(d, seed)| {
+            π_fac::prove(
+                shared_state(i),
+                &π_fac::Aux {
+                    s: d.s.clone(),
+                    t: d.t.clone(),
+                    rsa_modulo: d.N.clone(),
+                    multiexp: None,
+                    crt: None,
+                },
+                π_fac::Data {
+                    n: &N,
+                    n_root: &n_sqrt,
+                },
+                π_fac::PrivateData { p: &p, q: &q },
+                &π_fac_security,
+                &mut rand_chacha::ChaCha20Rng::from_seed(*seed),
+            )
+        })
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Bug::PiFac)?;
+
     tracer.stage("Prepare auxiliary params and security level for proofs");
     // message to each party
     let iterator =
         // use every share except ours
         but_nth(i, xs.iter())
         .zip(&encs)
-        .zip(decommitments.iter())
+        .zip(phis)
         .zip(iter_peers(i, n));
-    for (((x, enc), d), j) in iterator {
+    for (((x, enc), phi), j) in iterator {
         tracer.stage("Paillier encryption of x_i^j");
         let (C, _) = enc
             .encrypt_with_random(&mut rng, &scalar_to_bignumber(x))
             .map_err(|_| Bug::PaillierEnc)?;
-        tracer.stage("Compute П_fac (ф_i^j)");
-        let phi = π_fac::prove(
-            my_shared_state.clone(),
-            &π_fac::Aux {
-                s: d.s.clone(),
-                t: d.t.clone(),
-                rsa_modulo: d.N.clone(),
-                multiexp: None,
-                crt: None,
-            },
-            π_fac::Data {
-                n: &N,
-                n_root: &n_sqrt,
-            },
-            π_fac::PrivateData { p: &p, q: &q },
-            &π_fac_security,
-            &mut rng,
-        )
-        .map_err(Bug::PiFac)?;
 
         tracer.send_msg();
         let msg = MsgRound3 {
             mod_proof: psi.clone(),
-            fac_proof: phi.clone(),
+            fac_proof: phi,
             sch_proofs_x: psis.clone(),
             C,
         };
@@ -572,24 +602,15 @@ where
 
     tracer.stage("Validate ψ_j (П_mod)");
     // verify mod proofs
-    let blame = collect_blame(
+    let blame = collect_blame_par(
+        parallelism,
         &decommitments,
         &shares_msg_b,
         |j, decommitment, proof_msg| {
             let data = π_mod::Data {
                 n: decommitment.N.clone(),
             };
-            let (comm, proof) = &proof_msg.mod_proof;
-            π_mod::non_interactive::verify(
-                parties_shared_state
-                    .clone()
-                    .chain_update(j.to_be_bytes())
-                    .chain_update(&rho_bytes),
-                &data,
-                comm,
-                proof,
-            )
-            .is_err()
+            π_mod::verify(shared_state(j), L::M, &data, &proof_msg.mod_proof).is_err()
         },
     );
     if !blame.is_empty() {
@@ -601,7 +622,7 @@ where
 
     // note: `crt` contains private information
     let crt = if build_crt {
-        Some(paillier_zk::fast_paillier::utils::CrtExp::build_n(&p, &q).ok_or(Bug::BuildCrt)?)
+        Some(CrtParams::new(&p, &q).map_err(Bug::BuildCrt)?)
     } else {
         None
     };
@@ -610,17 +631,15 @@ where
         t: t.clone(),
         rsa_modulo: N.clone(),
         multiexp: None,
-        crt: crt.clone(),
+        crt: crt.as_ref().and_then(CrtParams::crt_exp).cloned(),
     };
-    let blame = collect_blame(
+    let blame = collect_blame_par(
+        parallelism,
         &decommitments,
         &shares_msg_b,
         |j, decommitment, proof_msg| {
             π_fac::verify(
-                parties_shared_state
-                    .clone()
-                    .chain_update(j.to_be_bytes())
-                    .chain_update(&rho_bytes),
+                shared_state(j),
                 &phi_common_aux,
                 π_fac::Data {
                     n: &decommitment.N,
@@ -662,6 +681,11 @@ where
     let new_core_share: IncompleteKeyShare<E> = DirtyIncompleteKeyShare {
         key_info: DirtyKeyInfo {
             public_shares: X_stars,
+            epoch: old_core_share
+                .key_info
+                .epoch
+                .checked_add(1)
+                .ok_or(Bug::EpochOverflow)?,
             ..old_core_share.key_info
         },
         x: NonZero::from_secret_scalar(SecretScalar::new(&mut x_star)).ok_or(Bug::ZeroShare)?,
@@ -0,0 +1,672 @@
+use digest::Digest;
+use futures::SinkExt;
+use paillier_zk::{
+    no_small_factor::non_interactive as π_fac,
+    rug::{Complete, Integer},
+    IntegerExt,
+};
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use round_based::{
+    rounds_router::{simple_store::RoundInput, RoundsRouter},
+    Delivery, Mpc, MpcParty, Outgoing, ProtocolMessage,
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    errors::IoError,
+    key_share::{AuxInfo, CrtParams, DirtyAuxInfo, PartyAux, Validate},
+    progress::Tracer,
+    security_level::SecurityLevel,
+    utils,
+    utils::{
+        collect_blame, collect_blame_par, collect_simple_blame, collect_simple_blame_batch,
+        AbortBlame, Parallelism,
+    },
+    zk::{paillier_blum_modulus as π_mod, ring_pedersen_parameters as π_prm},
+    ExecutionId,
+};
+
+use super::{Bug, KeyRefreshError, PregeneratedPrimes, ProtocolAborted};
+
+/// Message of partial aux info refresh protocol
+#[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+#[allow(clippy::large_enum_variant)]
+pub enum Msg<D: Digest, L: SecurityLevel> {
+    /// Round 1 message
+    Round1(MsgRound1<D>),
+    /// Round 2 message
+    Round2(MsgRound2<L>),
+    /// Round 3 message
+    Round3(MsgRound3),
+    /// Reliability check message (optional additional round)
+    ReliabilityCheck(MsgReliabilityCheck<D>),
+}
+
+/// Message from round 1
+#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.partial_aux_refresh.round1")]
+#[udigest(bound = "")]
+#[serde(bound = "")]
+pub struct MsgRound1<D: Digest> {
+    /// Hash of the public aux info and the set of refreshing parties
+    ///
+    /// All parties must start the protocol with the same aux info.
+    #[udigest(as_bytes)]
+    pub aux_info_hash: digest::Output<D>,
+    /// $V_i$
+    #[udigest(as_bytes)]
+    pub commitment: digest::Output<D>,
+}
+/// Message from round 2
+#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.partial_aux_refresh.round2")]
+#[udigest(bound = "")]
+#[serde(bound = "")]
+pub struct MsgRound2<L: SecurityLevel> {
+    /// New aux data, present only if sender is refreshing its aux data
+    pub new_aux: Option<NewPartyAux>,
+    /// $\rho_i$
+    // ideally it would be [u8; L::SECURITY_BYTES], but no rustc support yet
+    #[serde(with = "hex")]
+    #[udigest(as_bytes)]
+    pub rho_bytes: L::Rid,
+    /// $u_i$
+    #[serde(with = "hex")]
+    #[udigest(as_bytes)]
+    pub decommit: L::Rid,
+}
+/// New public aux data of the refreshing party
+#[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.partial_aux_refresh.new_aux")]
+pub struct NewPartyAux {
+    /// $N_i$
+    #[udigest(with = utils::encoding::integer)]
+    pub N: Integer,
+    /// $s_i$
+    #[udigest(with = utils::encoding::integer)]
+    pub s: Integer,
+    /// $t_i$
+    #[udigest(with = utils::encoding::integer)]
+    pub t: Integer,
+    /// $\hat \psi_i$
+    pub params_proof: π_prm::Proof,
+}
+/// Unicast message of round 3, sent to each participant
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MsgRound3 {
+    /// Proofs of the new aux data, present only if sender is refreshing its aux data
+    pub proofs: Option<NewAuxProofs>,
+}
+/// Proofs that new $N_i$ is well-formed
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NewAuxProofs {
+    /// $\psi_i$
+    pub mod_proof: π_mod::Proof,
+    /// $\phi_i^j$
+    pub fac_proof: π_fac::Proof,
+}
+
+/// Message from an optional round that enforces reliability check
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct MsgReliabilityCheck<D: Digest>(pub digest::Output<D>);
+
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.partial_aux_refresh.tag")]
+enum Tag<'a> {
+    /// Tag that includes the prover index
+    Indexed {
+        party_index: u16,
+        #[udigest(as_bytes)]
+        sid: &'a [u8],
+    },
+    /// Tag w/o party index
+    Unindexed {
+        #[udigest(as_bytes)]
+        sid: &'a [u8],
+    },
+}
+
+/// Public aux info that parties agree on before starting the protocol
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.partial_aux_refresh.aux_info")]
+struct AuxInfoDigest<'a> {
+    refreshing: &'a [u16],
+    parties: Vec<PartyAuxDigest<'a>>,
+}
+#[derive(udigest::Digestable)]
+struct PartyAuxDigest<'a> {
+    #[udigest(with = utils::encoding::integer)]
+    N: &'a Integer,
+    #[udigest(with = utils::encoding::integer)]
+    s: &'a Integer,
+    #[udigest(with = utils::encoding::integer)]
+    t: &'a Integer,
+}
+
+/// Invalid arguments of partial aux info refresh
+#[derive(Debug, Error)]
+pub(super) enum InvalidArgs {
+    #[error("aux info must contain at least 2 parties")]
+    TooFewParties,
+    #[error("aux info contains too many parties")]
+    TooManyParties,
+    #[error("party index is out of bounds")]
+    PartyIndexOutOfBounds,
+    #[error("list of refreshing parties is empty")]
+    NoRefreshingParties,
+    #[error("refreshing party index is out of bounds")]
+    RefreshingPartyOutOfBounds,
+    #[error("list of refreshing parties contains duplicates")]
+    DuplicatedRefreshingParty,
+    #[error("pregenerated primes are required for the refreshing party")]
+    MissingPrimes,
+    #[error("pregenerated primes are provided, but party is not refreshing its aux info")]
+    UnexpectedPrimes,
+}
+
+/// Validates arguments, returns amount of parties
+pub(super) fn validate_args<L: SecurityLevel>(
+    i: u16,
+    aux_info: &AuxInfo<L>,
+    refreshing: &[u16],
+    pregenerated: Option<&PregeneratedPrimes<L>>,
+) -> Result<u16, InvalidArgs> {
+    let n = u16::try_from(aux_info.parties.len()).map_err(|_| InvalidArgs::TooManyParties)?;
+    if n < 2 {
+        return Err(InvalidArgs::TooFewParties);
+    }
+    if i >= n {
+        return Err(InvalidArgs::PartyIndexOutOfBounds);
+    }
+    if refreshing.is_empty() {
+        return Err(InvalidArgs::NoRefreshingParties);
+    }
+    if refreshing.iter().any(|&j| j >= n) {
+        return Err(InvalidArgs::RefreshingPartyOutOfBounds);
+    }
+    if (1..refreshing.len()).any(|k| refreshing[..k].contains(&refreshing[k])) {
+        return Err(InvalidArgs::DuplicatedRefreshingParty);
+    }
+    match (refreshing.contains(&i), pregenerated.is_some()) {
+        (true, false) => Err(InvalidArgs::MissingPrimes),
+        (false, true) => Err(InvalidArgs::UnexpectedPrimes),
+        _ => Ok(n),
+    }
+}
+
+/// Secret data of the refreshing party
+struct NewSecrets {
+    p: Integer,
+    q: Integer,
+}
+
+pub async fn run_partial_aux_refresh<R, M, L, D>(
+    i: u16,
+    n: u16,
+    mut rng: &mut R,
+    party: M,
+    execution_id: ExecutionId<'_>,
+    aux_info: &AuxInfo<L>,
+    refreshing: &[u16],
+    pregenerated: Option<PregeneratedPrimes<L>>,
+    mut tracer: Option<&mut dyn Tracer>,
+    reliable_broadcast_enforced: bool,
+    compute_multiexp_table: bool,
+    compute_crt: bool,
+    parallelism: &Parallelism,
+) -> Result<AuxInfo<L>, KeyRefreshError>
+where
+    R: RngCore + CryptoRng,
+    M: Mpc<ProtocolMessage = Msg<D, L>>,
+    L: SecurityLevel,
+    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+{
+    tracer.protocol_begins();
+
+    tracer.stage("Setup networking");
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incomings, mut outgoings) = delivery.split();
+
+    let mut rounds = RoundsRouter::<Msg<D, L>>::builder();
+    let round1 = rounds.add_round(RoundInput::<MsgRound1<D>>::broadcast(i, n));
+    let round1_sync = rounds.add_round(RoundInput::<MsgReliabilityCheck<D>>::broadcast(i, n));
+    let round2 = rounds.add_round(RoundInput::<MsgRound2<L>>::broadcast(i, n));
+    let round3 = rounds.add_round(RoundInput::<MsgRound3>::p2p(i, n));
+    let mut rounds = rounds.listen(incomings);
+
+    tracer.stage("Precompute execution id and shared state");
+    let sid = execution_id.as_bytes();
+    let tag = |j| {
+        udigest::Tag::<D>::new_structured(Tag::Indexed {
+            party_index: j,
+            sid,
+        })
+    };
+    let tag_i = tag(i);
+    let aux_info_hash =
+        udigest::Tag::<D>::new_structured(Tag::Unindexed { sid }).digest(AuxInfoDigest {
+            refreshing,
+            parties: aux_info
+                .parties
+                .iter()
+                .map(|aux_j| PartyAuxDigest {
+                    N: &aux_j.N,
+                    s: &aux_j.s,
+                    t: &aux_j.t,
+                })
+                .collect(),
+        });
+    let parties_shared_state = |j: u16| {
+        D::new_with_prefix(D::digest(sid))
+            .chain_update(aux_info_hash)
+            .chain_update(j.to_be_bytes())
+    };
+    let is_refreshing = |j: u16| refreshing.contains(&j);
+
+    // Round 1
+    tracer.round_begins();
+
+    let (new_secrets, new_aux) = if let Some(pregenerated) = pregenerated {
+        tracer.stage("Retrieve primes (p and q)");
+        let (p, q) = pregenerated.split();
+        tracer.stage("Compute paillier decryption key (N)");
+        let N = (&p * &q).complete();
+        let phi_N = (&p - 1u8).complete() * (&q - 1u8).complete();
+
+        tracer.stage("Generate auxiliary params r, λ, t, s");
+        let r = Integer::gen_invertible(&N, rng);
+        let lambda = phi_N
+            .random_below_ref(&mut utils::external_rand(rng))
+            .into();
+        let t = r.square().modulo(&N);
+        let s = t.pow_mod_ref(&lambda, &N).ok_or(Bug::PowMod)?.into();
+
+        tracer.stage("Prove Πprm (ψˆ_i)");
+        let hat_psi = π_prm::prove(
+            parties_shared_state(i),
+            &mut rng,
+            L::M,
+            π_prm::Data {
+                N: &N,
+                s: &s,
+                t: &t,
+            },
+            &phi_N,
+            &lambda,
+            parallelism,
+        )
+        .map_err(Bug::PiPrm)?;
+
+        (
+            Some(NewSecrets { p, q }),
+            Some(NewPartyAux {
+                N,
+                s,
+                t,
+                params_proof: hat_psi,
+            }),
+        )
+    } else {
+        (None, None)
+    };
+
+    tracer.stage("Sample random bytes");
+    // rho_i in paper, this signer's share of bytes
+    let mut rho_bytes = L::Rid::default();
+    rng.fill_bytes(rho_bytes.as_mut());
+
+    tracer.stage("Compute hash commitment and sample decommitment");
+    // V_i and u_i in paper
+    let decommitment = MsgRound2::<L> {
+        new_aux,
+        rho_bytes: rho_bytes.clone(),
+        decommit: {
+            let mut nonce = L::Rid::default();
+            rng.fill_bytes(nonce.as_mut());
+            nonce
+        },
+    };
+    let hash_commit = tag_i.clone().digest(&decommitment);
+
+    tracer.send_msg();
+    let commitment = MsgRound1 {
+        aux_info_hash,
+        commitment: hash_commit,
+    };
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round1(commitment.clone())))
+        .await
+        .map_err(IoError::send_message)?;
+    tracer.msg_sent();
+
+    // Round 2
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let commitments = rounds
+        .complete(round1)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    tracer.stage("Validate that parties have the same aux info");
+    let blame = collect_simple_blame(&commitments, |msg| msg.aux_info_hash != aux_info_hash);
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::mismatched_aux_info(blame).into());
+    }
+
+    // Optional reliability check
+    if reliable_broadcast_enforced {
+        tracer.stage("Hash received msgs (reliability check)");
+        let h_i = udigest::Tag::<D>::new_structured(Tag::Unindexed { sid })
+            .digest_iter(commitments.iter_including_me(&commitment));
+
+        tracer.send_msg();
+        outgoings
+            .send(Outgoing::broadcast(Msg::ReliabilityCheck(
+                MsgReliabilityCheck(h_i),
+            )))
+            .await
+            .map_err(IoError::send_message)?;
+        tracer.msg_sent();
+
+        tracer.round_begins();
+
+        tracer.receive_msgs();
+        let hashes = rounds
+            .complete(round1_sync)
+            .await
+            .map_err(IoError::receive_message)?;
+        tracer.msgs_received();
+
+        tracer.stage("Assert other parties hashed messages (reliability check)");
+        let parties_have_different_hashes = hashes
+            .into_iter_indexed()
+            .filter(|(_j, _msg_id, h_j)| h_i != h_j.0)
+            .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
+            .collect::<Vec<_>>();
+        if !parties_have_different_hashes.is_empty() {
+            return Err(ProtocolAborted::round1_not_reliable(parties_have_different_hashes).into());
+        }
+    }
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round2(decommitment.clone())))
+        .await
+        .map_err(IoError::send_message)?;
+    tracer.msg_sent();
+
+    // Round 3
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let decommitments = rounds
+        .complete(round2)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    // validate decommitments
+    tracer.stage("Validate round 1 decommitments");
+    let blame = collect_blame(&decommitments, &commitments, |j, decomm, comm| {
+        tag(j).digest(decomm) != comm.commitment
+    });
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::invalid_decommitment(blame).into());
+    }
+    tracer.stage("Validate that only refreshing parties sent new aux data");
+    let blame = collect_blame(&decommitments, &decommitments, |j, d, _| {
+        is_refreshing(j) != d.new_aux.is_some()
+    });
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::invalid_data_size(blame).into());
+    }
+    // validate parameters and param_proofs
+    tracer.stage("Validate П_prm (ψ_i)");
+    let blame = collect_simple_blame(&decommitments, |d| {
+        d.new_aux.as_ref().is_some_and(|new_aux| {
+            !crate::security_level::validate_public_paillier_key_size::<L>(&new_aux.N)
+        })
+    });
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::invalid_ring_pedersen_parameters(blame).into());
+    }
+    let blame = collect_simple_blame_batch(
+        &decommitments,
+        |j, d| {
+            let new_aux = d.new_aux.as_ref()?;
+            Some(π_prm::BatchItem {
+                shared_state: parties_shared_state(j),
+                data: π_prm::Data {
+                    N: &new_aux.N,
+                    s: &new_aux.s,
+                    t: &new_aux.t,
+                },
+                proof: &new_aux.params_proof,
+            })
+        },
+        |items| π_prm::verify_batch(L::M, items, parallelism),
+    );
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::invalid_ring_pedersen_parameters(blame).into());
+    }
+
+    tracer.stage("Add together shared random bytes");
+    // rho in paper, collective random bytes
+    let rho_bytes = decommitments
+        .iter()
+        .map(|d| &d.rho_bytes)
+        .fold(rho_bytes, utils::xor_array);
+
+    tracer.stage("Assemble updated public aux data");
+    let mut party_auxes = aux_info.parties.clone();
+    for (j, d) in decommitments.iter_including_me(&decommitment).enumerate() {
+        if let Some(new_aux) = &d.new_aux {
+            party_auxes[j] = PartyAux {
+                N: new_aux.N.clone(),
+                s: new_aux.s.clone(),
+                t: new_aux.t.clone(),
+                multiexp: None,
+                crt: None,
+            };
+        }
+    }
+
+    // common data for messages
+    let shared_state = |j: u16| parties_shared_state(j).chain_update(&rho_bytes);
+    tracer.stage("Assemble security params for П_fac (ф_i)");
+    let π_fac_security = π_fac::SecurityParams {
+        l: L::ELL,
+        epsilon: L::EPSILON,
+        q: L::q(),
+    };
+
+    if let Some(NewSecrets { p, q, .. }) = &new_secrets {
+        let N = &party_auxes[usize::from(i)].N;
+
+        tracer.stage("Compute П_mod (ψ_i)");
+        let psi = π_mod::prove(
+            shared_state(i),
+            L::M,
+            &π_mod::Data { n: N.clone() },
+            &π_mod::PrivateData {
+                p: p.clone(),
+                q: q.clone(),
+            },
+            &mut rng,
+        )
+        .map_err(Bug::PiMod)?;
+        let n_sqrt = utils::sqrt(N);
+
+        tracer.stage("Compute П_fac (ф_i^j)");
+        let fac_proofs_data = decommitments
+            .iter_indexed()
+            .map(|(j, _, _)| {
+                let mut seed = [0u8; 32];
+                rng.fill_bytes(&mut seed);
+                (&party_auxes[usize::from(j)], seed)
+            })
+            .collect::<Vec<_>>();
+        let phis = parallelism
+            .map(&fac_proofs_data, |(aux_j, seed)| {
+                π_fac::prove(
+                    shared_state(i),
+                    &π_fac::Aux {
+                        s: aux_j.s.clone(),
+                        t: aux_j.t.clone(),
+                        rsa_modulo: aux_j.N.clone(),
+                        multiexp: None,
+                        crt: None,
+                    },
+                    π_fac::Data {
+                        n: N,
+                        n_root: &n_sqrt,
+                    },
+                    π_fac::PrivateData { p, q },
+                    &π_fac_security,
+                    &mut rand_chacha::ChaCha20Rng::from_seed(*seed),
+                )
+            })
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Bug::PiFac)?;
+
+        // message to each party
+        for ((j, _, _), phi) in decommitments.iter_indexed().zip(phis) {
+            tracer.send_msg();
+            let msg = MsgRound3 {
+                proofs: Some(NewAuxProofs {
+                    mod_proof: psi.clone(),
+                    fac_proof: phi,
+                }),
+            };
+            outgoings
+                .send(Outgoing::p2p(j, Msg::Round3(msg)))
+                .await
+                .map_err(IoError::send_message)?;
+            tracer.msg_sent();
+        }
+    } else {
+        for (j, _, _) in decommitments.iter_indexed() {
+            tracer.send_msg();
+            outgoings
+                .send(Outgoing::p2p(j, Msg::Round3(MsgRound3 { proofs: None })))
+                .await
+                .map_err(IoError::send_message)?;
+            tracer.msg_sent();
+        }
+    }
+
+    // Output
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let proofs_msgs = rounds
+        .complete(round3)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    tracer.stage("Validate that only refreshing parties sent proofs");
+    let blame = collect_blame(&decommitments, &proofs_msgs, |j, _, proof_msg| {
+        is_refreshing(j) != proof_msg.proofs.is_some()
+    });
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::invalid_data_size(blame).into());
+    }
+
+    tracer.stage("Validate ψ_j (П_mod)");
+    // verify mod proofs
+    let blame = collect_blame_par(
+        parallelism,
+        &decommitments,
+        &proofs_msgs,
+        |j, decommitment, proof_msg| {
+            let (Some(new_aux), Some(proofs)) = (&decommitment.new_aux, &proof_msg.proofs) else {
+                return false;
+            };
+            let data = π_mod::Data {
+                n: new_aux.N.clone(),
+            };
+            π_mod::verify(shared_state(j), L::M, &data, &proofs.mod_proof).is_err()
+        },
+    );
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::invalid_mod_proof(blame).into());
+    }
+
+    tracer.stage("Validate ф_j (П_fac)");
+    // verify fac proofs
+    let (p, q) = match new_secrets {
+        Some(NewSecrets { p, q }) => (p, q),
+        None => (aux_info.p.clone(), aux_info.q.clone()),
+    };
+    let crt = match &aux_info.parties[usize::from(i)].crt {
+        // CRT of the party that doesn't refresh aux data remains valid
+        Some(crt) if !is_refreshing(i) => Some(crt.clone()),
+        // note: `crt` contains private information
+        _ if compute_crt => Some(CrtParams::new(&p, &q).map_err(Bug::BuildCrt)?),
+        _ => None,
+    };
+    let my_aux = &party_auxes[usize::from(i)];
+    let phi_common_aux = π_fac::Aux {
+        s: my_aux.s.clone(),
+        t: my_aux.t.clone(),
+        rsa_modulo: my_aux.N.clone(),
+        multiexp: None,
+        crt: crt.as_ref().and_then(CrtParams::crt_exp).cloned(),
+    };
+    let blame = collect_blame_par(
+        parallelism,
+        &decommitments,
+        &proofs_msgs,
+        |j, decommitment, proof_msg| {
+            let (Some(new_aux), Some(proofs)) = (&decommitment.new_aux, &proof_msg.proofs) else {
+                return false;
+            };
+            π_fac::verify(
+                shared_state(j),
+                &phi_common_aux,
+                π_fac::Data {
+                    n: &new_aux.N,
+                    n_root: &utils::sqrt(&new_aux.N),
+                },
+                &π_fac_security,
+                &proofs.fac_proof,
+            )
+            .is_err()
+        },
+    );
+    if !blame.is_empty() {
+        return Err(ProtocolAborted::invalid_fac_proof(blame).into());
+    }
+
+    // verifications passed, compute updated aux info
+
+    tracer.stage("Assemble auxiliary info");
+    party_auxes[usize::from(i)].crt = crt;
+    let mut aux = DirtyAuxInfo {
+        p,
+        q,
+        parties: party_auxes,
+        security_level: std::marker::PhantomData,
+    };
+
+    if compute_multiexp_table {
+        tracer.stage("Precompute multiexp tables");
+
+        aux.precompute_multiexp_tables()
+            .map_err(Bug::BuildMultiexpTables)?;
+    }
+
+    let aux = aux
+        .validate()
+        .map_err(|err| Bug::InvalidShareGenerated(err.into_error()))?;
+
+    tracer.protocol_ends();
+    Ok(aux)
+}
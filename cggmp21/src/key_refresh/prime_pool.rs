@@ -0,0 +1,739 @@
+//! Background generation of safe primes
+//!
+//! Generating [`PregeneratedPrimes`] takes a lot of time, which is why it's usually done ahead
+//! of time. [`PrimePool`] is a service that generates safe primes on background threads and
+//! keeps up to a target amount of [`PregeneratedPrimes`] ready to be used in aux info
+//! generation or key refresh.
+//!
+//! Each pair of primes is handed out by the pool exactly once: it's removed from the pool
+//! (and from the disk, if the pool is persistent) before being returned. Primes must never
+//! be reused in another protocol execution, as they form a secret Paillier key.
+//!
+//! ## Persistence
+//! Pool can be persisted to the disk via [`PrimePoolBuilder::persist_to`]. Primes are stored
+//! encrypted with ChaCha20-Poly1305 under the key provided by the caller. File is rewritten
+//! atomically every time the pool changes, and it's loaded back when the pool is started,
+//! so generated primes survive restarts.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use cggmp21::key_refresh::prime_pool::{CancellationToken, PrimePool};
+//! use cggmp21::security_level::SecurityLevel128;
+//! # fn main() -> Result<(), cggmp21::key_refresh::prime_pool::PrimePoolError> {
+//! let mut rng = rand::rngs::OsRng;
+//! let storage_key: [u8; 32] = [0; 32]; // must be a secret key, e.g. obtained from KMS
+//!
+//! let cancel = CancellationToken::new();
+//! let pool = PrimePool::<SecurityLevel128>::builder(10)
+//!     .persist_to("primes.bin", storage_key)
+//!     .set_cancellation_token(cancel.clone())
+//!     .on_progress(|progress| println!("{}/{} primes ready", progress.available, progress.target))
+//!     .start(&mut rng)?;
+//!
+//! // Later, when aux info generation is about to start
+//! let pregenerated_primes = pool.wait_take()?;
+//! # Ok(()) }
+//! ```
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::ChaCha20Poly1305;
+use rand_chacha::ChaCha20Rng;
+use rand_core::{CryptoRng, RngCore, SeedableRng};
+use thiserror::Error;
+use zeroize::Zeroizing;
+
+use crate::rug::{integer::Order, Assign, Integer};
+use crate::security_level::SecurityLevel;
+use crate::utils;
+
+use super::PregeneratedPrimes;
+
+/// How often the threads blocked on the pool check whether it was cancelled
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Amount of small primes used to sieve candidates, similar to [`fast_paillier::utils::generate_safe_prime`]
+const SIEVE_SIZE: usize = 135;
+/// Version of the pool file format
+const FILE_VERSION: u8 = 1;
+
+/// Token that can be used to stop the [`PrimePool`]
+///
+/// Once cancelled, pool stops generating primes, and blocked [`PrimePool::wait_take`]
+/// calls return an error. Primes that are already generated are kept and can still be taken
+/// via [`PrimePool::take`].
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Constructs a new token
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancels the pool
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed)
+    }
+
+    /// Checks whether the token was cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// State of the pool reported to the progress callback
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Amount of pairs of primes ready to be taken
+    pub available: usize,
+    /// Amount of pairs of primes the pool is trying to maintain
+    pub target: usize,
+}
+
+type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
+
+/// Builds a [`PrimePool`]
+///
+/// Obtained via [`PrimePool::builder`]
+pub struct PrimePoolBuilder<L> {
+    target: usize,
+    threads: Option<usize>,
+    storage: Option<Storage>,
+    cancel: CancellationToken,
+    on_progress: Option<ProgressCallback>,
+    _security_level: std::marker::PhantomData<L>,
+}
+
+impl<L: SecurityLevel> PrimePoolBuilder<L> {
+    /// Sets amount of background threads generating primes
+    ///
+    /// By default, it's equal to [available parallelism](std::thread::available_parallelism)
+    pub fn set_threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    /// Persists the pool to the file at given path
+    ///
+    /// File is encrypted with a `key`. If the file already exists, primes stored in it are
+    /// loaded into the pool on [start](Self::start).
+    pub fn persist_to(mut self, path: impl Into<PathBuf>, key: [u8; 32]) -> Self {
+        self.storage = Some(Storage {
+            path: path.into(),
+            cipher: ChaCha20Poly1305::new(&key.into()),
+            written: Mutex::new(0),
+        });
+        self
+    }
+
+    /// Sets a token that can be used to stop the pool
+    pub fn set_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Sets a callback which is called every time the pool changes
+    ///
+    /// Callback is called from the background threads, as well as from the thread taking
+    /// primes from the pool, so it should not block.
+    pub fn on_progress(mut self, callback: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(callback));
+        self
+    }
+
+    /// Loads the persisted primes (if any) and starts background threads
+    ///
+    /// `rng` is only used to seed the background threads' randomness sources.
+    pub fn start<R: RngCore + CryptoRng>(
+        self,
+        rng: &mut R,
+    ) -> Result<PrimePool<L>, PrimePoolError> {
+        let primes = match &self.storage {
+            Some(storage) => storage.load::<L>()?,
+            None => VecDeque::new(),
+        };
+        let threads = match self.threads {
+            Some(threads) => threads,
+            None => std::thread::available_parallelism()
+                .map(usize::from)
+                .unwrap_or(1),
+        };
+
+        let inner = Arc::new(Inner {
+            state: Mutex::new(State {
+                primes,
+                generation: 0,
+                in_progress: 0,
+                nonce_rng: ChaCha20Rng::from_seed(rng.gen_seed()),
+                background_error: None,
+            }),
+            changed: Condvar::new(),
+            target: self.target,
+            storage: self.storage,
+            cancel: self.cancel,
+            stopped: AtomicBool::new(false),
+            on_progress: self.on_progress,
+            _security_level: std::marker::PhantomData,
+        });
+
+        let workers = (0..threads)
+            .map(|_| {
+                let inner = inner.clone();
+                let rng = ChaCha20Rng::from_seed(rng.gen_seed());
+                std::thread::spawn(move || inner.work(rng))
+            })
+            .collect();
+
+        Ok(PrimePool { inner, workers })
+    }
+}
+
+/// Pool of safe primes generated in the background
+///
+/// Background threads are stopped when the pool is dropped. See [module-level docs](self)
+/// for more details.
+pub struct PrimePool<L = crate::default_choice::SecurityLevel> {
+    inner: Arc<Inner<L>>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl<L: SecurityLevel> PrimePool<L> {
+    /// Constructs a builder of the pool that maintains `target` pairs of primes
+    pub fn builder(target: usize) -> PrimePoolBuilder<L> {
+        PrimePoolBuilder {
+            target,
+            threads: None,
+            storage: None,
+            cancel: CancellationToken::new(),
+            on_progress: None,
+            _security_level: std::marker::PhantomData,
+        }
+    }
+
+    /// Takes a pair of primes from the pool, if any is available
+    ///
+    /// Returned primes are removed from the pool. If the pool is persistent, they're
+    /// also removed from the disk before being returned.
+    pub fn take(&self) -> Result<Option<PregeneratedPrimes<L>>, PrimePoolError> {
+        let taken = self.inner.pop(&mut self.inner.lock())?;
+        taken.map(|taken| self.inner.persist(taken)).transpose()
+    }
+
+    /// Takes a pair of primes from the pool, blocks until one is available
+    ///
+    /// Returns an error if the pool is cancelled before primes become available.
+    pub fn wait_take(&self) -> Result<PregeneratedPrimes<L>, PrimePoolError> {
+        let mut state = self.inner.lock();
+        loop {
+            if let Some(taken) = self.inner.pop(&mut state)? {
+                drop(state);
+                return self.inner.persist(taken);
+            }
+            if self.inner.is_stopped() {
+                return Err(Reason::Cancelled.into());
+            }
+            state = self
+                .inner
+                .changed
+                .wait_timeout(state, CANCELLATION_POLL_INTERVAL)
+                .unwrap_or_else(|e| e.into_inner())
+                .0;
+        }
+    }
+
+    /// Returns current state of the pool
+    pub fn progress(&self) -> Progress {
+        self.inner.progress(&self.inner.lock())
+    }
+
+    /// Stops background threads
+    ///
+    /// Same as cancelling the [token](PrimePoolBuilder::set_cancellation_token)
+    pub fn cancel(&self) {
+        self.inner.cancel.cancel();
+        self.inner.changed.notify_all();
+    }
+}
+
+impl<L> Drop for PrimePool<L> {
+    fn drop(&mut self) {
+        self.inner.stopped.store(true, Ordering::Relaxed);
+        self.inner.changed.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+struct Inner<L> {
+    state: Mutex<State>,
+    /// Notified every time the pool changes
+    changed: Condvar,
+    target: usize,
+    storage: Option<Storage>,
+    cancel: CancellationToken,
+    /// Set when the pool is dropped
+    stopped: AtomicBool,
+    on_progress: Option<ProgressCallback>,
+    _security_level: std::marker::PhantomData<fn() -> L>,
+}
+
+struct State {
+    primes: VecDeque<(Integer, Integer)>,
+    /// Incremented every time the primes are changed, used to order writes to the disk
+    generation: u64,
+    /// Amount of pairs that are being generated at the moment
+    in_progress: usize,
+    /// Used to generate nonces for the file encryption
+    nonce_rng: ChaCha20Rng,
+    /// Error that occurred while persisting primes generated in the background
+    background_error: Option<std::io::Error>,
+}
+
+impl Drop for State {
+    fn drop(&mut self) {
+        for (p, q) in &mut self.primes {
+            utils::zeroize_integer(p);
+            utils::zeroize_integer(q);
+        }
+    }
+}
+
+/// Pair of primes removed from the pool, but not yet handed out
+struct Taken {
+    primes: (Integer, Integer),
+    /// Snapshot of the pool without the taken primes (if the pool is persistent)
+    snapshot: Option<Snapshot>,
+    progress: Progress,
+}
+
+impl<L: SecurityLevel> Inner<L> {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.cancel.is_cancelled() || self.stopped.load(Ordering::Relaxed)
+    }
+
+    fn progress(&self, state: &State) -> Progress {
+        Progress {
+            available: state.primes.len(),
+            target: self.target,
+        }
+    }
+
+    fn report(&self, progress: Progress) {
+        if let Some(on_progress) = &self.on_progress {
+            on_progress(progress)
+        }
+    }
+
+    /// Removes a pair of primes from the pool
+    ///
+    /// Primes must not be handed out until they're [persisted](Self::persist) as removed
+    fn pop(&self, state: &mut State) -> Result<Option<Taken>, PrimePoolError> {
+        if let Some(err) = state.background_error.take() {
+            return Err(Reason::Io(err).into());
+        }
+        let Some(primes) = state.primes.pop_front() else {
+            return Ok(None);
+        };
+        let snapshot = match &self.storage {
+            Some(storage) => match storage.snapshot::<L>(state) {
+                Ok(snapshot) => Some(snapshot),
+                Err(err) => {
+                    state.primes.push_front(primes);
+                    return Err(err);
+                }
+            },
+            None => None,
+        };
+        Ok(Some(Taken {
+            primes,
+            snapshot,
+            progress: self.progress(state),
+        }))
+    }
+
+    /// Writes the pool without the taken primes to the disk, and hands out the primes
+    ///
+    /// Must be called without holding the lock. If write fails, primes are returned back to the pool.
+    fn persist(&self, taken: Taken) -> Result<PregeneratedPrimes<L>, PrimePoolError> {
+        let Taken {
+            primes,
+            snapshot,
+            progress,
+        } = taken;
+        if let (Some(storage), Some(snapshot)) = (&self.storage, snapshot) {
+            if let Err(err) = storage.write(snapshot) {
+                self.lock().primes.push_front(primes);
+                return Err(err);
+            }
+        }
+
+        self.changed.notify_all();
+        self.report(progress);
+        let (p, q) = primes;
+        Ok(PregeneratedPrimes {
+            p,
+            q,
+            _phantom: std::marker::PhantomData,
+        })
+    }
+
+    /// Background thread generating primes until the pool is stopped
+    fn work(&self, mut rng: ChaCha20Rng) {
+        let sieve = small_primes(SIEVE_SIZE);
+        let bits = 4 * L::SECURITY_BITS;
+        loop {
+            {
+                let mut state = self.lock();
+                while !self.is_stopped() && state.primes.len() + state.in_progress >= self.target {
+                    state = self
+                        .changed
+                        .wait_timeout(state, CANCELLATION_POLL_INTERVAL)
+                        .unwrap_or_else(|e| e.into_inner())
+                        .0;
+                }
+                if self.is_stopped() {
+                    return;
+                }
+                state.in_progress += 1;
+            }
+
+            let primes = generate_safe_prime(&mut rng, bits, &sieve, || self.is_stopped())
+                .and_then(|mut p| {
+                    match generate_safe_prime(&mut rng, bits, &sieve, || self.is_stopped()) {
+                        Some(q) => Some((p, q)),
+                        None => {
+                            // Pool was stopped after `p` was found, it must not stay in memory
+                            utils::zeroize_integer(&mut p);
+                            None
+                        }
+                    }
+                });
+
+            let mut state = self.lock();
+            state.in_progress -= 1;
+            let Some(primes) = primes else {
+                return;
+            };
+            state.primes.push_back(primes);
+            let snapshot = self
+                .storage
+                .as_ref()
+                .map(|storage| storage.snapshot::<L>(&mut state));
+            let progress = self.progress(&state);
+            drop(state);
+
+            if let (Some(storage), Some(snapshot)) = (&self.storage, snapshot) {
+                if let Err(err) = snapshot.and_then(|snapshot| storage.write(snapshot)) {
+                    // Primes are kept in memory, error is reported on the next `take`
+                    self.lock().background_error = Some(match err.0 {
+                        Reason::Io(err) => err,
+                        err => std::io::Error::other(err),
+                    });
+                }
+            }
+            self.changed.notify_all();
+            self.report(progress);
+        }
+    }
+}
+
+/// Encrypted file containing the pool
+///
+/// File consists of 12 bytes nonce followed by ciphertext. Plaintext is a version byte,
+/// amount of pairs, and the pairs, each prime encoded as length-prefixed big-endian bytes.
+/// All integers are u32 big-endian. Security level is bound as associated data, so the
+/// primes can't be loaded into a pool of another security level.
+struct Storage {
+    path: PathBuf,
+    cipher: ChaCha20Poly1305,
+    /// Generation of the last snapshot written to the disk
+    ///
+    /// Also ensures that only one thread writes the file at a time.
+    written: Mutex<u64>,
+}
+
+/// Encrypted file content, ready to be written to the disk
+struct Snapshot {
+    generation: u64,
+    file: Vec<u8>,
+}
+
+impl Storage {
+    fn associated_data(security_bits: u32) -> Vec<u8> {
+        let mut ad = b"dfns.cggmp21.prime_pool".to_vec();
+        ad.extend_from_slice(&security_bits.to_be_bytes());
+        ad
+    }
+
+    fn load<L: SecurityLevel>(&self) -> Result<VecDeque<(Integer, Integer)>, PrimePoolError> {
+        let file = match std::fs::read(&self.path) {
+            Ok(file) => file,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(VecDeque::new()),
+            Err(err) => return Err(Reason::Io(err).into()),
+        };
+        if file.len() < 12 {
+            return Err(Reason::Malformed.into());
+        }
+        let (nonce, ciphertext) = file.split_at(12);
+        let plaintext = Zeroizing::new(
+            self.cipher
+                .decrypt(
+                    nonce.into(),
+                    Payload {
+                        msg: ciphertext,
+                        aad: &Self::associated_data(L::SECURITY_BITS),
+                    },
+                )
+                .map_err(|_| Reason::Decrypt)?,
+        );
+
+        let mut reader = Reader(&plaintext);
+        if reader.read_bytes(1)? != [FILE_VERSION] {
+            return Err(Reason::UnsupportedVersion.into());
+        }
+        let amount = reader.read_u32()?;
+        let mut primes = VecDeque::new();
+        for _ in 0..amount {
+            let p = reader.read_integer()?;
+            let q = reader.read_integer()?;
+            if !crate::security_level::validate_secret_paillier_key_size::<L>(&p, &q) {
+                return Err(Reason::InvalidPrimes.into());
+            }
+            primes.push_back((p, q));
+        }
+        if !reader.0.is_empty() {
+            return Err(Reason::Malformed.into());
+        }
+        Ok(primes)
+    }
+
+    /// Encrypts the primes from the `state`
+    ///
+    /// Called under the lock, so snapshots are ordered the same way as changes of the pool.
+    /// Snapshot is [written](Self::write) to the disk after the lock is released.
+    fn snapshot<L: SecurityLevel>(&self, state: &mut State) -> Result<Snapshot, PrimePoolError> {
+        state.generation += 1;
+        let mut plaintext = Zeroizing::new(vec![FILE_VERSION]);
+        let amount = u32::try_from(state.primes.len()).map_err(|_| Reason::TooManyPrimes)?;
+        plaintext.extend_from_slice(&amount.to_be_bytes());
+        for (p, q) in &state.primes {
+            for x in [p, q] {
+                let bytes = Zeroizing::new(x.to_digits::<u8>(Order::Msf));
+                let len = u32::try_from(bytes.len()).map_err(|_| Reason::TooManyPrimes)?;
+                plaintext.extend_from_slice(&len.to_be_bytes());
+                plaintext.extend_from_slice(&bytes);
+            }
+        }
+
+        let mut nonce = [0u8; 12];
+        state.nonce_rng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &nonce.into(),
+                Payload {
+                    msg: &plaintext,
+                    aad: &Self::associated_data(L::SECURITY_BITS),
+                },
+            )
+            .map_err(|_| Reason::Encrypt)?;
+        let mut file = nonce.to_vec();
+        file.extend_from_slice(&ciphertext);
+        Ok(Snapshot {
+            generation: state.generation,
+            file,
+        })
+    }
+
+    /// Atomically overwrites the file with the snapshot
+    ///
+    /// Does nothing if a newer snapshot is already written: it doesn't contain primes that
+    /// were taken before this snapshot was made either.
+    fn write(&self, snapshot: Snapshot) -> Result<(), PrimePoolError> {
+        let mut written = self.written.lock().unwrap_or_else(|e| e.into_inner());
+        if *written >= snapshot.generation {
+            return Ok(());
+        }
+        let tmp_path = tmp_path(&self.path);
+        std::fs::write(&tmp_path, &snapshot.file).map_err(Reason::Io)?;
+        std::fs::rename(&tmp_path, &self.path).map_err(Reason::Io)?;
+        *written = snapshot.generation;
+        Ok(())
+    }
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_owned();
+    file_name.push(".tmp");
+    path.with_file_name(file_name)
+}
+
+struct Reader<'b>(&'b [u8]);
+
+impl<'b> Reader<'b> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'b [u8], PrimePoolError> {
+        if self.0.len() < len {
+            return Err(Reason::Malformed.into());
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, PrimePoolError> {
+        let bytes = self.read_bytes(4)?;
+        Ok(u32::from_be_bytes(
+            bytes.try_into().map_err(|_| Reason::Malformed)?,
+        ))
+    }
+
+    fn read_integer(&mut self) -> Result<Integer, PrimePoolError> {
+        let len = self.read_u32()?;
+        let len = usize::try_from(len).map_err(|_| Reason::Malformed)?;
+        Ok(Integer::from_digits(self.read_bytes(len)?, Order::Msf))
+    }
+}
+
+/// Generates a random safe prime of `bits` size, returns `None` if `cancelled` before
+/// prime was found
+///
+/// Same algorithm as [`fast_paillier::utils::generate_safe_prime`], but can be interrupted.
+fn generate_safe_prime(
+    rng: &mut impl RngCore,
+    bits: u32,
+    sieve: &[u32],
+    cancelled: impl Fn() -> bool,
+) -> Option<Integer> {
+    use crate::rug::integer::IsPrime;
+
+    let mut rng = crate::fast_paillier::utils::external_rand(rng);
+    let mut x = Integer::new();
+
+    let prime = 'trial: loop {
+        if cancelled() {
+            break None;
+        }
+
+        // generate an odd number of length `bits - 2`
+        x.assign(Integer::random_bits(bits - 1, &mut rng));
+        x.set_bit(bits - 2, true);
+        x |= 1u32;
+
+        // discard `x` if `2x + 1` is divisible by any of small primes
+        for &small_prime in sieve {
+            if x.mod_u(small_prime) == (small_prime - 1) / 2 {
+                continue 'trial;
+            }
+        }
+
+        if let IsPrime::Yes | IsPrime::Probably = x.is_probably_prime(25) {
+            let mut p = (x.clone() << 1u32) + 1u32;
+            if let IsPrime::Yes | IsPrime::Probably = p.is_probably_prime(25) {
+                break Some(p);
+            }
+            utils::zeroize_integer(&mut p);
+        }
+    };
+    // Last candidate is either a factor of the prime, or it might become one if it's
+    // close to the prime that's found later
+    utils::zeroize_integer(&mut x);
+    prime
+}
+
+/// Returns first `amount` odd primes
+// `u32::is_multiple_of` requires Rust 1.87
+#[allow(unknown_lints, clippy::manual_is_multiple_of)]
+fn small_primes(amount: usize) -> Vec<u32> {
+    let mut primes = Vec::with_capacity(amount);
+    let mut candidate = 3u32;
+    while primes.len() < amount {
+        if primes
+            .iter()
+            .take_while(|&&p| p * p <= candidate)
+            .all(|&p| candidate % p != 0)
+        {
+            primes.push(candidate)
+        }
+        candidate += 2;
+    }
+    primes
+}
+
+trait GenSeed {
+    fn gen_seed(&mut self) -> [u8; 32];
+}
+
+impl<R: RngCore> GenSeed for R {
+    fn gen_seed(&mut self) -> [u8; 32] {
+        let mut seed = [0u8; 32];
+        self.fill_bytes(&mut seed);
+        seed
+    }
+}
+
+/// Error of the [`PrimePool`]
+#[derive(Debug, Error)]
+#[error("prime pool error")]
+pub struct PrimePoolError(#[source] Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("i/o error")]
+    Io(#[source] std::io::Error),
+    #[error("couldn't decrypt the pool file: wrong key or file is corrupted")]
+    Decrypt,
+    #[error("couldn't encrypt the pool")]
+    Encrypt,
+    #[error("pool file is malformed")]
+    Malformed,
+    #[error("pool file has unsupported version")]
+    UnsupportedVersion,
+    #[error("pool file contains primes that don't meet security level")]
+    InvalidPrimes,
+    #[error("too many primes in the pool")]
+    TooManyPrimes,
+    #[error("pool was cancelled")]
+    Cancelled,
+}
+
+impl From<Reason> for PrimePoolError {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn small_primes() {
+        assert_eq!(super::small_primes(8), [3, 5, 7, 11, 13, 17, 19, 23]);
+        assert_eq!(super::small_primes(135).last(), Some(&769));
+    }
+
+    #[test]
+    fn generates_same_primes_as_fast_paillier() {
+        use rand_core::SeedableRng;
+
+        let sieve = super::small_primes(super::SIEVE_SIZE);
+        for seed in 0..3 {
+            let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+            let expected = crate::fast_paillier::utils::generate_safe_prime(&mut rng, 256);
+
+            let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(seed);
+            let actual = super::generate_safe_prime(&mut rng, 256, &sieve, || false).unwrap();
+
+            assert_eq!(actual, expected);
+        }
+    }
+}
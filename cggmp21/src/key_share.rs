@@ -1,23 +1,49 @@
 //! Key share
 
+pub mod compact;
+mod dynamic;
+mod shared_aux;
+pub mod split;
+
 use std::ops;
 use std::sync::Arc;
 
 use generic_ec::{Curve, NonZero, Point, SecretScalar};
+use paillier_zk::fast_paillier::utils::CrtExp;
 use paillier_zk::paillier_encryption_in_range as π_enc;
 use paillier_zk::rug::{Complete, Integer};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use zeroize::Zeroize;
 
 use crate::security_level::SecurityLevel;
+use crate::utils;
+
+pub use self::dynamic::{DynKeyShare, KeyShareVisitor};
+pub use self::shared_aux::{DirtyKeyShareRef, KeyShareRef, SharedAuxError, SharedAuxStore};
 
 #[doc(inline)]
 pub use cggmp21_keygen::key_share::{
     CoreKeyShare as IncompleteKeyShare, DirtyCoreKeyShare as DirtyIncompleteKeyShare, DirtyKeyInfo,
-    HdError, InvalidCoreShare as InvalidIncompleteKeyShare, KeyInfo, Valid, Validate,
+    HdError, InvalidCoreShare as InvalidIncompleteKeyShare, KeyId, KeyInfo, Valid, Validate,
     ValidateError, ValidateFromParts, VssSetup,
 };
 
+#[cfg(feature = "backup")]
+#[doc(inline)]
+pub use ::key_share::backup;
+#[cfg(feature = "hd-wallets")]
+#[doc(inline)]
+pub use ::key_share::bip32;
+#[cfg(feature = "encrypted")]
+#[doc(inline)]
+pub use ::key_share::encrypted;
+#[cfg(feature = "pop")]
+#[doc(inline)]
+pub use ::key_share::pop;
+#[doc(inline)]
+pub use ::key_share::versioned;
+
 /// Key share
 ///
 #[doc = include_str!("../docs/key_share.md")]
@@ -30,6 +56,13 @@ pub type KeyShare<E, L = crate::default_choice::SecurityLevel> = Valid<DirtyKeyS
 pub type AuxInfo<L = crate::default_choice::SecurityLevel> = Valid<DirtyAuxInfo<L>>;
 
 /// Dirty aux info
+///
+/// Secret primes $p, q$ and [CRT parameters](PartyAux::crt) are wiped from memory on drop.
+///
+/// Note that some copies of the Paillier secret key are out of our control: [`CrtExp`] held by
+/// [`CrtParams`], and `fast_paillier::DecryptionKey` built from $p, q$ by signing and key refresh
+/// protocols. `fast_paillier` doesn't expose internals of these types, so they're deallocated
+/// without being wiped.
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct DirtyAuxInfo<L: SecurityLevel = crate::default_choice::SecurityLevel> {
@@ -42,7 +75,11 @@ pub struct DirtyAuxInfo<L: SecurityLevel = crate::default_choice::SecurityLevel>
     /// `parties[i]` corresponds to public auxiliary data of $\ith$ party
     pub parties: Vec<PartyAux>,
     /// Security level that was used to generate aux info
-    #[serde(skip)]
+    ///
+    /// Serialized as [`SecurityLevelDescriptor`](crate::security_level::SecurityLevelDescriptor).
+    /// Deserialization fails if serialized descriptor doesn't match `L`. Aux info serialized
+    /// without descriptor is assumed to match `L`.
+    #[serde(default, with = "crate::security_level::serde_descriptor")]
     pub security_level: std::marker::PhantomData<L>,
 }
 
@@ -58,6 +95,16 @@ pub struct DirtyKeyShare<E: Curve, L: SecurityLevel = crate::default_choice::Sec
     pub aux: DirtyAuxInfo<L>,
 }
 
+/// Key share serialized in specified format
+///
+/// Obtained via [`DirtyKeyShare::versioned`]
+#[derive(Serialize)]
+#[serde(bound = "")]
+pub struct VersionedKeyShare<'a, E: Curve, L: SecurityLevel> {
+    core: versioned::Versioned<'a, E>,
+    aux: &'a DirtyAuxInfo<L>,
+}
+
 /// Party public auxiliary data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
@@ -75,7 +122,122 @@ pub struct PartyAux {
     ///
     /// Note that it is extreamly sensitive! Leaking `crt` exposes Paillier private key.
     #[serde(default)]
-    pub crt: Option<paillier_zk::fast_paillier::utils::CrtExp>,
+    pub crt: Option<CrtParams>,
+}
+
+/// CRT parameters of Paillier key
+///
+/// Hold factorization $N = p \cdot q$ and [`CrtExp`] built from it once on construction, which
+/// enables faster modular exponentiation. Primes are wiped from memory on drop.
+///
+/// [`CrtExp`] is defined in `fast_paillier` crate which doesn't expose its internals, so we cannot
+/// overwrite its buffers: on drop (or [zeroize](Zeroize)), it's only deallocated, and ZK proofs get
+/// its copy once per protocol execution that is deallocated in the same way.
+///
+/// Serialized in the same format as [`CrtExp`], so CRT parameters serialized by previous versions
+/// of the library can be deserialized.
+#[derive(Clone)]
+pub struct CrtParams {
+    p: Integer,
+    q: Integer,
+    exp: Option<CrtExp>,
+}
+
+impl CrtParams {
+    /// Constructs CRT parameters from factorization $N = p \cdot q$
+    ///
+    /// Returns error if `p` and `q` are not coprime integers greater than one, or if building
+    /// [`CrtExp`] failed.
+    pub fn new(p: &Integer, q: &Integer) -> Result<Self, InvalidKeyShare> {
+        if *p <= *Integer::ONE || *q <= *Integer::ONE || p.gcd_ref(q).complete() != *Integer::ONE {
+            return Err(InvalidKeyShareReason::BuildCrt.into());
+        }
+        let exp = CrtExp::build_n(p, q).ok_or(InvalidKeyShareReason::BuildCrt)?;
+        Ok(Self {
+            p: p.clone(),
+            q: q.clone(),
+            exp: Some(exp),
+        })
+    }
+
+    /// Returns primes $p, q$
+    pub fn primes(&self) -> (&Integer, &Integer) {
+        (&self.p, &self.q)
+    }
+
+    /// Returns [`CrtExp`] used by ZK proofs
+    ///
+    /// Returns `None` if parameters were [zeroized](Zeroize).
+    pub fn crt_exp(&self) -> Option<&CrtExp> {
+        self.exp.as_ref()
+    }
+}
+
+impl Zeroize for CrtParams {
+    fn zeroize(&mut self) {
+        utils::zeroize_integer(&mut self.p);
+        utils::zeroize_integer(&mut self.q);
+        self.exp = None;
+    }
+}
+
+impl Drop for CrtParams {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
+impl std::fmt::Debug for CrtParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Don't leak the factorization
+        f.write_str("CrtParams")
+    }
+}
+
+/// Serialization format of [`CrtExp`](paillier_zk::fast_paillier::utils::CrtExp) built from
+/// primes `n1`, `n2`
+#[derive(Serialize, Deserialize)]
+struct CrtExpRepr {
+    n: Integer,
+    n1: Integer,
+    phi_n1: Integer,
+    n2: Integer,
+    phi_n2: Integer,
+    beta: Integer,
+}
+
+impl Drop for CrtExpRepr {
+    fn drop(&mut self) {
+        for x in [
+            &mut self.n1,
+            &mut self.phi_n1,
+            &mut self.n2,
+            &mut self.phi_n2,
+            &mut self.beta,
+        ] {
+            utils::zeroize_integer(x)
+        }
+    }
+}
+
+impl Serialize for CrtParams {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.exp
+            .as_ref()
+            .ok_or_else(|| serde::ser::Error::custom("CRT parameters were zeroized"))?
+            .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for CrtParams {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = CrtExpRepr::deserialize(deserializer)?;
+        if (&repr.n1 * &repr.n2).complete() != repr.n {
+            return Err(serde::de::Error::custom("invalid CRT parameters"));
+        }
+        Self::new(&repr.n1, &repr.n2)
+            .map_err(|_| serde::de::Error::custom("invalid CRT parameters"))
+    }
 }
 
 impl<L: SecurityLevel> Validate for DirtyAuxInfo<L> {
@@ -109,6 +271,26 @@ impl<L: SecurityLevel> Validate for DirtyAuxInfo<L> {
     }
 }
 
+impl<L: SecurityLevel> Zeroize for DirtyAuxInfo<L> {
+    fn zeroize(&mut self) {
+        utils::zeroize_integer(&mut self.p);
+        utils::zeroize_integer(&mut self.q);
+        for crt in self
+            .parties
+            .iter_mut()
+            .filter_map(|aux_j| aux_j.crt.as_mut())
+        {
+            crt.zeroize();
+        }
+    }
+}
+
+impl<L: SecurityLevel> Drop for DirtyAuxInfo<L> {
+    fn drop(&mut self) {
+        self.zeroize()
+    }
+}
+
 impl<L: SecurityLevel> DirtyAuxInfo<L> {
     /// Precomputes multiexponentiation tables
     ///
@@ -165,6 +347,14 @@ impl<L: SecurityLevel> DirtyAuxInfo<L> {
             .ok_or(InvalidKeyShareReason::CrtINotInRange)?;
         aux_i.precompute_crt(&self.p, &self.q)
     }
+
+    /// Returns fingerprint of public aux data
+    ///
+    /// Fingerprint is the same for all parties that hold the same aux info. Refer to
+    /// [`compact::AuxFingerprint`] for the details.
+    pub fn fingerprint(&self) -> compact::AuxFingerprint {
+        compact::PublicAux::new(self).fingerprint()
+    }
 }
 
 impl PartyAux {
@@ -210,8 +400,7 @@ impl PartyAux {
         if (p * q).complete() != self.N {
             return Err(InvalidKeyShareReason::CrtInvalidPq.into());
         }
-        let crt = paillier_zk::fast_paillier::utils::CrtExp::build_n(p, q)
-            .ok_or(InvalidKeyShareReason::BuildCrt)?;
+        let crt = CrtParams::new(p, q)?;
         self.crt = Some(crt);
         Ok(())
     }
@@ -245,6 +434,53 @@ impl<E: Curve, L: SecurityLevel> ValidateFromParts<(IncompleteKeyShare<E>, AuxIn
 }
 
 impl<E: Curve, L: SecurityLevel> DirtyKeyShare<E, L> {
+    /// Returns serializable key share with core share in specified format
+    ///
+    /// Can be used to roll back to an older version of the library. Aux info is serialized in the
+    /// current format: older versions of the library ignore the fields they don't know.
+    ///
+    /// Returns error if the core share can't be represented in that format, see
+    /// [`DirtyIncompleteKeyShare::versioned`].
+    pub fn versioned(
+        &self,
+        version: versioned::FormatVersion,
+    ) -> Result<VersionedKeyShare<'_, E, L>, versioned::VersionError> {
+        Ok(VersionedKeyShare {
+            core: self.core.versioned(version)?,
+            aux: &self.aux,
+        })
+    }
+
+    /// Returns fingerprint of public data of the key share
+    ///
+    /// Fingerprint is a hash over [key ID](DirtyKeyInfo::key_id), public shares of all signers,
+    /// epoch, and public aux data of all signers. It's the same for all signers holding shares of
+    /// the same key, so signers can compare fingerprints to make sure they hold shares of the same
+    /// key and the same generation before signing.
+    pub fn share_fingerprint(&self) -> ShareFingerprint {
+        #[derive(udigest::Digestable)]
+        #[udigest(bound = "")]
+        struct ShareFingerprintDigest<'a, E: Curve> {
+            key_id: udigest::Bytes<&'a [u8; 32]>,
+            public_shares: &'a [NonZero<Point<E>>],
+            epoch: u64,
+            aux: udigest::Bytes<&'a [u8; 32]>,
+        }
+
+        let key_id = self.core.key_id();
+        let aux_fingerprint = self.aux.fingerprint();
+        ShareFingerprint(
+            udigest::Tag::<sha2::Sha256>::new("dfns.cggmp21.key_share.share_fingerprint")
+                .digest(ShareFingerprintDigest::<E> {
+                    key_id: udigest::Bytes(key_id.as_bytes()),
+                    public_shares: &self.core.public_shares,
+                    epoch: self.core.epoch,
+                    aux: udigest::Bytes(aux_fingerprint.as_bytes()),
+                })
+                .into(),
+        )
+    }
+
     /// Perform consistency check between core and aux
     fn validate_consistency(
         core: &DirtyIncompleteKeyShare<E>,
@@ -258,11 +494,37 @@ impl<E: Curve, L: SecurityLevel> DirtyKeyShare<E, L> {
         if *N_i != (&aux.p * &aux.q).complete() {
             return Err(InvalidKeyShareReason::PrimesMul.into());
         }
+        // Index of the signer in aux info must be unambiguous, otherwise aux info
+        // could be bound to a key share of another party
+        if aux.parties.iter().filter(|aux_j| aux_j.N == *N_i).count() != 1 {
+            return Err(InvalidKeyShareReason::AuxIndexAmbiguous.into());
+        }
 
         Ok(())
     }
 }
 
+#[cfg(feature = "hd-wallets")]
+impl<E: Curve, L: SecurityLevel> DirtyKeyShare<E, L> {
+    /// Derives child key share, if it's HD key
+    ///
+    /// Aux info is kept as is. See [`DirtyIncompleteKeyShare::derive_child_share`].
+    pub fn derive_child_share<ChildIndex>(
+        &self,
+        derivation_path: impl IntoIterator<Item = ChildIndex>,
+    ) -> Result<KeyShare<E, L>, HdError<<ChildIndex as TryInto<slip_10::NonHardenedIndex>>::Error>>
+    where
+        slip_10::NonHardenedIndex: TryFrom<ChildIndex>,
+    {
+        let core = self.core.derive_child_share(derivation_path)?;
+        Valid::validate(DirtyKeyShare {
+            core: core.into_inner(),
+            aux: self.aux.clone(),
+        })
+        .map_err(|_| HdError::InvalidChildShare)
+    }
+}
+
 impl<E: Curve> DirtyKeyShare<E> {
     /// Precomputes CRT parameters
     ///
@@ -285,12 +547,24 @@ impl<E: Curve, L: SecurityLevel> AsRef<DirtyIncompleteKeyShare<E>> for DirtyKeyS
         &self.core
     }
 }
+impl<L: SecurityLevel> AsRef<DirtyAuxInfo<L>> for DirtyAuxInfo<L> {
+    fn as_ref(&self) -> &DirtyAuxInfo<L> {
+        self
+    }
+}
 impl<E: Curve, L: SecurityLevel> AsRef<DirtyAuxInfo<L>> for DirtyKeyShare<E, L> {
     fn as_ref(&self) -> &DirtyAuxInfo<L> {
         &self.aux
     }
 }
 
+#[cfg(feature = "encrypted")]
+impl<E: Curve, L: SecurityLevel> encrypted::SealableKeyShare<E> for DirtyKeyShare<E, L> {
+    fn key_info(&self) -> &DirtyKeyInfo<E> {
+        &self.core.key_info
+    }
+}
+
 impl<E: Curve, L: SecurityLevel> ops::Deref for DirtyKeyShare<E, L> {
     type Target = DirtyIncompleteKeyShare<E>;
 
@@ -299,6 +573,42 @@ impl<E: Curve, L: SecurityLevel> ops::Deref for DirtyKeyShare<E, L> {
     }
 }
 
+/// Fingerprint of public data of [key share](DirtyKeyShare::share_fingerprint)
+///
+/// Displayed as hex string. Alternate format (`{:#}`) displays only first 8 bytes of the
+/// fingerprint split into groups, which is convenient for comparing fingerprints by a human.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ShareFingerprint(#[serde(with = "hex")] [u8; 32]);
+
+impl ShareFingerprint {
+    /// Returns bytes representation of the fingerprint
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for ShareFingerprint {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl std::fmt::Display for ShareFingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if f.alternate() {
+            for (n, group) in self.0[..8].chunks(2).enumerate() {
+                if n > 0 {
+                    f.write_str("-")?;
+                }
+                group.iter().try_for_each(|b| write!(f, "{b:02x}"))?;
+            }
+            Ok(())
+        } else {
+            self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+        }
+    }
+}
+
 /// Any (validated) key share
 ///
 /// Implemented for both [KeyShare] and [IncompleteKeyShare]. Used in methods
@@ -330,6 +640,13 @@ pub trait AnyKeyShare<E: Curve>: AsRef<IncompleteKeyShare<E>> {
     fn shared_public_key(&self) -> NonZero<Point<E>> {
         self.as_ref().shared_public_key
     }
+
+    /// Returns epoch of the key share
+    ///
+    /// Epoch is a number of key refreshes performed since the key was generated
+    fn epoch(&self) -> u64 {
+        self.as_ref().epoch
+    }
 }
 
 impl<E: Curve, T: AsRef<IncompleteKeyShare<E>>> AnyKeyShare<E> for T {}
@@ -357,7 +674,7 @@ impl From<&PartyAux> for π_enc::Aux {
             t: aux.t.clone(),
             rsa_modulo: aux.N.clone(),
             multiexp: aux.multiexp.clone(),
-            crt: aux.crt.clone(),
+            crt: aux.crt.as_ref().and_then(CrtParams::crt_exp).cloned(),
         }
     }
 }
@@ -375,6 +692,8 @@ enum InvalidKeyShareReason {
     AuxLen,
     #[error("N_i != p q")]
     PrimesMul,
+    #[error("N_i is shared by several parties in aux info")]
+    AuxIndexAmbiguous,
     #[error("gcd(s_j, N_j) != 1 or gcd(t_j, N_j) != 1")]
     StGcdN,
     #[error("paillier secret key doesn't match security level (primes are too small)")]
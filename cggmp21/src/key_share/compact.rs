@@ -0,0 +1,727 @@
+//! Compact binary encoding of key shares and aux info
+//!
+//! Serde encoding of [`AuxInfo`] stores all integers as they are, and it may include precomputed
+//! multiexp tables, which makes key shares quite large. This module provides an alternative compact
+//! and versioned binary encoding:
+//! * Integers are encoded as big-endian bytes, ring-Pedersen parameters $s, t$ are stored reduced
+//!   modulo $N$
+//! * Precomputed [multiexp](super::PartyAux::multiexp) and [crt](super::PartyAux::crt) tables are
+//!   never encoded. Tables are not built on first use either: they must be precomputed by the
+//!   caller. Decoding functions take `precompute_tables` flag: when it's set, the tables are
+//!   precomputed eagerly right after decoding (multiexp tables of all parties and, for key shares,
+//!   CRT parameters of the owner). Otherwise, decoded value has no tables until the caller
+//!   precomputes them via [`DirtyAuxInfo::precompute_multiexp_tables`] and
+//!   [`DirtyKeyShare::precompute_crt`]. Signing and presigning without tables are slower.
+//! * Public aux data of all parties can be detached from the key share, so it's stored only once
+//!   when many key shares use the same aux data. Detached key share is bound to the public aux data
+//!   via [`AuxFingerprint`].
+//!
+//! ## Example
+//! Store many key shares that use the same aux data:
+//! ```rust,no_run
+//! # fn store(_: &[u8], _: &[u8]) {}
+//! # fn load(_: &[u8]) -> Vec<u8> { unimplemented!() }
+//! use cggmp21::key_share::compact;
+//! # type E = cggmp21::supported_curves::Secp256k1;
+//! # let key_shares: Vec<cggmp21::KeyShare<E>> = vec![];
+//!
+//! let mut encoded_shares = vec![];
+//! for key_share in &key_shares {
+//!     let (encoded, public_aux) = compact::encode_key_share_detached(key_share);
+//!     // Public aux is identical for all key shares that use the same aux data,
+//!     // it's enough to store it once
+//!     store(public_aux.fingerprint().as_bytes(), &public_aux.encode());
+//!     encoded_shares.push(encoded);
+//! }
+//!
+//! // Decode key shares
+//! for encoded in &encoded_shares {
+//!     let fingerprint = compact::detached_fingerprint(encoded)?;
+//!     let public_aux = compact::PublicAux::decode(&load(fingerprint.as_bytes()))?;
+//!     let key_share: cggmp21::KeyShare<E> =
+//!         compact::decode_key_share_detached(encoded, &public_aux, true)?;
+//! }
+//! # Ok::<_, compact::DecodeError>(())
+//! ```
+
+use std::fmt;
+
+use digest::Digest;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use paillier_zk::rug::{integer::Order, Integer};
+use thiserror::Error;
+
+use crate::security_level::SecurityLevel;
+
+use super::{
+    AuxInfo, DirtyAuxInfo, DirtyIncompleteKeyShare, DirtyKeyInfo, DirtyKeyShare, InvalidKeyShare,
+    KeyShare, PartyAux, Validate, VssSetup,
+};
+
+/// Version of the encoding
+///
+/// Encoding version is written into every encoded value, decoding of unknown versions is rejected.
+pub const VERSION: u8 = 1;
+
+/// Kind of encoded value, written after the version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum Kind {
+    AuxInfo = 1,
+    PublicAux = 2,
+    DetachedAuxInfo = 3,
+    KeyShare = 4,
+    DetachedKeyShare = 5,
+}
+
+/// Fingerprint of [public aux data](PublicAux)
+///
+/// Uniquely identifies public aux data, can be used as a key to store it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AuxFingerprint([u8; 32]);
+
+impl AuxFingerprint {
+    /// Returns bytes representation of the fingerprint
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<[u8; 32]> for AuxFingerprint {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+impl fmt::Display for AuxFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+
+/// Public aux data of all parties sharing the key
+///
+/// It's identical for all key shares of all keys that use the same aux data, so it can be
+/// stored separately from the key shares once. Obtained from [`encode_key_share_detached`] or
+/// [`encode_aux_info_detached`].
+pub struct PublicAux<L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    parties: Vec<PartyAux>,
+    fingerprint: AuxFingerprint,
+    _security_level: std::marker::PhantomData<L>,
+}
+
+impl<L: SecurityLevel> PublicAux<L> {
+    /// Extracts public aux data from the aux info
+    pub fn new(aux: &DirtyAuxInfo<L>) -> Self {
+        let parties = aux
+            .parties
+            .iter()
+            .map(|aux_j| PartyAux {
+                N: aux_j.N.clone(),
+                s: aux_j.s.clone(),
+                t: aux_j.t.clone(),
+                multiexp: None,
+                crt: None,
+            })
+            .collect::<Vec<_>>();
+        let fingerprint = fingerprint(&encode_public_aux::<L>(&parties));
+        Self {
+            parties,
+            fingerprint,
+            _security_level: std::marker::PhantomData,
+        }
+    }
+
+    /// Public aux data of all parties
+    ///
+    /// `parties()[j]` corresponds to public aux data of $j$-th party
+    pub fn parties(&self) -> &[PartyAux] {
+        &self.parties
+    }
+
+    /// Fingerprint of public aux data
+    pub fn fingerprint(&self) -> AuxFingerprint {
+        self.fingerprint
+    }
+
+    /// Encodes public aux data
+    pub fn encode(&self) -> Vec<u8> {
+        encode_public_aux::<L>(&self.parties)
+    }
+
+    /// Decodes public aux data
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = Reader::new(bytes);
+        reader.header::<L>(Kind::PublicAux)?;
+        let parties = reader.public_aux()?;
+        reader.finish()?;
+
+        // Encoding is canonical up to reduction of `s, t` modulo `N`, so we re-encode the data
+        // to obtain the fingerprint
+        let fingerprint = fingerprint(&encode_public_aux::<L>(&parties));
+        Ok(Self {
+            parties,
+            fingerprint,
+            _security_level: std::marker::PhantomData,
+        })
+    }
+}
+
+impl<L: SecurityLevel> Clone for PublicAux<L> {
+    fn clone(&self) -> Self {
+        Self {
+            parties: self.parties.clone(),
+            fingerprint: self.fingerprint,
+            _security_level: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Encodes aux info
+pub fn encode_aux_info<L: SecurityLevel>(aux: &DirtyAuxInfo<L>) -> Vec<u8> {
+    let mut writer = Writer::new::<L>(Kind::AuxInfo);
+    writer.integer(&aux.p);
+    writer.integer(&aux.q);
+    writer.public_aux(&aux.parties);
+    writer.0
+}
+
+/// Decodes and validates aux info
+///
+/// If `precompute_tables` is set, multiexp tables are precomputed for decoded aux info. Otherwise,
+/// decoded aux info has no tables, see [module docs](self).
+pub fn decode_aux_info<L: SecurityLevel>(
+    bytes: &[u8],
+    precompute_tables: bool,
+) -> Result<AuxInfo<L>, DecodeError> {
+    let mut reader = Reader::new(bytes);
+    reader.header::<L>(Kind::AuxInfo)?;
+    let p = reader.integer()?;
+    let q = reader.integer()?;
+    let parties = reader.public_aux()?;
+    reader.finish()?;
+
+    validate_aux(
+        DirtyAuxInfo {
+            p,
+            q,
+            parties,
+            security_level: std::marker::PhantomData,
+        },
+        precompute_tables,
+    )
+}
+
+/// Encodes aux info with detached public aux data
+///
+/// Returns encoded secret part of aux info (that includes fingerprint of public aux) and public aux data
+/// that needs to be stored separately.
+pub fn encode_aux_info_detached<L: SecurityLevel>(
+    aux: &DirtyAuxInfo<L>,
+) -> (Vec<u8>, PublicAux<L>) {
+    let public_aux = PublicAux::new(aux);
+    let mut writer = Writer::new::<L>(Kind::DetachedAuxInfo);
+    writer.fingerprint(&public_aux.fingerprint);
+    writer.integer(&aux.p);
+    writer.integer(&aux.q);
+    (writer.0, public_aux)
+}
+
+/// Decodes and validates aux info that was encoded via [`encode_aux_info_detached`]
+///
+/// Returns error if `public_aux` doesn't match the fingerprint in the encoding. If `precompute_tables`
+/// is set, multiexp tables are precomputed for decoded aux info. Otherwise, decoded aux info has no
+/// tables, see [module docs](self).
+pub fn decode_aux_info_detached<L: SecurityLevel>(
+    bytes: &[u8],
+    public_aux: &PublicAux<L>,
+    precompute_tables: bool,
+) -> Result<AuxInfo<L>, DecodeError> {
+    let mut reader = Reader::new(bytes);
+    reader.header::<L>(Kind::DetachedAuxInfo)?;
+    reader.expect_fingerprint(public_aux)?;
+    let p = reader.integer()?;
+    let q = reader.integer()?;
+    reader.finish()?;
+
+    validate_aux(
+        DirtyAuxInfo {
+            p,
+            q,
+            parties: public_aux.parties.clone(),
+            security_level: std::marker::PhantomData,
+        },
+        precompute_tables,
+    )
+}
+
+/// Encodes key share
+pub fn encode_key_share<E: Curve, L: SecurityLevel>(key_share: &DirtyKeyShare<E, L>) -> Vec<u8> {
+    let mut writer = Writer::new::<L>(Kind::KeyShare);
+    writer.curve::<E>();
+    writer.core_share(&key_share.core);
+    writer.integer(&key_share.aux.p);
+    writer.integer(&key_share.aux.q);
+    writer.public_aux(&key_share.aux.parties);
+    writer.0
+}
+
+/// Decodes and validates key share
+///
+/// If `precompute_tables` is set, multiexp tables and CRT parameters are precomputed for decoded
+/// key share. Otherwise, decoded key share has no tables, see [module docs](self).
+pub fn decode_key_share<E: Curve, L: SecurityLevel>(
+    bytes: &[u8],
+    precompute_tables: bool,
+) -> Result<KeyShare<E, L>, DecodeError> {
+    let mut reader = Reader::new(bytes);
+    reader.header::<L>(Kind::KeyShare)?;
+    reader.curve::<E>()?;
+    let core = reader.core_share()?;
+    let p = reader.integer()?;
+    let q = reader.integer()?;
+    let parties = reader.public_aux()?;
+    reader.finish()?;
+
+    validate_key_share(
+        DirtyKeyShare {
+            core,
+            aux: DirtyAuxInfo {
+                p,
+                q,
+                parties,
+                security_level: std::marker::PhantomData,
+            },
+        },
+        precompute_tables,
+    )
+}
+
+/// Encodes key share with detached public aux data
+///
+/// Returns encoded key share (that includes fingerprint of public aux) and public aux data that needs to
+/// be stored separately. Public aux data is the same for all key shares that use the same aux info.
+pub fn encode_key_share_detached<E: Curve, L: SecurityLevel>(
+    key_share: &DirtyKeyShare<E, L>,
+) -> (Vec<u8>, PublicAux<L>) {
+    let public_aux = PublicAux::new(&key_share.aux);
+    let mut writer = Writer::new::<L>(Kind::DetachedKeyShare);
+    writer.fingerprint(&public_aux.fingerprint);
+    writer.curve::<E>();
+    writer.core_share(&key_share.core);
+    writer.integer(&key_share.aux.p);
+    writer.integer(&key_share.aux.q);
+    (writer.0, public_aux)
+}
+
+/// Decodes and validates key share that was encoded via [`encode_key_share_detached`]
+///
+/// Returns error if `public_aux` doesn't match the fingerprint in the encoding. If `precompute_tables`
+/// is set, multiexp tables and CRT parameters are precomputed for decoded key share. Otherwise,
+/// decoded key share has no tables, see [module docs](self).
+pub fn decode_key_share_detached<E: Curve, L: SecurityLevel>(
+    bytes: &[u8],
+    public_aux: &PublicAux<L>,
+    precompute_tables: bool,
+) -> Result<KeyShare<E, L>, DecodeError> {
+    let mut reader = Reader::new(bytes);
+    reader.header::<L>(Kind::DetachedKeyShare)?;
+    reader.expect_fingerprint(public_aux)?;
+    reader.curve::<E>()?;
+    let core = reader.core_share()?;
+    let p = reader.integer()?;
+    let q = reader.integer()?;
+    reader.finish()?;
+
+    validate_key_share(
+        DirtyKeyShare {
+            core,
+            aux: DirtyAuxInfo {
+                p,
+                q,
+                parties: public_aux.parties.clone(),
+                security_level: std::marker::PhantomData,
+            },
+        },
+        precompute_tables,
+    )
+}
+
+/// Reads fingerprint of public aux data from detached key share or aux info
+///
+/// Can be used to look up public aux data required to decode detached value
+pub fn detached_fingerprint(bytes: &[u8]) -> Result<AuxFingerprint, DecodeError> {
+    let mut reader = Reader::new(bytes);
+    let version = reader.u8()?;
+    if version != VERSION {
+        return Err(Reason::UnsupportedVersion(version).into());
+    }
+    let kind = reader.u8()?;
+    if kind != Kind::DetachedAuxInfo as u8 && kind != Kind::DetachedKeyShare as u8 {
+        return Err(Reason::NotDetached.into());
+    }
+    let _security_bits = reader.varint()?;
+    reader.fingerprint()
+}
+
+fn validate_aux<L: SecurityLevel>(
+    mut aux: DirtyAuxInfo<L>,
+    precompute_tables: bool,
+) -> Result<AuxInfo<L>, DecodeError> {
+    if precompute_tables {
+        // Don't spend time on precomputation if decoded value is invalid
+        aux.is_valid().map_err(Reason::Invalid)?;
+        aux.precompute_multiexp_tables()
+            .map_err(Reason::Precompute)?;
+    }
+    aux.validate()
+        .map_err(|err| Reason::Invalid(err.into_error()).into())
+}
+
+fn validate_key_share<E: Curve, L: SecurityLevel>(
+    mut key_share: DirtyKeyShare<E, L>,
+    precompute_tables: bool,
+) -> Result<KeyShare<E, L>, DecodeError> {
+    if precompute_tables {
+        // Don't spend time on precomputation if decoded value is invalid
+        key_share.is_valid().map_err(Reason::Invalid)?;
+        key_share
+            .aux
+            .precompute_multiexp_tables()
+            .map_err(Reason::Precompute)?;
+        let i = key_share.core.i;
+        key_share
+            .aux
+            .precompute_crt(i)
+            .map_err(Reason::Precompute)?;
+    }
+    key_share
+        .validate()
+        .map_err(|err| Reason::Invalid(err.into_error()).into())
+}
+
+fn encode_public_aux<L: SecurityLevel>(parties: &[PartyAux]) -> Vec<u8> {
+    let mut writer = Writer::new::<L>(Kind::PublicAux);
+    writer.public_aux(parties);
+    writer.0
+}
+
+fn fingerprint(encoded_public_aux: &[u8]) -> AuxFingerprint {
+    AuxFingerprint(
+        sha2::Sha256::new()
+            .chain_update(b"dfns.cggmp21.key_share.public_aux_fingerprint")
+            .chain_update(encoded_public_aux)
+            .finalize()
+            .into(),
+    )
+}
+
+struct Writer(Vec<u8>);
+
+impl Writer {
+    fn new<L: SecurityLevel>(kind: Kind) -> Self {
+        let mut writer = Self(vec![VERSION, kind as u8]);
+        writer.varint(L::SECURITY_BITS.into());
+        writer
+    }
+
+    fn varint(&mut self, mut x: u64) {
+        while x >= 0x80 {
+            self.0.push((x as u8) | 0x80);
+            x >>= 7;
+        }
+        self.0.push(x as u8)
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.varint(bytes.len() as u64);
+        self.0.extend_from_slice(bytes)
+    }
+
+    fn integer(&mut self, x: &Integer) {
+        self.bytes(&x.to_digits::<u8>(Order::Msf))
+    }
+
+    fn fingerprint(&mut self, fingerprint: &AuxFingerprint) {
+        self.0.extend_from_slice(&fingerprint.0)
+    }
+
+    fn curve<E: Curve>(&mut self) {
+        self.bytes(E::CURVE_NAME.as_bytes())
+    }
+
+    fn point<E: Curve>(&mut self, point: &Point<E>) {
+        self.bytes(point.to_bytes(true).as_bytes())
+    }
+
+    fn public_aux(&mut self, parties: &[PartyAux]) {
+        self.varint(parties.len() as u64);
+        for aux_j in parties {
+            self.integer(&aux_j.N);
+            self.integer(&aux_j.s.clone().modulo(&aux_j.N));
+            self.integer(&aux_j.t.clone().modulo(&aux_j.N));
+        }
+    }
+
+    fn core_share<E: Curve>(&mut self, core: &DirtyIncompleteKeyShare<E>) {
+        self.varint(core.i.into());
+        self.point(&core.shared_public_key);
+        self.varint(core.public_shares.len() as u64);
+        for X_j in &core.public_shares {
+            self.point(X_j)
+        }
+        match &core.vss_setup {
+            Some(vss_setup) => {
+                self.0.push(1);
+                self.varint(vss_setup.min_signers.into());
+                self.varint(vss_setup.I.len() as u64);
+                for I_j in &vss_setup.I {
+                    self.bytes(I_j.to_be_bytes().as_bytes())
+                }
+            }
+            None => self.0.push(0),
+        }
+        #[cfg(feature = "hd-wallets")]
+        match &core.chain_code {
+            Some(chain_code) => {
+                self.0.push(1);
+                self.0.extend_from_slice(chain_code)
+            }
+            None => self.0.push(0),
+        }
+        #[cfg(not(feature = "hd-wallets"))]
+        self.0.push(0);
+        self.varint(core.epoch);
+        self.bytes(AsRef::<Scalar<E>>::as_ref(&core.x).to_be_bytes().as_bytes());
+    }
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self(bytes)
+    }
+
+    fn header<L: SecurityLevel>(&mut self, kind: Kind) -> Result<(), DecodeError> {
+        let version = self.u8()?;
+        if version != VERSION {
+            return Err(Reason::UnsupportedVersion(version).into());
+        }
+        let actual_kind = self.u8()?;
+        if actual_kind != kind as u8 {
+            return Err(Reason::UnexpectedKind {
+                expected: kind,
+                actual: actual_kind,
+            }
+            .into());
+        }
+        let security_bits = self.varint()?;
+        if security_bits != u64::from(L::SECURITY_BITS) {
+            return Err(Reason::SecurityLevelMismatch {
+                expected: L::SECURITY_BITS,
+                actual: security_bits,
+            }
+            .into());
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<(), DecodeError> {
+        if !self.0.is_empty() {
+            return Err(Reason::TrailingBytes.into());
+        }
+        Ok(())
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.0.len() < len {
+            return Err(Reason::UnexpectedEof.into());
+        }
+        let (taken, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u64, DecodeError> {
+        let mut x = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            let bits = u64::from(byte & 0x7f);
+            if (bits << shift) >> shift != bits {
+                return Err(Reason::VarintOverflow.into());
+            }
+            x |= bits << shift;
+            if byte & 0x80 == 0 {
+                return Ok(x);
+            }
+        }
+        Err(Reason::VarintOverflow.into())
+    }
+
+    fn len(&mut self) -> Result<usize, DecodeError> {
+        let len = self.varint()?;
+        // Length can't exceed amount of remaining bytes, that protects from allocating too much memory
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.0.len())
+            .ok_or(Reason::UnexpectedEof.into())
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        self.varint()?
+            .try_into()
+            .map_err(|_| Reason::VarintOverflow.into())
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.len()?;
+        self.take(len)
+    }
+
+    fn integer(&mut self) -> Result<Integer, DecodeError> {
+        Ok(Integer::from_digits(self.bytes()?, Order::Msf))
+    }
+
+    fn fingerprint(&mut self) -> Result<AuxFingerprint, DecodeError> {
+        let mut fingerprint = [0u8; 32];
+        fingerprint.copy_from_slice(self.take(32)?);
+        Ok(AuxFingerprint(fingerprint))
+    }
+
+    fn expect_fingerprint<L: SecurityLevel>(
+        &mut self,
+        public_aux: &PublicAux<L>,
+    ) -> Result<(), DecodeError> {
+        if self.fingerprint()? != public_aux.fingerprint {
+            return Err(Reason::FingerprintMismatch.into());
+        }
+        Ok(())
+    }
+
+    fn curve<E: Curve>(&mut self) -> Result<(), DecodeError> {
+        if self.bytes()? != E::CURVE_NAME.as_bytes() {
+            return Err(Reason::CurveMismatch.into());
+        }
+        Ok(())
+    }
+
+    fn point<E: Curve>(&mut self) -> Result<NonZero<Point<E>>, DecodeError> {
+        Point::from_bytes(self.bytes()?)
+            .ok()
+            .and_then(NonZero::from_point)
+            .ok_or(Reason::InvalidPoint.into())
+    }
+
+    fn public_aux(&mut self) -> Result<Vec<PartyAux>, DecodeError> {
+        let n = self.len()?;
+        (0..n)
+            .map(|_| {
+                Ok(PartyAux {
+                    N: self.integer()?,
+                    s: self.integer()?,
+                    t: self.integer()?,
+                    multiexp: None,
+                    crt: None,
+                })
+            })
+            .collect()
+    }
+
+    fn core_share<E: Curve>(&mut self) -> Result<DirtyIncompleteKeyShare<E>, DecodeError> {
+        let i = self.u16()?;
+        let shared_public_key = self.point()?;
+        let n = self.len()?;
+        let public_shares = (0..n)
+            .map(|_| self.point())
+            .collect::<Result<Vec<_>, _>>()?;
+        let vss_setup = match self.u8()? {
+            0 => None,
+            1 => {
+                let min_signers = self.u16()?;
+                let n = self.len()?;
+                let I = (0..n)
+                    .map(|_| {
+                        Scalar::from_be_bytes(self.bytes()?)
+                            .ok()
+                            .and_then(NonZero::from_scalar)
+                            .ok_or(Reason::InvalidScalar.into())
+                    })
+                    .collect::<Result<Vec<_>, DecodeError>>()?;
+                Some(VssSetup { min_signers, I })
+            }
+            _ => return Err(Reason::InvalidFlag.into()),
+        };
+        let chain_code = match self.u8()? {
+            0 => None,
+            1 => {
+                let mut chain_code = [0u8; 32];
+                chain_code.copy_from_slice(self.take(32)?);
+                Some(chain_code)
+            }
+            _ => return Err(Reason::InvalidFlag.into()),
+        };
+        #[cfg(not(feature = "hd-wallets"))]
+        let _ = chain_code;
+        let epoch = self.varint()?;
+        let x = SecretScalar::from_be_bytes(self.bytes()?)
+            .ok()
+            .and_then(NonZero::from_secret_scalar)
+            .ok_or(Reason::InvalidScalar)?;
+
+        Ok(DirtyIncompleteKeyShare {
+            i,
+            key_info: DirtyKeyInfo {
+                curve: Default::default(),
+                shared_public_key,
+                public_shares,
+                vss_setup,
+                #[cfg(feature = "hd-wallets")]
+                chain_code,
+                epoch,
+            },
+            x,
+        })
+    }
+}
+
+/// Error indicating that decoding failed
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct DecodeError(#[from] Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("unexpected end of input")]
+    UnexpectedEof,
+    #[error("input has unexpected trailing bytes")]
+    TrailingBytes,
+    #[error("unsupported encoding version: {0}")]
+    UnsupportedVersion(u8),
+    #[error("unexpected kind of encoded value: expected {expected:?}, actual {actual}")]
+    UnexpectedKind { expected: Kind, actual: u8 },
+    #[error("value is not encoded with detached public aux")]
+    NotDetached,
+    #[error("security level mismatch: expected {expected} bits, actual {actual} bits")]
+    SecurityLevelMismatch { expected: u32, actual: u64 },
+    #[error("curve mismatch")]
+    CurveMismatch,
+    #[error("varint overflows")]
+    VarintOverflow,
+    #[error("invalid flag")]
+    InvalidFlag,
+    #[error("invalid point")]
+    InvalidPoint,
+    #[error("invalid scalar")]
+    InvalidScalar,
+    #[error("public aux doesn't match the fingerprint")]
+    FingerprintMismatch,
+    #[error("decoded value is not valid")]
+    Invalid(#[source] InvalidKeyShare),
+    #[error("couldn't precompute tables")]
+    Precompute(#[source] InvalidKeyShare),
+}
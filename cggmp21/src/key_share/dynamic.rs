@@ -0,0 +1,224 @@
+//! Key share with security level selected at runtime
+//!
+//! [`KeyShare<E, L>`](KeyShare) has security level `L` fixed at compile time. [`DynKeyShare`] holds
+//! a key share of any of the [security levels](crate::security_level) provided by the crate, the
+//! actual level is determined when the key share is deserialized. Use [`KeyShareVisitor`] to run
+//! code that's generic over security level against the key share.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use cggmp21::key_share::{DynKeyShare, KeyShare, KeyShareVisitor};
+//! use cggmp21::security_level::SecurityLevel;
+//! # type E = cggmp21::supported_curves::Secp256k1;
+//!
+//! struct PaillierKeySize;
+//! impl KeyShareVisitor<E> for PaillierKeySize {
+//!     type Output = u32;
+//!     fn visit<L: SecurityLevel>(self, key_share: &KeyShare<E, L>) -> u32 {
+//!         key_share.aux.parties[usize::from(key_share.core.i)]
+//!             .N
+//!             .significant_bits()
+//!     }
+//! }
+//!
+//! # fn load_key_share() -> DynKeyShare<E> { unimplemented!() }
+//! // Security level of the key share is determined on deserialization
+//! let key_share: DynKeyShare<E> = load_key_share();
+//! println!("Security level: {} bits", key_share.security_level().security_bits);
+//! let size = key_share.visit(PaillierKeySize);
+//! ```
+
+use generic_ec::Curve;
+use paillier_zk::rug::Integer;
+use serde::{Deserialize, Serialize};
+
+use crate::security_level::{
+    SecurityLevel, SecurityLevel128, SecurityLevel192, SecurityLevel256, SecurityLevelDescriptor,
+};
+
+use super::{
+    DirtyAuxInfo, DirtyIncompleteKeyShare, DirtyKeyShare, IncompleteKeyShare, KeyShare, PartyAux,
+    Validate,
+};
+
+/// Key share of security level selected at runtime
+///
+/// Serialization format is the same as of [`KeyShare`]. Deserialization fails if serialized key share
+/// doesn't specify its security level, or if it's not one of the security levels provided by the crate.
+#[derive(Clone, Serialize)]
+#[serde(untagged, bound = "")]
+pub enum DynKeyShare<E: Curve> {
+    /// Key share of [`SecurityLevel128`]
+    SecurityLevel128(KeyShare<E, SecurityLevel128>),
+    /// Key share of [`SecurityLevel192`]
+    SecurityLevel192(KeyShare<E, SecurityLevel192>),
+    /// Key share of [`SecurityLevel256`]
+    SecurityLevel256(KeyShare<E, SecurityLevel256>),
+}
+
+/// Runs code generic over security level against [`DynKeyShare`]
+pub trait KeyShareVisitor<E: Curve> {
+    /// Output of the visitor
+    type Output;
+
+    /// Visits a key share of security level `L`
+    fn visit<L: SecurityLevel>(self, key_share: &KeyShare<E, L>) -> Self::Output;
+}
+
+impl<E: Curve> DynKeyShare<E> {
+    /// Returns descriptor of security level of the key share
+    pub fn security_level(&self) -> SecurityLevelDescriptor {
+        match self {
+            Self::SecurityLevel128(_) => SecurityLevelDescriptor::of::<SecurityLevel128>(),
+            Self::SecurityLevel192(_) => SecurityLevelDescriptor::of::<SecurityLevel192>(),
+            Self::SecurityLevel256(_) => SecurityLevelDescriptor::of::<SecurityLevel256>(),
+        }
+    }
+
+    /// Calls the visitor with the key share of actual security level
+    pub fn visit<V: KeyShareVisitor<E>>(&self, visitor: V) -> V::Output {
+        match self {
+            Self::SecurityLevel128(key_share) => visitor.visit(key_share),
+            Self::SecurityLevel192(key_share) => visitor.visit(key_share),
+            Self::SecurityLevel256(key_share) => visitor.visit(key_share),
+        }
+    }
+
+    /// Converts into key share of security level `L`
+    ///
+    /// Returns `Err(self)` if the key share has another security level.
+    #[allow(clippy::result_large_err)]
+    pub fn downcast<L: SecurityLevel>(self) -> Result<KeyShare<E, L>, Self> {
+        if !self.security_level().matches::<L>() {
+            return Err(self);
+        }
+        let key_share: DirtyKeyShare<E, L> = match self {
+            Self::SecurityLevel128(key_share) => cast_security_level(key_share.into_inner()),
+            Self::SecurityLevel192(key_share) => cast_security_level(key_share.into_inner()),
+            Self::SecurityLevel256(key_share) => cast_security_level(key_share.into_inner()),
+        };
+        // Security levels are identical, so the key share remains valid
+        #[allow(clippy::expect_used)]
+        Ok(key_share.validate().expect("key share is valid"))
+    }
+
+    /// Converts a key share of security level `L` into dynamic key share
+    ///
+    /// Returns `None` if `L` is not one of the security levels provided by the crate
+    pub fn new<L: SecurityLevel>(key_share: KeyShare<E, L>) -> Option<Self> {
+        from_dirty(cast_security_level(key_share.into_inner()), |d| {
+            d.matches::<L>()
+        })
+        .ok()
+    }
+}
+
+impl<E: Curve> AsRef<IncompleteKeyShare<E>> for DynKeyShare<E> {
+    fn as_ref(&self) -> &IncompleteKeyShare<E> {
+        match self {
+            Self::SecurityLevel128(key_share) => key_share.as_ref(),
+            Self::SecurityLevel192(key_share) => key_share.as_ref(),
+            Self::SecurityLevel256(key_share) => key_share.as_ref(),
+        }
+    }
+}
+
+impl<E: Curve> From<KeyShare<E, SecurityLevel128>> for DynKeyShare<E> {
+    fn from(key_share: KeyShare<E, SecurityLevel128>) -> Self {
+        Self::SecurityLevel128(key_share)
+    }
+}
+impl<E: Curve> From<KeyShare<E, SecurityLevel192>> for DynKeyShare<E> {
+    fn from(key_share: KeyShare<E, SecurityLevel192>) -> Self {
+        Self::SecurityLevel192(key_share)
+    }
+}
+impl<E: Curve> From<KeyShare<E, SecurityLevel256>> for DynKeyShare<E> {
+    fn from(key_share: KeyShare<E, SecurityLevel256>) -> Self {
+        Self::SecurityLevel256(key_share)
+    }
+}
+
+/// Same as [`DirtyKeyShare`] but security level is not known at compile time
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct UntypedKeyShare<E: Curve> {
+    core: DirtyIncompleteKeyShare<E>,
+    aux: UntypedAuxInfo,
+}
+
+/// Same as [`DirtyAuxInfo`] but security level is not known at compile time
+#[derive(Deserialize)]
+struct UntypedAuxInfo {
+    p: Integer,
+    q: Integer,
+    parties: Vec<PartyAux>,
+    #[serde(default)]
+    security_level: Option<SecurityLevelDescriptor>,
+}
+
+impl<'de, E: Curve> Deserialize<'de> for DynKeyShare<E> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+
+        let UntypedKeyShare { core, aux } = UntypedKeyShare::<E>::deserialize(deserializer)?;
+        let descriptor = aux
+            .security_level
+            .ok_or_else(|| D::Error::custom("key share doesn't specify its security level"))?;
+        let key_share = DirtyKeyShare::<E, SecurityLevel128> {
+            core,
+            aux: DirtyAuxInfo {
+                p: aux.p,
+                q: aux.q,
+                parties: aux.parties,
+                security_level: std::marker::PhantomData,
+            },
+        };
+        from_dirty(key_share, |d| *d == descriptor).map_err(D::Error::custom)
+    }
+}
+
+/// Validates the key share against security level that satisfies `is_level`
+fn from_dirty<E: Curve>(
+    key_share: DirtyKeyShare<E, SecurityLevel128>,
+    is_level: impl Fn(&SecurityLevelDescriptor) -> bool,
+) -> Result<DynKeyShare<E>, String> {
+    fn validate<E: Curve, L: SecurityLevel>(
+        key_share: DirtyKeyShare<E, SecurityLevel128>,
+    ) -> Result<KeyShare<E, L>, String> {
+        cast_security_level(key_share)
+            .validate()
+            .map_err(|err| err.into_error().to_string())
+    }
+
+    if is_level(&SecurityLevelDescriptor::of::<SecurityLevel128>()) {
+        validate(key_share).map(DynKeyShare::SecurityLevel128)
+    } else if is_level(&SecurityLevelDescriptor::of::<SecurityLevel192>()) {
+        validate(key_share).map(DynKeyShare::SecurityLevel192)
+    } else if is_level(&SecurityLevelDescriptor::of::<SecurityLevel256>()) {
+        validate(key_share).map(DynKeyShare::SecurityLevel256)
+    } else {
+        Err("unknown security level".into())
+    }
+}
+
+/// Changes type-level security level of the key share
+///
+/// Key share must be validated after the conversion.
+fn cast_security_level<E: Curve, L1: SecurityLevel, L2: SecurityLevel>(
+    key_share: DirtyKeyShare<E, L1>,
+) -> DirtyKeyShare<E, L2> {
+    let DirtyKeyShare { core, mut aux } = key_share;
+    DirtyKeyShare {
+        core,
+        aux: DirtyAuxInfo {
+            p: std::mem::take(&mut aux.p),
+            q: std::mem::take(&mut aux.q),
+            parties: std::mem::take(&mut aux.parties),
+            security_level: std::marker::PhantomData,
+        },
+    }
+}
@@ -0,0 +1,234 @@
+//! Aux info shared between many keys
+//!
+//! The same aux info can be used with many keys, however [`KeyShare`] embeds its own copy of aux
+//! data. [`KeyShareRef`] is an alternative representation that holds a shared reference to the aux
+//! info instead, and [`SharedAuxStore`] keeps a single copy of each aux info looked up by its
+//! [fingerprint](AuxFingerprint).
+//!
+//! ## Example
+//! ```rust,no_run
+//! use cggmp21::key_share::{KeyShareRef, SharedAuxStore, Validate};
+//! # type E = cggmp21::supported_curves::Secp256k1;
+//! # let key_shares: Vec<cggmp21::KeyShare<E>> = vec![];
+//!
+//! let mut store = SharedAuxStore::new();
+//! let shares: Vec<KeyShareRef<E>> = key_shares
+//!     .into_iter()
+//!     .map(|key_share| store.share_aux(key_share))
+//!     .collect::<Result<_, _>>()?;
+//!
+//! // Core shares can be persisted along with the fingerprint of aux info they use, and
+//! // later bound back to the aux info from the store
+//! let (core, fingerprint) = (shares[0].core.clone(), shares[0].aux_fingerprint());
+//! let share: KeyShareRef<E> = store.key_share(core.validate()?, &fingerprint)?;
+//! # Ok::<_, Box<dyn std::error::Error>>(())
+//! ```
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use generic_ec::Curve;
+use thiserror::Error;
+
+use crate::security_level::SecurityLevel;
+
+use super::compact::AuxFingerprint;
+use super::{
+    AuxInfo, DirtyAuxInfo, DirtyIncompleteKeyShare, DirtyKeyShare, IncompleteKeyShare,
+    InvalidKeyShare, KeyShare, Valid, Validate, ValidateFromParts,
+};
+
+/// Key share that refers to a shared aux info
+///
+/// Same as [`KeyShare`], but aux info is not owned by the key share, so it can be used with many
+/// keys without being copied.
+pub type KeyShareRef<E, L = crate::default_choice::SecurityLevel> = Valid<DirtyKeyShareRef<E, L>>;
+
+/// Dirty (unvalidated) key share that refers to a shared aux info
+#[derive(Clone)]
+pub struct DirtyKeyShareRef<E: Curve, L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    /// Core key share
+    pub core: DirtyIncompleteKeyShare<E>,
+    /// Shared auxiliary info
+    pub aux: Arc<AuxInfo<L>>,
+}
+
+impl<E: Curve, L: SecurityLevel> Validate for DirtyKeyShareRef<E, L> {
+    type Error = InvalidKeyShare;
+
+    fn is_valid(&self) -> Result<(), InvalidKeyShare> {
+        self.core.is_valid()?;
+        DirtyKeyShare::<E, L>::validate_consistency(&self.core, &self.aux)
+    }
+}
+
+impl<E: Curve, L: SecurityLevel> ValidateFromParts<(IncompleteKeyShare<E>, Arc<AuxInfo<L>>)>
+    for DirtyKeyShareRef<E, L>
+{
+    fn validate_parts(
+        (core, aux): &(IncompleteKeyShare<E>, Arc<AuxInfo<L>>),
+    ) -> Result<(), Self::Error> {
+        DirtyKeyShare::<E, L>::validate_consistency(core, aux)
+    }
+
+    fn from_parts((core, aux): (IncompleteKeyShare<E>, Arc<AuxInfo<L>>)) -> Self {
+        Self {
+            core: core.into_inner(),
+            aux,
+        }
+    }
+}
+
+impl<E: Curve, L: SecurityLevel> DirtyKeyShareRef<E, L> {
+    /// Returns fingerprint of the aux info used by the key share
+    pub fn aux_fingerprint(&self) -> AuxFingerprint {
+        self.aux.fingerprint()
+    }
+
+    /// Converts into regular key share that owns a copy of aux info
+    pub fn to_key_share(&self) -> DirtyKeyShare<E, L> {
+        DirtyKeyShare {
+            core: self.core.clone(),
+            aux: DirtyAuxInfo::clone(&self.aux),
+        }
+    }
+}
+
+impl<E: Curve, L: SecurityLevel> AsRef<DirtyIncompleteKeyShare<E>> for DirtyKeyShareRef<E, L> {
+    fn as_ref(&self) -> &DirtyIncompleteKeyShare<E> {
+        &self.core
+    }
+}
+impl<E: Curve, L: SecurityLevel> AsRef<DirtyAuxInfo<L>> for DirtyKeyShareRef<E, L> {
+    fn as_ref(&self) -> &DirtyAuxInfo<L> {
+        &self.aux
+    }
+}
+
+impl<E: Curve, L: SecurityLevel> std::ops::Deref for DirtyKeyShareRef<E, L> {
+    type Target = DirtyIncompleteKeyShare<E>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.core
+    }
+}
+
+/// Store of aux infos shared between many keys
+///
+/// Keeps a single copy of each aux info, looked up by its [fingerprint](DirtyAuxInfo::fingerprint).
+/// Store is meant to be used by a single party: aux infos of different parties that were generated
+/// together have the same fingerprint.
+pub struct SharedAuxStore<L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    aux: HashMap<AuxFingerprint, Arc<AuxInfo<L>>>,
+}
+
+impl<L: SecurityLevel> SharedAuxStore<L> {
+    /// Constructs an empty store
+    pub fn new() -> Self {
+        Self {
+            aux: HashMap::new(),
+        }
+    }
+
+    /// Inserts aux info into the store
+    ///
+    /// Returns fingerprint of the aux info. If the store already contains aux info with the same
+    /// fingerprint, the stored copy is kept. Returns error if stored aux info has the same public
+    /// data but different secret primes, i.e. it belongs to another party.
+    pub fn insert(&mut self, aux: AuxInfo<L>) -> Result<AuxFingerprint, SharedAuxError> {
+        self.insert_shared(aux).map(|(fingerprint, _)| fingerprint)
+    }
+
+    /// Returns aux info with given fingerprint
+    pub fn get(&self, fingerprint: &AuxFingerprint) -> Option<&Arc<AuxInfo<L>>> {
+        self.aux.get(fingerprint)
+    }
+
+    /// Removes aux info with given fingerprint from the store
+    ///
+    /// Key shares referring to the removed aux info remain usable.
+    pub fn remove(&mut self, fingerprint: &AuxFingerprint) -> Option<Arc<AuxInfo<L>>> {
+        self.aux.remove(fingerprint)
+    }
+
+    /// Returns amount of aux infos in the store
+    pub fn len(&self) -> usize {
+        self.aux.len()
+    }
+
+    /// Checks whether the store is empty
+    pub fn is_empty(&self) -> bool {
+        self.aux.is_empty()
+    }
+
+    /// Binds a core key share to the aux info with given fingerprint
+    ///
+    /// Returns error if the store has no aux info with given fingerprint, or if the aux info
+    /// is inconsistent with the core share (e.g. amount of parties or index of the signer mismatches).
+    pub fn key_share<E: Curve>(
+        &self,
+        core: IncompleteKeyShare<E>,
+        fingerprint: &AuxFingerprint,
+    ) -> Result<KeyShareRef<E, L>, SharedAuxError> {
+        let aux = self
+            .get(fingerprint)
+            .ok_or(Reason::UnknownAux(*fingerprint))?
+            .clone();
+        KeyShareRef::from_parts((core, aux)).map_err(|err| Reason::Invalid(err.into_error()).into())
+    }
+
+    /// Converts a key share into [`KeyShareRef`], sharing its aux info via the store
+    ///
+    /// If the store already contains the same aux info, the key share will refer to it. Otherwise,
+    /// aux info of the key share is inserted into the store.
+    pub fn share_aux<E: Curve>(
+        &mut self,
+        key_share: KeyShare<E, L>,
+    ) -> Result<KeyShareRef<E, L>, SharedAuxError> {
+        let DirtyKeyShare { core, aux } = key_share.into_inner();
+        // Key share is valid, so are its parts
+        #[allow(clippy::expect_used)]
+        let (core, aux) = (
+            core.validate().expect("core of valid key share is valid"),
+            aux.validate().expect("aux of valid key share is valid"),
+        );
+        let (_, aux) = self.insert_shared(aux)?;
+        KeyShareRef::from_parts((core, aux)).map_err(|err| Reason::Invalid(err.into_error()).into())
+    }
+
+    fn insert_shared(
+        &mut self,
+        aux: AuxInfo<L>,
+    ) -> Result<(AuxFingerprint, Arc<AuxInfo<L>>), SharedAuxError> {
+        let fingerprint = aux.fingerprint();
+        let stored = self
+            .aux
+            .entry(fingerprint)
+            .or_insert_with(|| Arc::new(aux.clone()));
+        if stored.p != aux.p || stored.q != aux.q {
+            return Err(Reason::Conflict(fingerprint).into());
+        }
+        Ok((fingerprint, stored.clone()))
+    }
+}
+
+impl<L: SecurityLevel> Default for SharedAuxStore<L> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Error related to [shared aux info](SharedAuxStore)
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct SharedAuxError(#[from] Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("aux info {0} is not in the store")]
+    UnknownAux(AuxFingerprint),
+    #[error("store contains aux info {0} of another party")]
+    Conflict(AuxFingerprint),
+    #[error("aux info doesn't match the key share")]
+    Invalid(#[source] InvalidKeyShare),
+}
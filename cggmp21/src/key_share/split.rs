@@ -0,0 +1,325 @@
+//! Key shares split into public and secret halves
+//!
+//! Key share mixes public data (key info, public aux data of all parties) with secrets (secret
+//! share $x_i$, Paillier primes $p_i, q_i$). This module splits a validated key share into a public
+//! half and a secret half, so they can be stored separately: e.g. the secret half in HSM or KMS, and
+//! the public half in a database.
+//!
+//! Secret half contains a [commitment](PublicShareCommitment) to the public half it belongs to.
+//! When the halves are joined back, the commitment is checked and the resulting key share is
+//! validated, so halves of different key shares (or different generations of the same key) can
+//! not be mixed up.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use cggmp21::key_share::split;
+//! # type E = cggmp21::supported_curves::Secp256k1;
+//! # fn store_public(_: &split::PublicKeyShare<E>) {}
+//! # fn store_secret(_: &split::SecretKeyShare<E>) {}
+//! # let key_share: cggmp21::KeyShare<E> = unimplemented!();
+//!
+//! let (public, secret) = split::split_key_share(&key_share);
+//! store_public(&public);
+//! store_secret(&secret);
+//!
+//! // Later, the halves can be joined back
+//! let key_share: cggmp21::KeyShare<E> = split::join_key_share(public, secret)?;
+//! # Ok::<_, split::JoinError>(())
+//! ```
+
+use std::fmt;
+
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use paillier_zk::rug::Integer;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::security_level::SecurityLevel;
+use crate::utils;
+
+use super::{
+    DirtyAuxInfo, DirtyIncompleteKeyShare, DirtyKeyInfo, DirtyKeyShare, IncompleteKeyShare,
+    InvalidIncompleteKeyShare, InvalidKeyShare, KeyShare, PartyAux, Validate,
+};
+
+/// Commitment to the public half of a key share
+///
+/// Stored in the secret half, binds it to the public half it belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct PublicShareCommitment(#[serde(with = "hex")] [u8; 32]);
+
+impl PublicShareCommitment {
+    /// Returns bytes representation of the commitment
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl fmt::Display for PublicShareCommitment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+
+/// Public half of [incomplete key share](IncompleteKeyShare)
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PublicCoreShare<E: Curve> {
+    /// Index of local party in key generation protocol
+    pub i: u16,
+    /// Public key info
+    pub key_info: DirtyKeyInfo<E>,
+}
+
+/// Secret half of [incomplete key share](IncompleteKeyShare)
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SecretCoreShare<E: Curve> {
+    /// Commitment to the public half
+    pub commitment: PublicShareCommitment,
+    /// Secret share $x_i$
+    pub x: NonZero<SecretScalar<E>>,
+}
+
+/// Public half of [key share](KeyShare)
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct PublicKeyShare<E: Curve, L: SecurityLevel = crate::default_choice::SecurityLevel> {
+    /// Public half of the core share
+    pub core: PublicCoreShare<E>,
+    /// Public auxiliary data of all parties sharing the key
+    ///
+    /// Never contains [CRT parameters](PartyAux::crt) as they expose Paillier private key
+    pub parties: Vec<PartyAux>,
+    /// Security level that was used to generate aux info
+    #[serde(default, with = "crate::security_level::serde_descriptor")]
+    pub security_level: std::marker::PhantomData<L>,
+}
+
+/// Secret half of [key share](KeyShare)
+///
+/// Secret primes $p, q$ are wiped from memory on drop.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct SecretKeyShare<E: Curve> {
+    /// Commitment to the public half
+    pub commitment: PublicShareCommitment,
+    /// Secret share $x_i$
+    pub x: NonZero<SecretScalar<E>>,
+    /// Secret prime $p$
+    pub p: Integer,
+    /// Secret prime $q$
+    pub q: Integer,
+}
+
+impl<E: Curve> zeroize::Zeroize for SecretKeyShare<E> {
+    fn zeroize(&mut self) {
+        utils::zeroize_integer(&mut self.p);
+        utils::zeroize_integer(&mut self.q);
+    }
+}
+
+impl<E: Curve> Drop for SecretKeyShare<E> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self)
+    }
+}
+
+impl<E: Curve> PublicCoreShare<E> {
+    /// Computes commitment to the public half
+    pub fn commitment(&self) -> PublicShareCommitment {
+        commit::<E>(self.i, &self.key_info, None)
+    }
+}
+
+impl<E: Curve, L: SecurityLevel> PublicKeyShare<E, L> {
+    /// Computes commitment to the public half
+    pub fn commitment(&self) -> PublicShareCommitment {
+        commit::<E>(
+            self.core.i,
+            &self.core.key_info,
+            Some((L::SECURITY_BITS, self.parties.as_slice())),
+        )
+    }
+}
+
+/// Splits incomplete key share into public and secret halves
+pub fn split_core_share<E: Curve>(
+    key_share: &IncompleteKeyShare<E>,
+) -> (PublicCoreShare<E>, SecretCoreShare<E>) {
+    let public = PublicCoreShare {
+        i: key_share.i,
+        key_info: key_share.key_info.clone(),
+    };
+    let secret = SecretCoreShare {
+        commitment: public.commitment(),
+        x: key_share.x.clone(),
+    };
+    (public, secret)
+}
+
+/// Joins public and secret halves into incomplete key share
+///
+/// Returns error if the halves don't belong to the same key share, or if resulting key share is not valid.
+pub fn join_core_share<E: Curve>(
+    public: PublicCoreShare<E>,
+    secret: SecretCoreShare<E>,
+) -> Result<IncompleteKeyShare<E>, JoinError> {
+    if public.commitment() != secret.commitment {
+        return Err(Reason::CommitmentMismatch.into());
+    }
+    DirtyIncompleteKeyShare {
+        i: public.i,
+        key_info: public.key_info,
+        x: secret.x,
+    }
+    .validate()
+    .map_err(|err| Reason::InvalidCoreShare(err.into_error()).into())
+}
+
+/// Splits key share into public and secret halves
+pub fn split_key_share<E: Curve, L: SecurityLevel>(
+    key_share: &KeyShare<E, L>,
+) -> (PublicKeyShare<E, L>, SecretKeyShare<E>) {
+    let public = PublicKeyShare {
+        core: PublicCoreShare {
+            i: key_share.core.i,
+            key_info: key_share.core.key_info.clone(),
+        },
+        parties: key_share
+            .aux
+            .parties
+            .iter()
+            .map(|aux_j| PartyAux {
+                N: aux_j.N.clone(),
+                s: aux_j.s.clone(),
+                t: aux_j.t.clone(),
+                multiexp: aux_j.multiexp.clone(),
+                crt: None,
+            })
+            .collect(),
+        security_level: std::marker::PhantomData,
+    };
+    let secret = SecretKeyShare {
+        commitment: public.commitment(),
+        x: key_share.core.x.clone(),
+        p: key_share.aux.p.clone(),
+        q: key_share.aux.q.clone(),
+    };
+    (public, secret)
+}
+
+/// Joins public and secret halves into key share
+///
+/// Returns error if the halves don't belong to the same key share, or if resulting key share is not valid.
+/// CRT parameters are not restored, use [`DirtyKeyShare::precompute_crt`] if you need them.
+pub fn join_key_share<E: Curve, L: SecurityLevel>(
+    public: PublicKeyShare<E, L>,
+    mut secret: SecretKeyShare<E>,
+) -> Result<KeyShare<E, L>, JoinError> {
+    if public.commitment() != secret.commitment {
+        return Err(Reason::CommitmentMismatch.into());
+    }
+    DirtyKeyShare {
+        core: DirtyIncompleteKeyShare {
+            i: public.core.i,
+            key_info: public.core.key_info,
+            x: secret.x.clone(),
+        },
+        aux: DirtyAuxInfo {
+            p: std::mem::take(&mut secret.p),
+            q: std::mem::take(&mut secret.q),
+            // CRT parameters are never supposed to be in the public half
+            parties: public
+                .parties
+                .into_iter()
+                .map(|aux_j| PartyAux { crt: None, ..aux_j })
+                .collect(),
+            security_level: std::marker::PhantomData,
+        },
+    }
+    .validate()
+    .map_err(|err| Reason::InvalidKeyShare(err.into_error()).into())
+}
+
+fn commit<E: Curve>(
+    i: u16,
+    key_info: &DirtyKeyInfo<E>,
+    aux: Option<(u32, &[PartyAux])>,
+) -> PublicShareCommitment {
+    let digest = PublicShareDigest::<E> {
+        curve: E::CURVE_NAME,
+        i,
+        shared_public_key: key_info.shared_public_key,
+        public_shares: &key_info.public_shares,
+        vss_setup: key_info
+            .vss_setup
+            .as_ref()
+            .map(|s| (s.min_signers, s.I.as_slice())),
+        #[cfg(feature = "hd-wallets")]
+        chain_code: key_info.chain_code.as_ref().map(|c| udigest::Bytes(&c[..])),
+        #[cfg(not(feature = "hd-wallets"))]
+        chain_code: None,
+        epoch: key_info.epoch,
+        aux: aux.map(|(security_bits, parties)| AuxDigest {
+            security_bits,
+            parties: parties
+                .iter()
+                .map(|aux_j| PartyAuxDigest {
+                    N: &aux_j.N,
+                    s: &aux_j.s,
+                    t: &aux_j.t,
+                })
+                .collect(),
+        }),
+    };
+    PublicShareCommitment(
+        udigest::Tag::<sha2::Sha256>::new("dfns.cggmp21.key_share.split.public_share")
+            .digest(digest)
+            .into(),
+    )
+}
+
+#[derive(udigest::Digestable)]
+#[udigest(bound = "")]
+struct PublicShareDigest<'a, E: Curve> {
+    curve: &'static str,
+    i: u16,
+    shared_public_key: NonZero<Point<E>>,
+    public_shares: &'a [NonZero<Point<E>>],
+    vss_setup: Option<(u16, &'a [NonZero<Scalar<E>>])>,
+    chain_code: Option<udigest::Bytes<&'a [u8]>>,
+    epoch: u64,
+    aux: Option<AuxDigest<'a>>,
+}
+
+#[derive(udigest::Digestable)]
+struct AuxDigest<'a> {
+    security_bits: u32,
+    parties: Vec<PartyAuxDigest<'a>>,
+}
+
+#[derive(udigest::Digestable)]
+struct PartyAuxDigest<'a> {
+    #[udigest(with = utils::encoding::integer)]
+    N: &'a Integer,
+    #[udigest(with = utils::encoding::integer)]
+    s: &'a Integer,
+    #[udigest(with = utils::encoding::integer)]
+    t: &'a Integer,
+}
+
+/// Error indicating that halves of key share can't be joined
+#[derive(Debug, Error)]
+#[error(transparent)]
+pub struct JoinError(#[from] Reason);
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("secret half doesn't belong to the public half: commitment mismatch")]
+    CommitmentMismatch,
+    #[error("joined core share is invalid")]
+    InvalidCoreShare(#[source] InvalidIncompleteKeyShare),
+    #[error("joined key share is invalid")]
+    InvalidKeyShare(#[source] InvalidKeyShare),
+}
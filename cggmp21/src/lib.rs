@@ -30,9 +30,9 @@
 //! Our implementation has been audited by Kudelski. Report can be found [here][report].
 //!
 //! > About notion of threshold and non-threshold keys: originally, CGGMP21 paper does not have support of
-//! arbitrary `t` and only works with non-threshold n-out-of-n keys. We have added support of arbitrary
-//! threshold $2 \le t \le n$, however, we made it possible to opt out therhsoldness so original CGGMP21
-//! protocol can be carried out if needed.
+//! > arbitrary `t` and only works with non-threshold n-out-of-n keys. We have added support of arbitrary
+//! > threshold $2 \le t \le n$, however, we made it possible to opt out therhsoldness so original CGGMP21
+//! > protocol can be carried out if needed.
 //!
 //! ## Running the protocol
 //!
@@ -127,11 +127,25 @@
 //! The auxiliary-data generation protocol is computationally heavy as it requires the generation
 //! of safe primes and involves several zero-knowledge (ZK) proofs.
 //!
+//! ZK proofs can be computed and verified in parallel by enabling `parallel` feature and
+//! calling `.parallel(true)` on the protocol builder.
+//!
+//! Safe primes can be generated ahead of time on background threads with `PrimePool` (requires
+//! `prime-pool` feature), which keeps a target amount of `PregeneratedPrimes` ready, optionally
+//! persisting them encrypted to the disk.
+//!
+//! If Paillier key of some party is suspected to be compromised, only aux data of that party
+//! can be refreshed with `partial_aux_refresh`, instead of rerunning aux info generation for
+//! all parties.
+//!
 //! #### On reusability of the auxiliary data
 //! The CGGMP21 paper assumes that new auxiliary data is generated for each secret key that is shared.
 //! However, examination of the proof shows that this is not necessary, and a fixed group of signers
 //! can use the same auxiliary data for the secure sharing/usage of multiple keys.
 //!
+//! To avoid storing a copy of the same aux data in key share of every key, use
+//! [`KeyShareRef`](key_share::KeyShareRef) and [`SharedAuxStore`](key_share::SharedAuxStore).
+//!
 //! ### Distributed Key Generation (DKG)
 //! The DKG protocol involves all signers who will co-share a key. All signers need to agree on
 //! some basic parameters including the participants' indices, the execution ID, and the
@@ -165,6 +179,15 @@
 //!
 //! The above produces an [`IncompleteKeyShare`]. An incomplete key share can be saved on disk by serializing using
 //! [`serde` crate][serde]. Treat this material appropriately as it contains sensitive information.
+//! Key share can be stored encrypted under a passphrase or a key-encryption key with `key_share::encrypted`
+//! module (requires `encrypted` feature).
+//! Secret share can be backed up to offline custodians with `key_share::backup` module (requires `backup`
+//! feature).
+//! Signers can prove possession of their secret shares to an external auditor with `key_share::pop` module
+//! (requires `pop` feature).
+//! Public and secret halves of the key share can be stored separately with [`key_share::split`].
+//! Serialized key share carries a format version, key shares serialized by older versions of the library are
+//! upgraded on deserialization. See [`key_share::versioned`].
 //!
 //! Assuming auxiliary-data generation has already been done (see above), you can "complete" the
 //! key share using:
@@ -233,6 +256,12 @@
 //!
 //! When master key is generated, you can issue a signature for child key by setting
 //! [derivation path](signing::SigningBuilder::set_derivation_path) in the signing.
+//! Alternatively, each signer can derive a key share of the child key via
+//! [`derive_child_share`](key_share::DirtyKeyShare::derive_child_share), and use it as a regular key share.
+//!
+//! Derivation paths can be parsed from strings like `m/44/60/0/0/5`, and extended public key of
+//! secp256k1 key can be exported in BIP32 format (e.g. `xpub`) to set up a watch-only wallet, see
+//! [`key_share::bip32`].
 //!
 //! ## SPOF code: Key Import and Export
 //! CGGMP21 protocol is designed to avoid Single Point of Failure by guaranteeing that attacker would
@@ -287,7 +316,7 @@ pub use {
 };
 
 #[doc(inline)]
-pub use cggmp21_keygen::{keygen, progress, ExecutionId};
+pub use cggmp21_keygen::{echo_broadcast, keygen, progress, ExecutionId};
 
 use generic_ec::{coords::HasAffineX, Curve, Point};
 use key_share::AnyKeyShare;
@@ -296,9 +325,11 @@ use security_level::SecurityLevel;
 use signing::SigningBuilder;
 
 mod errors;
+pub mod key_conversion;
 pub mod key_refresh;
 pub mod key_share;
 pub mod security_level;
+pub mod share_recovery;
 pub mod signing;
 pub mod supported_curves;
 mod utils;
@@ -317,8 +348,8 @@ mod default_choice {
 pub mod keygen {
     #[doc(inline)]
     pub use cggmp21_keygen::{
-        msg, GenericKeygenBuilder, KeygenBuilder, KeygenError, NonThreshold,
-        ThresholdKeygenBuilder, WithThreshold,
+        msg, non_interactive, transcript, GenericKeygenBuilder, KeygenBuilder, KeygenError,
+        NonThreshold, ThresholdKeygenBuilder, WithThreshold,
     };
 
     pub use msg::non_threshold::Msg as NonThresholdMsg;
@@ -326,9 +357,11 @@ pub mod keygen {
 }
 
 pub use self::{
+    key_conversion::KeyConversionError,
     key_refresh::{KeyRefreshError, PregeneratedPrimes},
     key_share::{IncompleteKeyShare, KeyShare},
     keygen::KeygenError,
+    share_recovery::ShareRecoveryError,
     signing::{DataToSign, PartialSignature, Presignature, Signature, SigningError},
 };
 
@@ -362,6 +395,10 @@ where
 ///
 /// Doesn't work with general-threshold key shares at this point.
 ///
+/// Refreshed key share has [epoch](key_share::AnyKeyShare::epoch) incremented by one. Signing
+/// fails if signers hold key shares of different epochs. Execution ID of the next refresh can
+/// be derived via [`key_refresh::next_refresh_execution_id`].
+///
 /// PregeneratedPrimes can be obtained with [`key_refresh::PregeneratedPrimes::generate`]
 pub fn key_refresh<'a, E, L>(
     eid: ExecutionId<'a>,
@@ -375,6 +412,47 @@ where
     key_refresh::KeyRefreshBuilder::new(eid, key_share, pregenerated)
 }
 
+/// Protocol for refreshing aux info of a subset of parties
+///
+/// Parties listed in `refreshing` generate new Paillier and ring-Pedersen parameters, all the
+/// other parties verify them and update their `aux_info`. Either [`AuxInfo`](key_share::AuxInfo)
+/// or [`KeyShare`] can be provided as `aux_info`. Refreshing parties must provide
+/// [pregenerated primes](key_refresh::PartialAuxRefreshBuilder::set_pregenerated_primes).
+///
+/// Outputs updated [`AuxInfo`](key_share::AuxInfo).
+pub fn partial_aux_refresh<'a, L>(
+    eid: ExecutionId<'a>,
+    i: u16,
+    aux_info: &'a impl AsRef<key_share::AuxInfo<L>>,
+    refreshing: &'a [u16],
+) -> key_refresh::PartialAuxRefreshBuilder<'a, L>
+where
+    L: SecurityLevel,
+{
+    key_refresh::PartialAuxRefreshBuilder::new(eid, i, aux_info, refreshing)
+}
+
+/// Protocol for recovering a key share of a party that lost it
+///
+/// At least `min_signers` parties listed in `helpers` (indexes at keygen) help the party
+/// `lost_party` to recover its share. See [`share_recovery`](mod@share_recovery) module
+/// docs for details.
+pub fn share_recovery<'a>(
+    eid: ExecutionId<'a>,
+    lost_party: u16,
+    helpers: &'a [u16],
+) -> share_recovery::ShareRecoveryBuilder<'a> {
+    share_recovery::ShareRecoveryBuilder::new(eid, lost_party, helpers)
+}
+
+/// Protocol for converting additive n-out-of-n key shares into threshold n-out-of-n key shares
+///
+/// All parties sharing the key must participate. Threshold key shares can be converted back
+/// via [`key_conversion::to_additive`]. See [`key_conversion`] module docs for details.
+pub fn to_threshold(eid: ExecutionId) -> key_conversion::ToThresholdBuilder {
+    key_conversion::ToThresholdBuilder::new(eid)
+}
+
 /// Protocol for generating a signature or presignature
 pub fn signing<'r, E, L>(
     eid: ExecutionId<'r>,
@@ -423,6 +501,7 @@ mod tests {
 
         crate::key_refresh::msg::aux_only::Msg<D, L>,
         crate::key_refresh::msg::non_threshold::Msg<E, D, L>,
+        crate::key_refresh::msg::partial_aux::Msg<D, L>,
 
         crate::signing::msg::Msg<E, D>,
         crate::signing::Presignature<E>,
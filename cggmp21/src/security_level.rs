@@ -3,21 +3,28 @@
 //! Security level is defined as set of parameters in the CGGMP paper. Higher security level gives more
 //! security but makes protocol execution slower.
 //!
-//! We provide a predefined default [SecurityLevel128].
+//! We provide a predefined default [SecurityLevel128], as well as ready-made higher security levels
+//! [SecurityLevel192] and [SecurityLevel256]. Note that the overall security of the signature scheme is
+//! also bounded by the curve being used (e.g. secp256k1 provides 128 bits of security), higher security
+//! levels strengthen the parts of the protocol that do not depend on the curve: Paillier encryption
+//! and ZK proofs.
 //!
 //! You can define your own security level using macro [define_security_level]. Be sure that you properly
 //! analyzed the CGGMP paper and you understand implications. Inconsistent security level may cause unexpected
 //! unverbose runtime error or reduced security of the protocol.
 
+use serde::{Deserialize, Serialize};
+
 use crate::rug::Integer;
 
 /// Security level of CGGMP21 DKG protocol
 pub use cggmp21_keygen::security_level::SecurityLevel as KeygenSecurityLevel;
 
-/// Hardcoded value for parameter $m$ of security level
+/// Amount of iterations in a single repetition of $\Pi^{\text{mod}}$ proof
 ///
-/// Currently, [security parameter $m$](SecurityLevel::M) is hardcoded to this constant. We're going to fix that
-/// once `feature(generic_const_exprs)` is stable.
+/// Amount of iterations is fixed at compile time in the underlying implementation, so
+/// $\Pi^{\text{mod}}$ proof consists of $\lceil m / M \rceil$ repetitions to reach
+/// [security parameter $m$](SecurityLevel::M).
 pub const M: usize = 128;
 
 /// Security level of the CGGMP21 protocol
@@ -34,10 +41,8 @@ pub trait SecurityLevel: KeygenSecurityLevel {
 
     /// $m$ parameter
     ///
-    /// **Note:** currently, security parameter $m$ is hardcoded to [`M = 128`](M) due to compiler limitations.
-    /// If you implement this trait directly, actual value of $m$ will be ignored. If you're using [define_security_level] macro
-    /// it will produce a compilation error if different value of $m$ is set. We're going to fix that once `generic_const_exprs`
-    /// feature is stable.
+    /// Determines amount of iterations in $\Pi^{\text{prm}}$ proof. $\Pi^{\text{mod}}$ proof has $m$ iterations
+    /// rounded up to a multiple of [`M`](crate::security_level::M).
     const M: usize;
 
     /// $q$ parameter
@@ -67,6 +72,76 @@ pub fn max_exponents_size<L: SecurityLevel>() -> (u32, u32) {
     (x_bits, y_bits)
 }
 
+/// Runtime descriptor of security level
+///
+/// Unlike [`SecurityLevel`] which is set at compile time, descriptor can be obtained and compared at
+/// runtime. Descriptor is serialized along with [aux info](crate::key_share::DirtyAuxInfo), so key shares
+/// of different security levels can be told apart on load.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SecurityLevelDescriptor {
+    /// $\kappa$ bits of security
+    pub security_bits: u32,
+    /// $\varepsilon$ bits
+    pub epsilon: usize,
+    /// $\ell$ parameter
+    pub ell: usize,
+    /// $\ell'$ parameter
+    pub ell_prime: usize,
+    /// $m$ parameter
+    pub m: usize,
+    /// $q$ parameter
+    pub q: Integer,
+}
+
+impl SecurityLevelDescriptor {
+    /// Returns descriptor of security level `L`
+    pub fn of<L: SecurityLevel>() -> Self {
+        Self {
+            security_bits: L::SECURITY_BITS,
+            epsilon: L::EPSILON,
+            ell: L::ELL,
+            ell_prime: L::ELL_PRIME,
+            m: L::M,
+            q: L::q(),
+        }
+    }
+
+    /// Checks whether descriptor corresponds to security level `L`
+    pub fn matches<L: SecurityLevel>(&self) -> bool {
+        *self == Self::of::<L>()
+    }
+}
+
+/// Serializes [`SecurityLevelDescriptor`] in place of `PhantomData<L>`
+///
+/// On deserialization, checks that serialized descriptor matches `L`
+pub(crate) mod serde_descriptor {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{SecurityLevel, SecurityLevelDescriptor};
+
+    pub fn serialize<L: SecurityLevel, S: Serializer>(
+        _: &std::marker::PhantomData<L>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        SecurityLevelDescriptor::of::<L>().serialize(serializer)
+    }
+
+    pub fn deserialize<'de, L: SecurityLevel, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<std::marker::PhantomData<L>, D::Error> {
+        let descriptor = SecurityLevelDescriptor::deserialize(deserializer)?;
+        if !descriptor.matches::<L>() {
+            return Err(D::Error::custom(format_args!(
+                "security level mismatch: expected {} security bits, actual {}",
+                L::SECURITY_BITS,
+                descriptor.security_bits
+            )));
+        }
+        Ok(std::marker::PhantomData)
+    }
+}
+
 /// Internal module that's powers `define_security_level` macro
 #[doc(hidden)]
 pub mod _internal {
@@ -131,10 +206,6 @@ pub mod _internal {
 ///     q = (Integer::ONE.clone() << 48_u32) - 1,
 /// });
 /// ```
-///
-/// **Note:** currently, security parameter $m$ is hardcoded to the [`M = 128`](M) due to compiler limitations.
-/// Setting any other value of $m$ results into compilation error. We're going to fix that once `generic_const_exprs`
-/// feature is stable.
 #[macro_export]
 macro_rules! define_security_level {
     ($struct_name:ident {
@@ -142,7 +213,7 @@ macro_rules! define_security_level {
         epsilon = $e:expr,
         ell = $ell:expr,
         ell_prime = $ell_prime:expr,
-        m = $m:tt,
+        m = $m:expr,
         q = $q:expr,
     }) => {
         $crate::define_security_level! {
@@ -164,29 +235,20 @@ macro_rules! define_security_level {
         epsilon = $e:expr,
         ell = $ell:expr,
         ell_prime = $ell_prime:expr,
-        m = 128,
+        m = $m:expr,
         q = $q:expr,
     }) => {
         impl $crate::security_level::SecurityLevel for $struct_name {
             const EPSILON: usize = $e;
             const ELL: usize = $ell;
             const ELL_PRIME: usize = $ell_prime;
-            const M: usize = 128;
+            const M: usize = $m;
 
             fn q() -> $crate::security_level::_internal::Integer {
                 $q
             }
         }
     };
-    ($struct_name:ident {
-        epsilon = $e:expr,
-        ell = $ell:expr,
-        ell_prime = $ell_prime:expr,
-        m = $m:tt,
-        q = $q:expr,
-    }) => {
-        compile_error!(concat!("Currently, we can not set security parameter M to anything but 128 (you set m=", stringify!($m), ")"));
-    };
 }
 
 #[doc(inline)]
@@ -202,6 +264,30 @@ define_security_level!(SecurityLevel128{
     q = (Integer::ONE << 128_u32).into(),
 });
 
+// Parameters below follow guidelines from the spec with statistical security parameter $s = 128$
+// and $\lambda = 256$: $Q = 2^m$, $\varepsilon \ge 2 + s + \log Q$, $\ell' \ge 2\lambda + \varepsilon + s$.
+// $\kappa$ is chosen according to NIST recommendations on size of RSA moduli.
+
+#[doc(inline)]
+pub use cggmp21_keygen::security_level::SecurityLevel192;
+define_security_level!(SecurityLevel192{
+    epsilon = 330,
+    ell = 256,
+    ell_prime = 976,
+    m = 192,
+    q = (Integer::ONE << 192_u32).into(),
+});
+
+#[doc(inline)]
+pub use cggmp21_keygen::security_level::SecurityLevel256;
+define_security_level!(SecurityLevel256{
+    epsilon = 390,
+    ell = 256,
+    ell_prime = 1040,
+    m = 256,
+    q = (Integer::ONE << 256_u32).into(),
+});
+
 /// Checks that public paillier key meets security level constraints
 pub(crate) fn validate_public_paillier_key_size<L: SecurityLevel>(N: &Integer) -> bool {
     N.significant_bits() >= 8 * L::SECURITY_BITS - 1
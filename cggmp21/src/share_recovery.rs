@@ -0,0 +1,744 @@
+//! Key share recovery protocol
+//!
+//! Allows a party that lost its key share to recover it with help of other key share holders.
+//! At least `min_signers` parties holding their key shares (_helpers_) jointly re-derive Shamir
+//! secret share of the lost party and deliver it to that party. Each helper masks its
+//! contribution with random values that cancel out in the sum, so no helper learns the recovered
+//! share, and the party recovering the share learns nothing about shares of the helpers.
+//!
+//! The party recovering the share doesn't hold any secret, so it must be authenticated by the
+//! transport layer before the helpers deliver contributions to it. It only needs to know public
+//! [`KeyInfo`] of the key, which can be obtained from any other key share holder. Recovered share is
+//! verified against [`public_shares`](crate::key_share::DirtyKeyInfo::public_shares) of the key,
+//! so the protocol either outputs a correct key share or fails.
+//!
+//! Recovered share is [`IncompleteKeyShare`]: the party doesn't recover its auxiliary info. Instead, all
+//! parties sharing the key should generate fresh aux info via [`aux_info_gen`](crate::aux_info_gen)
+//! and complete their key shares with it.
+//!
+//! Only threshold key shares can be recovered: in non-threshold (additive) setting, share of each
+//! party is independent of shares of other parties.
+//!
+//! ## Protocol
+//! Let $\ell$ be the index of the party recovering the share, $H$ be a set of helpers, $|H| \ge t$.
+//! 1. Every helper $h \in H$ samples masks $r_{h \to j} \gets \Zq$ for each other helper $j$,
+//!    broadcasts commitments $R_{h \to j} = r_{h \to j} \cdot G$ and a hash of key info
+//!    it holds. The party $\ell$ broadcasts a hash of key info as well.
+//! 2. Every helper $h$ sends $r_{h \to j}$ to helper $j$, who checks it against $R_{h \to j}$.
+//! 3. Every helper $h$ sends $c_h = \lambda_h x_h + \sum_j r_{h \to j} - \sum_j r_{j \to h}$ to
+//!    party $\ell$, where $\lambda_h$ is the lagrange coefficient that interpolates the
+//!    polynomial at point $I_\ell$.
+//! 4. Party $\ell$ checks that $c_h \cdot G = \lambda_h X_h + \sum_j R_{h \to j} - \sum_j R_{j \to h}$
+//!    for each helper, computes $x_\ell = \sum_h c_h$ and checks that $x_\ell \cdot G = X_\ell$.
+//!
+//! ## Example
+//! ```rust,no_run
+//! # async fn doc() -> Result<(), cggmp21::share_recovery::ShareRecoveryError> {
+//! # type Msg = cggmp21::share_recovery::msg::Msg<cggmp21::supported_curves::Secp256k1, sha2::Sha256>;
+//! # let incoming = futures::stream::pending::<Result<round_based::Incoming<Msg>, std::convert::Infallible>>();
+//! # let outgoing = futures::sink::drain::<round_based::Outgoing<Msg>>();
+//! # let delivery = (incoming, outgoing);
+//! # let party = round_based::MpcParty::connected(delivery);
+//! #
+//! # use cggmp21::supported_curves::Secp256k1;
+//! # let key_info: cggmp21::key_share::KeyInfo<Secp256k1> = unimplemented!();
+//! # let mut rng = rand_core::OsRng;
+//! let eid = cggmp21::ExecutionId::new(b"execution id, unique per protocol execution");
+//! // Party 1 lost its key share, parties 0 and 2 help to recover it
+//! let lost_party = 1;
+//! let helpers = [0, 2];
+//!
+//! // Party 1 recovers its key share
+//! let key_share = cggmp21::share_recovery(eid, lost_party, &helpers)
+//!     .recover(party, &key_info)
+//!     .await?;
+//! # Ok(()) }
+//! ```
+
+use digest::Digest;
+use futures::SinkExt;
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::polynomial::lagrange_coefficient;
+use rand_core::{CryptoRng, RngCore};
+use round_based::{
+    rounds_router::{simple_store::RoundInput, RoundsRouter},
+    Delivery, Mpc, MpcParty, Outgoing,
+};
+use thiserror::Error;
+
+use crate::{
+    errors::IoError,
+    key_share::{
+        AnyKeyShare, DirtyIncompleteKeyShare, DirtyKeyInfo, IncompleteKeyShare,
+        InvalidIncompleteKeyShare, KeyInfo, Validate,
+    },
+    progress::Tracer,
+    utils::AbortBlame,
+    ExecutionId,
+};
+
+use self::msg::*;
+
+/// Message of share recovery protocol
+pub mod msg {
+    use digest::Digest;
+    use generic_ec::{Curve, Point, Scalar};
+    use round_based::ProtocolMessage;
+    use serde::{Deserialize, Serialize};
+
+    /// Message of share recovery protocol
+    #[derive(ProtocolMessage, Clone, Serialize, Deserialize)]
+    #[serde(bound = "")]
+    pub enum Msg<E: Curve, D: Digest> {
+        /// Round 1 message
+        Round1(MsgRound1<E, D>),
+        /// Round 2 message
+        Round2(MsgRound2<E>),
+        /// Round 3 message
+        Round3(MsgRound3<E>),
+    }
+
+    /// Message from round 1, broadcast by every participant
+    #[derive(Clone, Serialize, Deserialize)]
+    #[serde(bound = "")]
+    pub struct MsgRound1<E: Curve, D: Digest> {
+        /// Hash of key info held by the party
+        pub key_info_hash: digest::Output<D>,
+        /// $R_{h \to j}$
+        ///
+        /// Commitments to the masks sent to each helper. Commitment at the sender's own position
+        /// is zero. Empty if message is sent by the party recovering the share.
+        pub mask_commitments: Vec<Point<E>>,
+    }
+
+    /// Message from round 2, sent by every helper to each other helper
+    #[derive(Clone, Serialize, Deserialize)]
+    #[serde(bound = "")]
+    pub struct MsgRound2<E: Curve> {
+        /// $r_{h \to j}$
+        pub mask: Scalar<E>,
+    }
+
+    /// Message from round 3, sent by every helper to the party recovering the share
+    #[derive(Clone, Serialize, Deserialize)]
+    #[serde(bound = "")]
+    pub struct MsgRound3<E: Curve> {
+        /// $c_h$
+        pub contribution: Scalar<E>,
+    }
+}
+
+/// Tag used to hash key info
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.share_recovery.tag")]
+struct Tag<'a> {
+    #[udigest(as_bytes)]
+    sid: &'a [u8],
+    lost_party: u16,
+    helpers: &'a [u16],
+}
+
+/// Public data of the key that is hashed to ensure all parties hold the same key info
+#[derive(udigest::Digestable)]
+#[udigest(tag = "dfns.cggmp21.share_recovery.key_info")]
+#[udigest(bound = "")]
+pub(crate) struct KeyInfoDigest<'a, E: Curve> {
+    shared_public_key: NonZero<Point<E>>,
+    public_shares: &'a [NonZero<Point<E>>],
+    vss_setup: Option<(u16, &'a [NonZero<Scalar<E>>])>,
+    chain_code: Option<udigest::Bytes<&'a [u8]>>,
+    epoch: u64,
+}
+
+impl<'a, E: Curve> KeyInfoDigest<'a, E> {
+    pub(crate) fn new(key_info: &'a DirtyKeyInfo<E>) -> Self {
+        Self {
+            shared_public_key: key_info.shared_public_key,
+            public_shares: &key_info.public_shares,
+            vss_setup: key_info
+                .vss_setup
+                .as_ref()
+                .map(|s| (s.min_signers, s.I.as_slice())),
+            #[cfg(feature = "hd-wallets")]
+            chain_code: key_info.chain_code.as_ref().map(|c| udigest::Bytes(&c[..])),
+            #[cfg(not(feature = "hd-wallets"))]
+            chain_code: None,
+            epoch: key_info.epoch,
+        }
+    }
+}
+
+/// Share recovery protocol builder
+///
+/// Build it via [`cggmp21::share_recovery`](crate::share_recovery()). Helpers start the protocol
+/// via [`help`](Self::help), and the party that lost its share via [`recover`](Self::recover).
+pub struct ShareRecoveryBuilder<'a, D = crate::default_choice::Digest>
+where
+    D: Digest,
+{
+    execution_id: ExecutionId<'a>,
+    lost_party: u16,
+    helpers: &'a [u16],
+    tracer: Option<&'a mut dyn Tracer>,
+    _digest: std::marker::PhantomData<D>,
+}
+
+impl<'a, D> ShareRecoveryBuilder<'a, D>
+where
+    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+{
+    /// Constructs a builder
+    ///
+    /// `lost_party` is the index (at keygen) of the party recovering its key share, `helpers`
+    /// are indexes (at keygen) of parties helping to recover it. At least `min_signers` helpers
+    /// are required.
+    ///
+    /// Within the protocol, helper `helpers[j]` has index `j`, and the party recovering the share
+    /// has index `helpers.len()`.
+    pub fn new(eid: ExecutionId<'a>, lost_party: u16, helpers: &'a [u16]) -> Self {
+        Self {
+            execution_id: eid,
+            lost_party,
+            helpers,
+            tracer: None,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    /// Specifies another hash function to use
+    pub fn set_digest<D2>(self) -> ShareRecoveryBuilder<'a, D2>
+    where
+        D2: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+    {
+        ShareRecoveryBuilder {
+            execution_id: self.execution_id,
+            lost_party: self.lost_party,
+            helpers: self.helpers,
+            tracer: self.tracer,
+            _digest: std::marker::PhantomData,
+        }
+    }
+
+    /// Specifies a tracer that tracks progress of protocol execution
+    pub fn set_progress_tracer(mut self, tracer: &'a mut dyn Tracer) -> Self {
+        self.tracer = Some(tracer);
+        self
+    }
+
+    /// Helps the lost party to recover its key share
+    ///
+    /// `key_share` must belong to one of the helpers.
+    pub async fn help<E, R, M>(
+        self,
+        rng: &mut R,
+        party: M,
+        key_share: &impl AnyKeyShare<E>,
+    ) -> Result<(), ShareRecoveryError>
+    where
+        E: Curve,
+        R: RngCore + CryptoRng,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        run_helper(
+            self.tracer,
+            rng,
+            party,
+            self.execution_id,
+            self.lost_party,
+            self.helpers,
+            key_share.as_ref(),
+        )
+        .await
+    }
+
+    /// Recovers a key share of the lost party
+    ///
+    /// `key_info` is the public information about the key, which can be obtained
+    /// from any other key share holder.
+    pub async fn recover<E, M>(
+        self,
+        party: M,
+        key_info: &KeyInfo<E>,
+    ) -> Result<IncompleteKeyShare<E>, ShareRecoveryError>
+    where
+        E: Curve,
+        M: Mpc<ProtocolMessage = Msg<E, D>>,
+    {
+        run_recovery(
+            self.tracer,
+            party,
+            self.execution_id,
+            self.lost_party,
+            self.helpers,
+            key_info,
+        )
+        .await
+    }
+}
+
+/// Validates arguments, returns share preimages of the helpers
+fn validate_args<E: Curve>(
+    key_info: &DirtyKeyInfo<E>,
+    lost_party: u16,
+    helpers: &[u16],
+) -> Result<Vec<NonZero<Scalar<E>>>, InvalidArgs> {
+    let vss_setup = key_info
+        .vss_setup
+        .as_ref()
+        .ok_or(InvalidArgs::NonThresholdKey)?;
+    let n = key_info.public_shares.len();
+    if usize::from(lost_party) >= n {
+        return Err(InvalidArgs::LostPartyOutOfBounds);
+    }
+    if helpers.len() < usize::from(vss_setup.min_signers) {
+        return Err(InvalidArgs::TooFewHelpers);
+    }
+    if helpers.len() >= n {
+        return Err(InvalidArgs::TooManyHelpers);
+    }
+    for (k, &j) in helpers.iter().enumerate() {
+        if usize::from(j) >= n {
+            return Err(InvalidArgs::HelperOutOfBounds);
+        }
+        if j == lost_party {
+            return Err(InvalidArgs::LostPartyIsHelper);
+        }
+        if helpers[..k].contains(&j) {
+            return Err(InvalidArgs::DuplicatedHelper);
+        }
+    }
+    crate::utils::subset(helpers, &vss_setup.I).ok_or(InvalidArgs::HelperOutOfBounds)
+}
+
+async fn run_helper<E, R, M, D>(
+    mut tracer: Option<&mut dyn Tracer>,
+    rng: &mut R,
+    party: M,
+    sid: ExecutionId<'_>,
+    lost_party: u16,
+    helpers: &[u16],
+    key_share: &DirtyIncompleteKeyShare<E>,
+) -> Result<(), ShareRecoveryError>
+where
+    E: Curve,
+    R: RngCore + CryptoRng,
+    M: Mpc<ProtocolMessage = Msg<E, D>>,
+    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+{
+    tracer.protocol_begins();
+
+    tracer.stage("Validate arguments");
+    let I = validate_args(&key_share.key_info, lost_party, helpers)?;
+    let h = u16::try_from(I.len()).map_err(|_| InvalidArgs::TooManyHelpers)?;
+    let i: u16 = helpers
+        .iter()
+        .position(|&j| j == key_share.i)
+        .ok_or(InvalidArgs::NotAHelper)?
+        .try_into()
+        .map_err(|_| InvalidArgs::TooManyHelpers)?;
+    let I_lost = key_share
+        .share_preimage(lost_party)
+        .ok_or(InvalidArgs::LostPartyOutOfBounds)?;
+    let lambda_i =
+        lagrange_coefficient(I_lost.into(), usize::from(i), &I).ok_or(Bug::LagrangeCoef)?;
+
+    tracer.stage("Setup networking");
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incomings, mut outgoings) = delivery.split();
+
+    let mut rounds = RoundsRouter::<Msg<E, D>>::builder();
+    let round1 = rounds.add_round(RoundInput::<MsgRound1<E, D>>::broadcast(i, h + 1));
+    let round2 = rounds.add_round(RoundInput::<MsgRound2<E>>::p2p(i, h));
+    let mut rounds = rounds.listen(incomings);
+
+    // Round 1
+    tracer.round_begins();
+
+    tracer.stage("Hash key info");
+    let key_info_hash = udigest::Tag::<D>::new_structured(Tag {
+        sid: sid.as_bytes(),
+        lost_party,
+        helpers,
+    })
+    .digest(KeyInfoDigest::new(&key_share.key_info));
+
+    tracer.stage("Sample masks");
+    let masks = (0..h)
+        .map(|j| {
+            if j == i {
+                SecretScalar::<E>::zero()
+            } else {
+                SecretScalar::random(rng)
+            }
+        })
+        .collect::<Vec<_>>();
+    let mask_commitments = masks
+        .iter()
+        .map(|r| Point::generator() * r)
+        .collect::<Vec<_>>();
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round1(MsgRound1 {
+            key_info_hash,
+            mask_commitments,
+        })))
+        .await
+        .map_err(IoError::send_message)?;
+    for j in crate::utils::iter_peers(i, h) {
+        outgoings
+            .send(Outgoing::p2p(
+                j,
+                Msg::Round2(MsgRound2 {
+                    mask: *masks[usize::from(j)].as_ref(),
+                }),
+            ))
+            .await
+            .map_err(IoError::send_message)?;
+    }
+    tracer.msg_sent();
+
+    // Round 2
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let round1_msgs = rounds
+        .complete(round1)
+        .await
+        .map_err(IoError::receive_message)?;
+    let round2_msgs = rounds
+        .complete(round2)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    tracer.stage("Validate key info hashes");
+    let blame = round1_msgs
+        .iter_indexed()
+        .filter(|(_, _, msg)| msg.key_info_hash != key_info_hash)
+        .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(ShareRecoveryAborted::MismatchedKeyInfo(blame).into());
+    }
+
+    tracer.stage("Validate received masks");
+    let round1_msgs_vec = round1_msgs.iter_indexed().collect::<Vec<_>>();
+    let mut blame = vec![];
+    for (j, mask_msg_id, mask_msg) in round2_msgs.iter_indexed() {
+        let (_, commitment_msg_id, commitment_msg) = round1_msgs_vec
+            .iter()
+            .find(|(j2, _, _)| *j2 == j)
+            .ok_or(Bug::MissingMessage)?;
+        let valid = commitment_msg.mask_commitments.len() == usize::from(h)
+            && Point::generator() * mask_msg.mask
+                == commitment_msg.mask_commitments[usize::from(i)];
+        if !valid {
+            blame.push(AbortBlame::new(j, *commitment_msg_id, mask_msg_id))
+        }
+    }
+    if !blame.is_empty() {
+        return Err(ShareRecoveryAborted::InvalidMask(blame).into());
+    }
+
+    tracer.stage("Compute contribution");
+    let sent_masks = masks.iter().map(|r| r.as_ref()).sum::<Scalar<E>>();
+    let received_masks = round2_msgs.iter().map(|msg| msg.mask).sum::<Scalar<E>>();
+    let contribution = lambda_i * &key_share.x + sent_masks - received_masks;
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::p2p(h, Msg::Round3(MsgRound3 { contribution })))
+        .await
+        .map_err(IoError::send_message)?;
+    tracer.msg_sent();
+
+    tracer.protocol_ends();
+    Ok(())
+}
+
+async fn run_recovery<E, M, D>(
+    mut tracer: Option<&mut dyn Tracer>,
+    party: M,
+    sid: ExecutionId<'_>,
+    lost_party: u16,
+    helpers: &[u16],
+    key_info: &KeyInfo<E>,
+) -> Result<IncompleteKeyShare<E>, ShareRecoveryError>
+where
+    E: Curve,
+    M: Mpc<ProtocolMessage = Msg<E, D>>,
+    D: Digest<OutputSize = digest::typenum::U32> + Clone + 'static,
+{
+    tracer.protocol_begins();
+
+    tracer.stage("Validate arguments");
+    let I = validate_args(key_info, lost_party, helpers)?;
+    let h = u16::try_from(I.len()).map_err(|_| InvalidArgs::TooManyHelpers)?;
+    let I_lost = key_info
+        .share_preimage(lost_party)
+        .ok_or(InvalidArgs::LostPartyOutOfBounds)?;
+    let lambda = (0..usize::from(h))
+        .map(|j| lagrange_coefficient(I_lost.into(), j, &I))
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Bug::LagrangeCoef)?;
+    let public_shares = crate::utils::subset(helpers, &key_info.public_shares)
+        .ok_or(InvalidArgs::HelperOutOfBounds)?;
+
+    tracer.stage("Setup networking");
+    let MpcParty { delivery, .. } = party.into_party();
+    let (incomings, mut outgoings) = delivery.split();
+
+    let mut rounds = RoundsRouter::<Msg<E, D>>::builder();
+    let round1 = rounds.add_round(RoundInput::<MsgRound1<E, D>>::broadcast(h, h + 1));
+    let round3 = rounds.add_round(RoundInput::<MsgRound3<E>>::p2p(h, h + 1));
+    let mut rounds = rounds.listen(incomings);
+
+    // Round 1
+    tracer.round_begins();
+
+    tracer.stage("Hash key info");
+    let key_info_hash = udigest::Tag::<D>::new_structured(Tag {
+        sid: sid.as_bytes(),
+        lost_party,
+        helpers,
+    })
+    .digest(KeyInfoDigest::new(key_info));
+
+    tracer.send_msg();
+    outgoings
+        .send(Outgoing::broadcast(Msg::Round1(MsgRound1 {
+            key_info_hash,
+            mask_commitments: vec![],
+        })))
+        .await
+        .map_err(IoError::send_message)?;
+    tracer.msg_sent();
+
+    // Round 2
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let round1_msgs = rounds
+        .complete(round1)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    tracer.stage("Validate key info hashes and mask commitments");
+    let blame = round1_msgs
+        .iter_indexed()
+        .filter(|(_, _, msg)| msg.key_info_hash != key_info_hash)
+        .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(ShareRecoveryAborted::MismatchedKeyInfo(blame).into());
+    }
+    let blame = round1_msgs
+        .iter_indexed()
+        .filter(|(_, _, msg)| msg.mask_commitments.len() != usize::from(h))
+        .map(|(j, msg_id, _)| AbortBlame::new(j, msg_id, msg_id))
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(ShareRecoveryAborted::InvalidMaskCommitments(blame).into());
+    }
+
+    // Round 3
+    tracer.round_begins();
+
+    tracer.receive_msgs();
+    let round3_msgs = rounds
+        .complete(round3)
+        .await
+        .map_err(IoError::receive_message)?;
+    tracer.msgs_received();
+
+    tracer.stage("Validate contributions");
+    let R = round1_msgs
+        .iter()
+        .map(|msg| msg.mask_commitments.as_slice())
+        .collect::<Vec<_>>();
+    let blame = round1_msgs
+        .iter_indexed()
+        .zip(round3_msgs.iter_indexed())
+        .filter(|((j, _, _), (_, _, msg))| {
+            let j = usize::from(*j);
+            let sent_masks = R[j].iter().sum::<Point<E>>();
+            let received_masks = R.iter().map(|R_k| R_k[j]).sum::<Point<E>>();
+            Point::generator() * msg.contribution
+                != lambda[j] * public_shares[j] + sent_masks - received_masks
+        })
+        .map(|((j, commitment_msg_id, _), (_, contribution_msg_id, _))| {
+            AbortBlame::new(j, commitment_msg_id, contribution_msg_id)
+        })
+        .collect::<Vec<_>>();
+    if !blame.is_empty() {
+        return Err(ShareRecoveryAborted::InvalidContribution(blame).into());
+    }
+
+    tracer.stage("Recover secret share");
+    let mut x = round3_msgs
+        .iter()
+        .map(|msg| msg.contribution)
+        .sum::<Scalar<E>>();
+    let x = NonZero::from_secret_scalar(SecretScalar::new(&mut x))
+        .ok_or(ShareRecoveryAborted::RecoveredShareMismatch)?;
+    if Point::generator() * &x != key_info.public_shares[usize::from(lost_party)] {
+        return Err(ShareRecoveryAborted::RecoveredShareMismatch.into());
+    }
+
+    let key_share = DirtyIncompleteKeyShare {
+        i: lost_party,
+        key_info: (**key_info).clone(),
+        x,
+    }
+    .validate()
+    .map_err(|err| Bug::InvalidKeyShare(err.into_error()))?;
+
+    tracer.protocol_ends();
+    Ok(key_share)
+}
+
+/// Error indicating that share recovery failed
+#[derive(Debug, Error)]
+#[error("share recovery failed")]
+pub struct ShareRecoveryError(#[source] Reason);
+
+crate::errors::impl_from! {
+    impl From for ShareRecoveryError {
+        err: InvalidArgs => ShareRecoveryError(Reason::InvalidArgs(err)),
+        err: ShareRecoveryAborted => ShareRecoveryError(Reason::Aborted(err)),
+        err: IoError => ShareRecoveryError(Reason::IoError(err)),
+        err: Bug => ShareRecoveryError(Reason::Bug(err)),
+    }
+}
+
+#[derive(Debug, Error)]
+enum Reason {
+    #[error("invalid arguments")]
+    InvalidArgs(#[source] InvalidArgs),
+    /// Protocol was maliciously aborted by another party
+    #[error("protocol was aborted by malicious party")]
+    Aborted(#[source] ShareRecoveryAborted),
+    #[error("i/o error")]
+    IoError(#[source] IoError),
+    /// Bug occurred
+    #[error("bug occurred")]
+    Bug(#[source] Bug),
+}
+
+#[derive(Debug, Error)]
+enum InvalidArgs {
+    #[error("only threshold key shares can be recovered")]
+    NonThresholdKey,
+    #[error("index of the party recovering the share is out of bounds (must be < n)")]
+    LostPartyOutOfBounds,
+    #[error("party recovering the share can not be a helper")]
+    LostPartyIsHelper,
+    #[error("at least `min_signers` helpers are required")]
+    TooFewHelpers,
+    #[error("too many helpers (must be < n)")]
+    TooManyHelpers,
+    #[error("helper index is out of bounds (must be < n)")]
+    HelperOutOfBounds,
+    #[error("helpers list contains duplicates")]
+    DuplicatedHelper,
+    #[error("key share doesn't belong to any of helpers")]
+    NotAHelper,
+}
+
+/// Error indicating that protocol was aborted by malicious party
+#[derive(Debug, Error)]
+enum ShareRecoveryAborted {
+    #[error("parties hold different key info")]
+    MismatchedKeyInfo(Vec<AbortBlame>),
+    #[error("mask commitments are malformed")]
+    InvalidMaskCommitments(Vec<AbortBlame>),
+    #[error("mask doesn't match its commitment")]
+    InvalidMask(Vec<AbortBlame>),
+    #[error("contribution doesn't match public data")]
+    InvalidContribution(Vec<AbortBlame>),
+    #[error("recovered share doesn't match its public commitment")]
+    RecoveredShareMismatch,
+}
+
+#[derive(Debug, Error)]
+enum Bug {
+    #[error("couldn't derive lagrange coefficient")]
+    LagrangeCoef,
+    #[error("message is missing")]
+    MissingMessage,
+    #[error("recovered key share is not valid")]
+    InvalidKeyShare(#[source] InvalidIncompleteKeyShare),
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::key_share::{DirtyKeyInfo, VssSetup};
+    use generic_ec::{NonZero, Point, Scalar};
+
+    use super::{validate_args, InvalidArgs};
+
+    type E = crate::supported_curves::Secp256k1;
+
+    fn key_info(n: u16, t: Option<u16>) -> DirtyKeyInfo<E> {
+        let public_shares = (1..=n)
+            .map(|j| NonZero::from_point(Point::generator() * Scalar::from(j)).unwrap())
+            .collect::<Vec<_>>();
+        DirtyKeyInfo {
+            curve: Default::default(),
+            shared_public_key: public_shares[0],
+            public_shares,
+            vss_setup: t.map(|min_signers| VssSetup {
+                min_signers,
+                I: (1..=n)
+                    .map(|j| NonZero::from_scalar(Scalar::from(j)).unwrap())
+                    .collect(),
+            }),
+            #[cfg(feature = "hd-wallets")]
+            chain_code: None,
+            epoch: 0,
+        }
+    }
+
+    #[test]
+    fn invalid_args_are_rejected() {
+        let key_info = key_info(5, Some(3));
+        let I = validate_args(&key_info, 1, &[0, 2, 4]).unwrap();
+        assert_eq!(I.len(), 3);
+        let I = validate_args(&key_info, 1, &[4, 3, 2, 0]).unwrap();
+        assert_eq!(
+            I,
+            [5, 4, 3, 1].map(|j| NonZero::from_scalar(Scalar::from(j)).unwrap())
+        );
+
+        let err = |lost_party, helpers: &[u16]| validate_args(&key_info, lost_party, helpers);
+        assert!(matches!(
+            err(5, &[0, 2, 4]),
+            Err(InvalidArgs::LostPartyOutOfBounds)
+        ));
+        assert!(matches!(err(1, &[0, 2]), Err(InvalidArgs::TooFewHelpers)));
+        assert!(matches!(
+            err(1, &[0, 1, 2]),
+            Err(InvalidArgs::LostPartyIsHelper)
+        ));
+        assert!(matches!(
+            err(1, &[0, 2, 5]),
+            Err(InvalidArgs::HelperOutOfBounds)
+        ));
+        assert!(matches!(
+            err(1, &[0, 2, 2]),
+            Err(InvalidArgs::DuplicatedHelper)
+        ));
+        assert!(matches!(
+            err(1, &[0, 1, 2, 3, 4]),
+            Err(InvalidArgs::TooManyHelpers)
+        ));
+
+        let key_info = self::key_info(3, None);
+        assert!(matches!(
+            validate_args(&key_info, 1, &[0, 2]),
+            Err(InvalidArgs::NonThresholdKey)
+        ));
+    }
+}
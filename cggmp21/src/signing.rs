@@ -19,10 +19,14 @@ use round_based::{
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use zeroize::Zeroizing;
 
 use crate::errors::IoError;
-use crate::key_share::{KeyShare, PartyAux, VssSetup};
+use crate::key_share::{
+    DirtyAuxInfo, DirtyIncompleteKeyShare, KeyShare, KeyShareRef, PartyAux, VssSetup,
+};
 use crate::progress::Tracer;
+use crate::utils::SecretInteger;
 use crate::{key_share::InvalidKeyShare, security_level::SecurityLevel, utils, ExecutionId};
 
 use self::msg::*;
@@ -70,6 +74,10 @@ impl<E: Curve> DataToSign<E> {
 /// Presignature, can be used to issue a [partial signature](PartialSignature) without interacting with other signers
 ///
 /// [Threshold](crate::key_share::AnyKeyShare::min_signers) amount of partial signatures (from different signers) can be [combined](PartialSignature::combine) into regular signature
+///
+/// Secret components $k$ and $\chi$ are wiped from memory on drop. Note that Paillier decryption key
+/// and copy of CRT parameters used while generating the presignature are deallocated without being
+/// wiped, refer to [`DirtyAuxInfo`](crate::key_share::DirtyAuxInfo) docs.
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(bound = "")]
 pub struct Presignature<E: Curve> {
@@ -152,6 +160,8 @@ pub mod msg {
         /// $G_i$
         #[udigest(with = utils::encoding::integer)]
         pub G: fast_paillier::Ciphertext,
+        /// Epoch of the signer's key share
+        pub epoch: u64,
     }
 
     /// Message from round 1b
@@ -222,7 +232,8 @@ pub struct SigningBuilder<
 {
     i: PartyIndex,
     parties_indexes_at_keygen: &'r [PartyIndex],
-    key_share: &'r KeyShare<E, L>,
+    core: &'r DirtyIncompleteKeyShare<E>,
+    aux: &'r DirtyAuxInfo<L>,
     execution_id: ExecutionId<'r>,
     tracer: Option<&'r mut dyn Tracer>,
     enforce_reliable_broadcast: bool,
@@ -245,11 +256,47 @@ where
         i: PartyIndex,
         parties_indexes_at_keygen: &'r [PartyIndex],
         secret_key_share: &'r KeyShare<E, L>,
+    ) -> Self {
+        Self::from_parts(
+            eid,
+            i,
+            parties_indexes_at_keygen,
+            &secret_key_share.core,
+            &secret_key_share.aux,
+        )
+    }
+
+    /// Construct a signing builder from a key share that refers to a shared aux info
+    ///
+    /// Refer to [`KeyShareRef`] for the details.
+    pub fn new_with_shared_aux(
+        eid: ExecutionId<'r>,
+        i: PartyIndex,
+        parties_indexes_at_keygen: &'r [PartyIndex],
+        secret_key_share: &'r KeyShareRef<E, L>,
+    ) -> Self {
+        Self::from_parts(
+            eid,
+            i,
+            parties_indexes_at_keygen,
+            &secret_key_share.core,
+            &secret_key_share.aux,
+        )
+    }
+
+    /// Constructs a builder from core share and aux info that are guaranteed to be consistent
+    fn from_parts(
+        eid: ExecutionId<'r>,
+        i: PartyIndex,
+        parties_indexes_at_keygen: &'r [PartyIndex],
+        core: &'r DirtyIncompleteKeyShare<E>,
+        aux: &'r DirtyAuxInfo<L>,
     ) -> Self {
         Self {
             i,
             parties_indexes_at_keygen,
-            key_share: secret_key_share,
+            core,
+            aux,
             execution_id: eid,
             tracer: None,
             enforce_reliable_broadcast: true,
@@ -267,7 +314,8 @@ where
         SigningBuilder {
             i: self.i,
             parties_indexes_at_keygen: self.parties_indexes_at_keygen,
-            key_share: self.key_share,
+            core: self.core,
+            aux: self.aux,
             tracer: self.tracer,
             enforce_reliable_broadcast: self.enforce_reliable_broadcast,
             execution_id: self.execution_id,
@@ -308,6 +356,18 @@ where
     ///     .set_derivation_path([1, 999])?
     /// # ; Ok::<_, Box<dyn std::error::Error>>(())
     /// ```
+    ///
+    /// Derivation path can also be parsed from a string, see [`bip32::DerivationPath`](crate::key_share::bip32::DerivationPath)
+    ///
+    /// ```rust,no_run
+    /// # let eid = cggmp21::ExecutionId::new(b"protocol nonce");
+    /// # let (i, parties_indexes_at_keygen, key_share): (u16, Vec<u16>, cggmp21::KeyShare<cggmp21::supported_curves::Secp256k1>)
+    /// # = unimplemented!();
+    /// let path: cggmp21::key_share::bip32::DerivationPath = "m/1/999".parse()?;
+    /// cggmp21::signing(eid, i, &parties_indexes_at_keygen, &key_share)
+    ///     .set_derivation_path(&path)?
+    /// # ; Ok::<_, Box<dyn std::error::Error>>(())
+    /// ```
     #[cfg(feature = "hd-wallets")]
     pub fn set_derivation_path<Index>(
         mut self,
@@ -317,10 +377,7 @@ where
         slip_10::NonHardenedIndex: TryFrom<Index>,
     {
         use crate::key_share::HdError;
-        let public_key = self
-            .key_share
-            .extended_public_key()
-            .ok_or(HdError::DisabledHd)?;
+        let public_key = self.core.extended_public_key().ok_or(HdError::DisabledHd)?;
         self.additive_shift =
             Some(derive_additive_shift(public_key, path).map_err(HdError::InvalidPath)?);
         Ok(self)
@@ -342,7 +399,8 @@ where
             party,
             self.execution_id,
             self.i,
-            self.key_share,
+            self.core,
+            self.aux,
             self.parties_indexes_at_keygen,
             None,
             self.enforce_reliable_broadcast,
@@ -375,7 +433,8 @@ where
             party,
             self.execution_id,
             self.i,
-            self.key_share,
+            self.core,
+            self.aux,
             self.parties_indexes_at_keygen,
             Some(message_to_sign),
             self.enforce_reliable_broadcast,
@@ -397,6 +456,7 @@ where
 #[udigest(tag = "dfns.cggmp21.signing.tag")]
 struct TagUnindexed<'a> {
     sid: &'a [u8],
+    epoch: u64,
 }
 
 /// t-out-of-n signing
@@ -411,7 +471,8 @@ async fn signing_t_out_of_n<M, E, L, D, R>(
     party: M,
     sid: ExecutionId<'_>,
     i: PartyIndex,
-    key_share: &KeyShare<E, L>,
+    core: &DirtyIncompleteKeyShare<E>,
+    aux: &DirtyAuxInfo<L>,
     S: &[PartyIndex],
     message_to_sign: Option<DataToSign<E>>,
     enforce_reliable_broadcast: bool,
@@ -429,18 +490,12 @@ where
     tracer.stage("Map t-out-of-n protocol to t-out-of-t");
 
     // Validate arguments
-    let n: u16 = key_share
-        .aux
+    let n: u16 = aux
         .parties
         .len()
         .try_into()
         .map_err(|_| Bug::PartiesNumberExceedsU16)?;
-    let t = key_share
-        .core
-        .vss_setup
-        .as_ref()
-        .map(|s| s.min_signers)
-        .unwrap_or(n);
+    let t = core.vss_setup.as_ref().map(|s| s.min_signers).unwrap_or(n);
     if S.len() != usize::from(t) {
         return Err(InvalidArgs::MismatchedAmountOfParties.into());
     }
@@ -452,14 +507,14 @@ where
     }
 
     // Assemble x_i and \vec X
-    let (mut x_i, mut X) = if let Some(VssSetup { I, .. }) = &key_share.core.vss_setup {
+    let (mut x_i, mut X) = if let Some(VssSetup { I, .. }) = &core.vss_setup {
         // For t-out-of-n keys generated via VSS DKG scheme
         let I = utils::subset(S, I).ok_or(Bug::Subset)?;
-        let X = utils::subset(S, &key_share.core.public_shares).ok_or(Bug::Subset)?;
+        let X = utils::subset(S, &core.public_shares).ok_or(Bug::Subset)?;
 
         let lambda_i =
             lagrange_coefficient(Scalar::zero(), usize::from(i), &I).ok_or(Bug::LagrangeCoef)?;
-        let x_i = (lambda_i * &key_share.core.x).into_secret();
+        let x_i = (lambda_i * &core.x).into_secret();
 
         let lambda = (0..t).map(|j| lagrange_coefficient(Scalar::zero(), usize::from(j), &I));
         let X = lambda
@@ -471,10 +526,10 @@ where
         (x_i, X)
     } else {
         // For n-out-of-n keys generated using original CGGMP DKG
-        let X = utils::subset(S, &key_share.core.public_shares).ok_or(Bug::Subset)?;
-        (key_share.core.x.clone(), X)
+        let X = utils::subset(S, &core.public_shares).ok_or(Bug::Subset)?;
+        (core.x.clone(), X)
     };
-    debug_assert_eq!(key_share.core.shared_public_key, X.iter().sum::<Point<E>>());
+    debug_assert_eq!(core.shared_public_key, X.iter().sum::<Point<E>>());
 
     // Apply additive shift
     let shift = additive_shift.unwrap_or(Scalar::zero());
@@ -486,14 +541,11 @@ where
             .ok_or(Bug::DerivedChildShareZero)?
             .into_secret();
     }
-    debug_assert_eq!(
-        key_share.core.shared_public_key + Shift,
-        X.iter().sum::<Point<E>>()
-    );
+    debug_assert_eq!(core.shared_public_key + Shift, X.iter().sum::<Point<E>>());
 
     // Assemble rest of the data
-    let (p_i, q_i) = (&key_share.aux.p, &key_share.aux.q);
-    let R = utils::subset(S, &key_share.aux.parties).ok_or(Bug::Subset)?;
+    let (p_i, q_i) = (&aux.p, &aux.q);
+    let R = utils::subset(S, &aux.parties).ok_or(Bug::Subset)?;
 
     // t-out-of-t signing
     signing_n_out_of_n::<_, _, L, _, _>(
@@ -505,7 +557,8 @@ where
         t,
         &x_i,
         &X,
-        key_share.core.shared_public_key + Shift,
+        core.shared_public_key + Shift,
+        core.key_info.epoch,
         p_i,
         q_i,
         &R,
@@ -529,6 +582,7 @@ async fn signing_n_out_of_n<M, E, L, D, R>(
     x_i: &NonZero<SecretScalar<E>>,
     X: &[NonZero<Point<E>>],
     pk: Point<E>,
+    epoch: u64,
     p_i: &Integer,
     q_i: &Integer,
     R: &[PartyAux],
@@ -554,6 +608,8 @@ where
     let dec_i: fast_paillier::DecryptionKey =
         fast_paillier::DecryptionKey::from_primes(p_i.clone(), q_i.clone())
             .map_err(|_| Bug::InvalidOwnPaillierKey)?;
+    // Built once as it holds a copy of our CRT parameters (if they were precomputed)
+    let aux_i: pi_enc::Aux = R_i.into();
 
     tracer.stage("Precompute execution id and security params");
     let sid = sid.as_bytes();
@@ -576,15 +632,20 @@ where
     let gamma_i = SecretScalar::<E>::random(rng);
     let k_i = SecretScalar::<E>::random(rng);
 
-    let v_i = Integer::gen_invertible(N_i, rng);
-    let rho_i = Integer::gen_invertible(N_i, rng);
+    let v_i = SecretInteger::new(Integer::gen_invertible(N_i, rng));
+    let rho_i = SecretInteger::new(Integer::gen_invertible(N_i, rng));
+
+    // Integer representations of secret scalars
+    let gamma_i_int = SecretInteger::new(utils::scalar_to_bignumber(&gamma_i));
+    let k_i_int = SecretInteger::new(utils::scalar_to_bignumber(&k_i));
+    let x_i_int = SecretInteger::new(utils::scalar_to_bignumber(x_i));
 
     tracer.stage("Encrypt G_i and K_i");
     let G_i = dec_i
-        .encrypt_with(&utils::scalar_to_bignumber(&gamma_i), &v_i)
+        .encrypt_with(&gamma_i_int, &v_i)
         .map_err(|_| Bug::PaillierEnc(BugSource::G_i))?;
     let K_i = dec_i
-        .encrypt_with(&utils::scalar_to_bignumber(&k_i), &rho_i)
+        .encrypt_with(&k_i_int, &rho_i)
         .map_err(|_| Bug::PaillierEnc(BugSource::K_i))?;
     runtime.yield_now().await;
 
@@ -593,12 +654,15 @@ where
         .send(Outgoing::broadcast(Msg::Round1a(MsgRound1a {
             K: K_i.clone(),
             G: G_i.clone(),
+            epoch,
         })))
         .await
         .map_err(IoError::send_message)?;
     tracer.msg_sent();
 
-    let parties_shared_state = D::new_with_prefix(D::digest(sid));
+    // Proofs are bound to the epoch, so they can't be replayed in signing with key shares of
+    // another generation
+    let parties_shared_state = D::new_with_prefix(D::digest(sid)).chain_update(epoch.to_be_bytes());
     for j in utils::iter_peers(i, n) {
         tracer.stage("Prove ψ0_j");
         let R_j = &R[usize::from(j)];
@@ -611,7 +675,7 @@ where
                 ciphertext: &K_i,
             },
             pi_enc::PrivateData {
-                plaintext: &utils::scalar_to_bignumber(&k_i),
+                plaintext: &k_i_int,
                 nonce: &rho_i,
             },
             &security_params.pi_enc,
@@ -642,13 +706,24 @@ where
         .map_err(IoError::receive_message)?;
     tracer.msgs_received();
 
+    tracer.stage("Assert other parties hold key shares of the same epoch");
+    let parties_with_different_epoch = ciphertexts
+        .iter_indexed()
+        .filter(|(_j, _msg_id, msg)| msg.epoch != epoch)
+        .map(|(j, msg_id, _)| (j, msg_id))
+        .collect::<Vec<_>>();
+    if !parties_with_different_epoch.is_empty() {
+        return Err(SigningAborted::MismatchedEpoch(parties_with_different_epoch).into());
+    }
+
     // Reliability check (if enabled)
     if enforce_reliable_broadcast {
         tracer.stage("Hash received msgs (reliability check)");
-        let h_i = udigest::Tag::<D>::new_structured(TagUnindexed { sid }).digest_iter(
+        let h_i = udigest::Tag::<D>::new_structured(TagUnindexed { sid, epoch }).digest_iter(
             ciphertexts.iter_including_me(&MsgRound1a {
                 K: K_i.clone(),
                 G: G_i.clone(),
+                epoch,
             }),
         );
 
@@ -690,7 +765,7 @@ where
             let R_j = &R[usize::from(j)];
             if pi_enc::non_interactive::verify(
                 parties_shared_state.clone().chain_update(j.to_be_bytes()),
-                &R_i.into(),
+                &aux_i,
                 pi_enc::Data {
                     key: &fast_paillier::EncryptionKey::from_n(R_j.N.clone()),
                     ciphertext: &ciphertext.K,
@@ -715,33 +790,37 @@ where
     let Gamma_i = Point::generator() * &gamma_i;
     let J = (Integer::ONE << L::ELL_PRIME).complete();
 
-    let mut beta_sum = Scalar::zero();
-    let mut hat_beta_sum = Scalar::zero();
+    let mut beta_sum = Zeroizing::new(Scalar::<E>::zero());
+    let mut hat_beta_sum = Zeroizing::new(Scalar::<E>::zero());
     for (j, _, ciphertext_j) in ciphertexts.iter_indexed() {
         tracer.stage("Sample random r, hat_r, s, hat_s, beta, hat_beta");
         let R_j = &R[usize::from(j)];
         let N_j = &R_j.N;
         let enc_j = fast_paillier::EncryptionKey::from_n(N_j.clone());
 
-        let r_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
-        let hat_r_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
-        let s_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
-        let hat_s_ij = N_i.random_below_ref(&mut utils::external_rand(rng)).into();
+        let mut random_below_n_i =
+            || SecretInteger::new(N_i.random_below_ref(&mut utils::external_rand(rng)).into());
+        let r_ij = random_below_n_i();
+        let hat_r_ij = random_below_n_i();
+        let s_ij = random_below_n_i();
+        let hat_s_ij = random_below_n_i();
 
-        let beta_ij = Integer::from_rng_pm(&J, rng);
-        let hat_beta_ij = Integer::from_rng_pm(&J, rng);
+        let beta_ij = SecretInteger::new(Integer::from_rng_pm(&J, rng));
+        let hat_beta_ij = SecretInteger::new(Integer::from_rng_pm(&J, rng));
+        let neg_beta_ij = SecretInteger::new((-&*beta_ij).complete());
+        let neg_hat_beta_ij = SecretInteger::new((-&*hat_beta_ij).complete());
 
-        beta_sum += beta_ij.to_scalar();
-        hat_beta_sum += hat_beta_ij.to_scalar();
+        *beta_sum += beta_ij.to_scalar();
+        *hat_beta_sum += hat_beta_ij.to_scalar();
 
         tracer.stage("Encrypt D_ji");
         // D_ji = (gamma_i * K_j) + enc_j(-beta_ij, s_ij)
         let D_ji = {
             let gamma_i_times_K_j = enc_j
-                .omul(&utils::scalar_to_bignumber(&gamma_i), &ciphertext_j.K)
+                .omul(&gamma_i_int, &ciphertext_j.K)
                 .map_err(|_| Bug::PaillierOp(BugSource::gamma_i_times_K_j))?;
             let neg_beta_ij_enc = enc_j
-                .encrypt_with(&(-&beta_ij).complete(), &s_ij)
+                .encrypt_with(&neg_beta_ij, &s_ij)
                 .map_err(|_| Bug::PaillierEnc(BugSource::neg_beta_ij_enc))?;
             enc_j
                 .oadd(&gamma_i_times_K_j, &neg_beta_ij_enc)
@@ -750,17 +829,17 @@ where
 
         tracer.stage("Encrypt F_ji");
         let F_ji = dec_i
-            .encrypt_with(&(-&beta_ij).complete(), &r_ij)
+            .encrypt_with(&neg_beta_ij, &r_ij)
             .map_err(|_| Bug::PaillierEnc(BugSource::F_ji))?;
 
         tracer.stage("Encrypt hat_D_ji");
         // Dˆ_ji = (x_i * K_j) + enc_j(-hat_beta_ij, hat_s_ij)
         let hat_D_ji = {
             let x_i_times_K_j = enc_j
-                .omul(&utils::scalar_to_bignumber(x_i), &ciphertext_j.K)
+                .omul(&x_i_int, &ciphertext_j.K)
                 .map_err(|_| Bug::PaillierOp(BugSource::x_i_times_K_j))?;
             let neg_hat_beta_ij_enc = enc_j
-                .encrypt_with(&(-&hat_beta_ij).complete(), &hat_s_ij)
+                .encrypt_with(&neg_hat_beta_ij, &hat_s_ij)
                 .map_err(|_| Bug::PaillierEnc(BugSource::hat_beta_ij_enc))?;
             enc_j
                 .oadd(&x_i_times_K_j, &neg_hat_beta_ij_enc)
@@ -770,7 +849,7 @@ where
 
         tracer.stage("Encrypt hat_F_ji");
         let hat_F_ji = dec_i
-            .encrypt_with(&(-&hat_beta_ij).complete(), &hat_r_ij)
+            .encrypt_with(&neg_hat_beta_ij, &hat_r_ij)
             .map_err(|_| Bug::PaillierEnc(BugSource::hat_F))?;
 
         tracer.stage("Prove psi_ji");
@@ -787,8 +866,8 @@ where
                 x: &Gamma_i,
             },
             pi_aff::PrivateData {
-                x: &utils::scalar_to_bignumber(&gamma_i),
-                y: &(-&beta_ij).complete(),
+                x: &gamma_i_int,
+                y: &neg_beta_ij,
                 nonce: &s_ij,
                 nonce_y: &r_ij,
             },
@@ -811,8 +890,8 @@ where
                 x: &(Point::generator() * x_i),
             },
             pi_aff::PrivateData {
-                x: &utils::scalar_to_bignumber(x_i),
-                y: &(-&hat_beta_ij).complete(),
+                x: &x_i_int,
+                y: &neg_hat_beta_ij,
                 nonce: &hat_s_ij,
                 nonce_y: &hat_r_ij,
             },
@@ -832,7 +911,7 @@ where
                 b: &Point::<E>::generator().to_point(),
             },
             pi_log::PrivateData {
-                x: &utils::scalar_to_bignumber(&gamma_i),
+                x: &gamma_i_int,
                 nonce: &v_i,
             },
             &security_params.pi_log,
@@ -885,7 +964,7 @@ where
         tracer.stage("Validate psi");
         let psi_invalid = pi_aff::non_interactive::verify(
             cst_j.clone(),
-            &R_i.into(),
+            &aux_i,
             pi_aff::Data {
                 key0: &dec_i,
                 key1: &enc_j,
@@ -903,7 +982,7 @@ where
         tracer.stage("Validate hat_psi");
         let hat_psi_invalid = pi_aff::non_interactive::verify(
             cst_j.clone(),
-            &R_i.into(),
+            &aux_i,
             pi_aff::Data {
                 key0: &dec_i,
                 key1: &enc_j,
@@ -921,7 +1000,7 @@ where
         tracer.stage("Validate psi_prime");
         let psi_prime_invalid = pi_log::non_interactive::verify(
             cst_j,
-            &R_i.into(),
+            &aux_i,
             pi_log::Data {
                 key0: &enc_j,
                 c: &ciphertexts.G,
@@ -954,29 +1033,29 @@ where
     let Gamma = Gamma_i + round2_msgs.iter().map(|msg| msg.Gamma).sum::<Point<E>>();
     let Delta_i = Gamma * &k_i;
 
-    let alpha_sum =
-        round2_msgs
-            .iter()
-            .map(|msg| &msg.D)
-            .try_fold(Scalar::<E>::zero(), |sum, D_ij| {
-                let alpha_ij = dec_i
-                    .decrypt(D_ij)
-                    .map_err(|_| Bug::PaillierDec(BugSource::alpha))?;
-                Ok::<_, Bug>(sum + alpha_ij.to_scalar())
-            })?;
-    let hat_alpha_sum =
-        round2_msgs
-            .iter()
-            .map(|msg| &msg.hat_D)
-            .try_fold(Scalar::zero(), |sum, hat_D_ij| {
-                let hat_alpha_ij = dec_i
-                    .decrypt(hat_D_ij)
-                    .map_err(|_| Bug::PaillierDec(BugSource::hat_alpha))?;
-                Ok::<_, Bug>(sum + hat_alpha_ij.to_scalar())
-            })?;
-
-    let delta_i = gamma_i.as_ref() * k_i.as_ref() + alpha_sum + beta_sum;
-    let chi_i = x_i * k_i.as_ref() + hat_alpha_sum + hat_beta_sum;
+    let alpha_sum = Zeroizing::new(round2_msgs.iter().map(|msg| &msg.D).try_fold(
+        Scalar::<E>::zero(),
+        |sum, D_ij| {
+            let alpha_ij = dec_i
+                .decrypt(D_ij)
+                .map(SecretInteger::new)
+                .map_err(|_| Bug::PaillierDec(BugSource::alpha))?;
+            Ok::<_, Bug>(sum + alpha_ij.to_scalar())
+        },
+    )?);
+    let hat_alpha_sum = Zeroizing::new(round2_msgs.iter().map(|msg| &msg.hat_D).try_fold(
+        Scalar::zero(),
+        |sum, hat_D_ij| {
+            let hat_alpha_ij = dec_i
+                .decrypt(hat_D_ij)
+                .map(SecretInteger::new)
+                .map_err(|_| Bug::PaillierDec(BugSource::hat_alpha))?;
+            Ok::<_, Bug>(sum + hat_alpha_ij.to_scalar())
+        },
+    )?);
+
+    let delta_i = gamma_i.as_ref() * k_i.as_ref() + *alpha_sum + *beta_sum;
+    let chi_i = SecretScalar::new(&mut (x_i * k_i.as_ref() + *hat_alpha_sum + *hat_beta_sum));
     runtime.yield_now().await;
 
     for j in utils::iter_peers(i, n) {
@@ -992,7 +1071,7 @@ where
                 b: &Gamma,
             },
             pi_log::PrivateData {
-                x: &utils::scalar_to_bignumber(&k_i),
+                x: &k_i_int,
                 nonce: &rho_i,
             },
             &security_params.pi_log,
@@ -1043,7 +1122,7 @@ where
 
         if pi_log::non_interactive::verify(
             parties_shared_state.clone().chain_update(j.to_be_bytes()),
-            &R_i.into(),
+            &aux_i,
             data,
             &msg_j.psi_prime_prime.0,
             &security_params.pi_log,
@@ -1077,7 +1156,7 @@ where
     let presig = Presignature {
         R,
         k: k_i,
-        chi: SecretScalar::new(&mut chi_i.clone()),
+        chi: chi_i,
     };
 
     // If message is not specified, protocol terminates here and outputs partial
@@ -1181,6 +1260,16 @@ impl<E: Curve> Presignature<E> {
     }
 }
 
+impl<E: Curve> zeroize::Zeroize for Presignature<E> {
+    /// Replaces secret components $k$ and $\chi$ with zeroes
+    ///
+    /// Previous values are wiped from memory unless they're shared with a clone of the presignature.
+    fn zeroize(&mut self) {
+        self.k = SecretScalar::zero();
+        self.chi = SecretScalar::zero();
+    }
+}
+
 #[cfg(feature = "hd-wallets")]
 fn derive_additive_shift<E: Curve, Index>(
     mut epub: slip_10::ExtendedPublicKey<E>,
@@ -1382,6 +1471,8 @@ enum SigningAborted {
     SignatureInvalid,
     #[error("other parties received different broadcast messages at round1a")]
     Round1aNotReliable(Vec<(PartyIndex, MsgId)>),
+    #[error("other parties hold key shares of different epoch")]
+    MismatchedEpoch(Vec<(PartyIndex, MsgId)>),
 }
 
 #[derive(Debug, Error)]
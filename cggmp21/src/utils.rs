@@ -16,6 +16,40 @@ pub fn scalar_to_bignumber<E: Curve>(scalar: impl AsRef<Scalar<E>>) -> Integer {
     Integer::from_digits(&scalar.as_ref().to_be_bytes(), rug::integer::Order::Msf)
 }
 
+/// Overwrites the whole buffer allocated by the integer with zeroes
+///
+/// Unlike assigning zero, it wipes all the limbs the integer has allocated, not only significant
+/// ones. Capacity of the integer is retained, so the buffer is not reallocated.
+pub fn zeroize_integer(x: &mut Integer) {
+    // Capacity is always a multiple of limb size, which is either 32 or 64 bits
+    let zeroes = vec![0u32; x.capacity() / 32];
+    x.assign_digits(&zeroes, rug::integer::Order::Lsf);
+}
+
+/// Big integer that's [wiped](zeroize_integer) on drop
+///
+/// Used to hold intermediate secrets of the protocols.
+pub struct SecretInteger(Integer);
+
+impl SecretInteger {
+    pub fn new(x: Integer) -> Self {
+        Self(x)
+    }
+}
+
+impl std::ops::Deref for SecretInteger {
+    type Target = Integer;
+    fn deref(&self) -> &Integer {
+        &self.0
+    }
+}
+
+impl Drop for SecretInteger {
+    fn drop(&mut self) {
+        zeroize_integer(&mut self.0)
+    }
+}
+
 pub struct SecurityParams {
     pub pi_aff: pi_aff::SecurityParams,
     pub pi_log: pi_log::SecurityParams,
@@ -64,6 +98,7 @@ where
 /// In the future we might want to replace the data_message and proof_message
 /// with a generic vec of messages.
 #[derive(Debug)]
+#[allow(dead_code)] // fields are only read via `Debug` impl when error is reported
 pub struct AbortBlame {
     /// Party which can be blamed for breaking the protocol
     pub faulty_party: PartyIndex,
@@ -123,6 +158,33 @@ where
         .collect()
 }
 
+/// Same as [`collect_simple_blame`], but messages are verified all at once
+///
+/// `item` maps every message into an item to be verified, messages that don't need to be
+/// verified are skipped by returning `None`. `verify` returns indexes of faulty items.
+pub fn collect_simple_blame_batch<'m, D, T, I, V>(
+    messages: &'m RoundMsgs<D>,
+    mut item: I,
+    verify: V,
+) -> Vec<AbortBlame>
+where
+    I: FnMut(PartyIndex, &'m D) -> Option<T>,
+    V: FnOnce(&[T]) -> Result<(), Vec<usize>>,
+{
+    let (senders, items): (Vec<_>, Vec<_>) = messages
+        .iter_indexed()
+        .filter_map(|(j, msg_id, data)| Some(((j, msg_id), item(j, data)?)))
+        .unzip();
+    match verify(&items) {
+        Ok(()) => vec![],
+        Err(faulty) => faulty
+            .into_iter()
+            .filter_map(|index| senders.get(index))
+            .map(|(j, msg_id)| AbortBlame::new(*j, *msg_id, *msg_id))
+            .collect(),
+    }
+}
+
 /// Same as [`collect_blame`], but filter can fail, in which case whole blame
 /// collection will fail. So to not lose security the error type should be some
 /// kind of unrecoverable internal assertion failure.
@@ -146,6 +208,72 @@ where
     Ok(r)
 }
 
+/// Same as [`collect_blame`], but filter is evaluated for all message pairs
+/// as specified by `parallelism`
+pub fn collect_blame_par<D, P, F>(
+    parallelism: &Parallelism,
+    data_messages: &RoundMsgs<D>,
+    proof_messages: &RoundMsgs<P>,
+    filter: F,
+) -> Vec<AbortBlame>
+where
+    D: Sync,
+    P: Sync,
+    F: Fn(PartyIndex, &D, &P) -> bool + Send + Sync,
+{
+    let pairs = data_messages
+        .iter_indexed()
+        .zip(proof_messages.iter_indexed())
+        .collect::<Vec<_>>();
+    let faulty = parallelism.map(&pairs, |((j, _, data), (_, _, proof))| {
+        filter(*j, data, proof)
+    });
+    pairs
+        .into_iter()
+        .zip(faulty)
+        .filter(|(_, faulty)| *faulty)
+        .map(|(((j, data_msg_id, _), (_, proof_msg_id, _)), _)| {
+            AbortBlame::new(j, data_msg_id, proof_msg_id)
+        })
+        .collect()
+}
+
+/// Specifies how independent heavy computations (like ZK proofs) are carried out
+#[derive(Clone, Default)]
+pub enum Parallelism {
+    /// Computations are carried out one by one in the current thread
+    #[default]
+    Sequential,
+    /// Computations are carried out concurrently in the rayon thread pool
+    ///
+    /// Global thread pool is used if the pool is not specified
+    #[cfg(feature = "parallel")]
+    ThreadPool(Option<std::sync::Arc<rayon::ThreadPool>>),
+}
+
+impl Parallelism {
+    /// Applies `f` to each item, returns results in the same order
+    pub fn map<T, U, F>(&self, items: &[T], f: F) -> Vec<U>
+    where
+        T: Sync,
+        U: Send,
+        F: Fn(&T) -> U + Send + Sync,
+    {
+        match self {
+            Self::Sequential => items.iter().map(f).collect(),
+            #[cfg(feature = "parallel")]
+            Self::ThreadPool(pool) => {
+                use rayon::prelude::*;
+                let compute = || items.par_iter().map(f).collect();
+                match pool {
+                    Some(pool) => pool.install(compute),
+                    None => compute(),
+                }
+            }
+        }
+    }
+}
+
 /// Iterate peers of i-th party
 pub fn iter_peers(i: u16, n: u16) -> impl Iterator<Item = u16> {
     (0..n).filter(move |x| *x != i)
@@ -269,4 +397,13 @@ mod test {
             assert!(root.square_ref().complete() > x);
         }
     }
+
+    #[test]
+    fn zeroize_integer() {
+        let mut x = (super::Integer::ONE << 1024_u32).complete() - 1u8;
+        let capacity = x.capacity();
+        super::zeroize_integer(&mut x);
+        assert_eq!(x, 0);
+        assert_eq!(x.capacity(), capacity);
+    }
 }
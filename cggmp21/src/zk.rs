@@ -1 +1,2 @@
+pub mod paillier_blum_modulus;
 pub mod ring_pedersen_parameters;
@@ -0,0 +1,153 @@
+//! Пmod or Rmod in the paper. Proof that N is a Paillier-Blum modulus. Non-interactive
+//! version only.
+//!
+//! Wraps [`paillier_zk::paillier_blum_modulus`] which has amount of iterations fixed at compile time
+//! to [`M`]. To reach `m` iterations required by the security level, the proof consists of
+//! $\lceil m / M \rceil$ repetitions. Prover commits to all repetitions before challenges are derived,
+//! so soundness error of the proof is $2^{-M \cdot \lceil m / M \rceil} \le 2^{-m}$.
+use digest::{typenum::U32, Digest};
+use paillier_zk::paillier_blum_modulus as π_mod;
+use rand_core::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::security_level::M;
+
+pub use π_mod::{Data, PrivateData};
+
+/// The ZK proof. Computed by [`prove`].
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Proof {
+    /// Commitment and proof of each repetition
+    pub repetitions: Vec<(π_mod::Commitment, π_mod::Proof<M>)>,
+}
+
+/// Returns amount of repetitions needed to reach `m` iterations
+pub fn repetitions(m: usize) -> usize {
+    m.div_ceil(M)
+}
+
+/// Derives challenge of `k`-th repetition
+///
+/// Challenge depends on commitments of all repetitions, so the prover can't choose commitment of
+/// each repetition independently
+fn derive_challenge<D>(
+    shared_state: &D,
+    data: &Data,
+    commitments: &[&π_mod::Commitment],
+    k: usize,
+) -> π_mod::Challenge<M>
+where
+    D: Digest<OutputSize = U32> + Clone,
+{
+    let order = paillier_zk::rug::integer::Order::Msf;
+    let mut shared_state = shared_state.clone();
+    for commitment in commitments {
+        shared_state.update(commitment.w.to_digits::<u8>(order));
+    }
+    shared_state.update((k as u64).to_be_bytes());
+    π_mod::non_interactive::challenge(shared_state, data, commitments[k])
+}
+
+/// Compute the proof for the given data, producing random commitments and
+/// deriving deterministic challenges based on `shared_state` and `data`
+///
+/// Proof consists of [`repetitions(m)`](repetitions) repetitions
+pub fn prove<R, D>(
+    shared_state: D,
+    m: usize,
+    data: &Data,
+    pdata: &PrivateData,
+    rng: &mut R,
+) -> Result<Proof, paillier_zk::Error>
+where
+    D: Digest<OutputSize = U32> + Clone,
+    R: RngCore,
+{
+    let commitments = (0..repetitions(m))
+        .map(|_| π_mod::interactive::commit(data, rng))
+        .collect::<Vec<_>>();
+    let commitments_refs = commitments.iter().collect::<Vec<_>>();
+    let proofs = (0..commitments.len())
+        .map(|k| {
+            let challenge = derive_challenge(&shared_state, data, &commitments_refs, k);
+            π_mod::interactive::prove(data, pdata, &commitments[k], &challenge)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Proof {
+        repetitions: commitments.into_iter().zip(proofs).collect(),
+    })
+}
+
+/// Verify the proof. Derives deterministic challenges based on `shared_state`
+/// and `data`.
+///
+/// Proof must consist of exactly [`repetitions(m)`](repetitions) repetitions
+pub fn verify<D>(shared_state: D, m: usize, data: &Data, proof: &Proof) -> Result<(), InvalidProof>
+where
+    D: Digest<OutputSize = U32> + Clone,
+{
+    if proof.repetitions.len() != repetitions(m) {
+        return Err(InvalidProof);
+    }
+    let commitments = proof
+        .repetitions
+        .iter()
+        .map(|(commitment, _)| commitment)
+        .collect::<Vec<_>>();
+    for (k, (commitment, proof)) in proof.repetitions.iter().enumerate() {
+        let challenge = derive_challenge(&shared_state, data, &commitments, k);
+        π_mod::interactive::verify(data, commitment, &challenge, proof)
+            .map_err(|_| InvalidProof)?;
+    }
+    Ok(())
+}
+
+/// Witness that proof is invalid
+#[derive(Debug)]
+pub struct InvalidProof;
+
+#[cfg(test)]
+mod test {
+    use paillier_zk::rug::{Complete, Integer};
+
+    use super::{Data, PrivateData};
+
+    fn prove_and_verify(m: usize) {
+        let mut rng = rand_dev::DevRng::new();
+        let p = crate::fast_paillier::utils::generate_safe_prime(&mut rng, 256);
+        let q = crate::fast_paillier::utils::generate_safe_prime(&mut rng, 256);
+        let data = Data {
+            n: (&p * &q).complete(),
+        };
+        let pdata = PrivateData { p, q };
+        let shared_state = sha2::Sha256::default();
+
+        let proof = super::prove(shared_state.clone(), m, &data, &pdata, &mut rng).unwrap();
+        assert_eq!(proof.repetitions.len(), super::repetitions(m));
+        super::verify(shared_state.clone(), m, &data, &proof).unwrap();
+
+        // Proof doesn't verify with another amount of iterations
+        super::verify(shared_state.clone(), m + super::M, &data, &proof).unwrap_err();
+        // Repetitions can't be reordered
+        if proof.repetitions.len() > 1 {
+            let mut reordered = proof.clone();
+            reordered.repetitions.swap(0, 1);
+            super::verify(shared_state.clone(), m, &data, &reordered).unwrap_err();
+        }
+        // Proof is bound to the modulus
+        let other_data = Data {
+            n: data.n.clone() + Integer::from(2),
+        };
+        super::verify(shared_state, m, &other_data, &proof).unwrap_err();
+    }
+
+    #[test]
+    fn single_repetition() {
+        prove_and_verify(128)
+    }
+
+    #[test]
+    fn many_repetitions() {
+        prove_and_verify(192)
+    }
+}
@@ -7,11 +7,12 @@ use paillier_zk::{
 };
 use rand_core::{RngCore, SeedableRng};
 use serde::{Deserialize, Serialize};
-use serde_with::serde_as;
 use thiserror::Error;
 
-struct Challenge<const M: usize> {
-    es: [bool; M],
+use crate::utils::Parallelism;
+
+struct Challenge {
+    es: Vec<bool>,
 }
 
 /// Data to construct proof about
@@ -24,25 +25,18 @@ pub struct Data<'a> {
 
 /// The ZK proof. Computed by [`prove`].
 ///
-/// Parameter `M` is security level. The probability of an adversary generating
-/// a correct proof for incorrect data is $2^{-M}$. You can use M defined here
-/// as [`SECURITY`]
-#[serde_as]
+/// Proof consists of `m` repetitions, where `m` is security parameter. The probability
+/// of an adversary generating a correct proof for incorrect data is $2^{-m}$. Normally,
+/// `m` is set to [`SecurityLevel::M`](crate::security_level::SecurityLevel::M).
 #[derive(Clone, Serialize, Deserialize, udigest::Digestable)]
-pub struct Proof<const M: usize> {
-    #[serde_as(as = "[_; M]")]
+pub struct Proof {
     #[udigest(with = crate::utils::encoding::integers_list)]
-    pub commitment: [Integer; M],
-    #[serde_as(as = "[_; M]")]
+    pub commitment: Vec<Integer>,
     #[udigest(with = crate::utils::encoding::integers_list)]
-    pub zs: [Integer; M],
+    pub zs: Vec<Integer>,
 }
 
-fn derive_challenge<const M: usize, D>(
-    shared_state: D,
-    data: Data,
-    commitment: &[Integer; M],
-) -> Challenge<M>
+fn derive_challenge<D>(shared_state: D, data: Data, commitment: &[Integer]) -> Challenge
 where
     D: Digest<OutputSize = U32>,
 {
@@ -58,7 +52,7 @@ where
     let mut rng = rand_chacha::ChaCha20Rng::from_seed(seed.into());
 
     // generate bools by hand since we don't have rand
-    let mut es = [false; M];
+    let mut es = vec![false; commitment.len()];
     let mut current = rng.next_u32();
     let mut bits_generated = 0;
     for e_ref in es.iter_mut() {
@@ -68,6 +62,7 @@ where
         }
         *e_ref = (current & 1) == 1;
         current >>= 1;
+        bits_generated += 1;
     }
     Challenge { es }
 }
@@ -77,32 +72,36 @@ where
 ///
 /// - `phi` - $φ(N) = (p-1)(q-1)$
 /// - `lambda` - λ such that $s = t^λ$
-pub fn prove<const M: usize, R, D>(
+///
+/// `m` repetitions of the proof are computed as specified by `parallelism`
+pub fn prove<R, D>(
     shared_state: D,
     rng: &mut R,
+    m: usize,
     data: Data,
     phi: &Integer,
     lambda: &Integer,
-) -> Result<Proof<M>, ZkError>
+    parallelism: &Parallelism,
+) -> Result<Proof, ZkError>
 where
     D: Digest<OutputSize = U32>,
     R: RngCore,
 {
-    let private_commitment =
-        [(); M].map(|()| phi.random_below_ref(&mut utils::external_rand(rng)).into());
-    let commitment = private_commitment
-        .clone()
-        .map(|a| data.t.pow_mod_ref(&a, data.N).map(|r| r.into()));
-    // TODO: since array::try_map is not stable yet, we have to be hacky here
-    let commitment = if commitment.iter().any(Option::is_none) {
-        return Err(Reason::PowMod.into());
-    } else {
-        // We made sure that every item in the array is `Some(_)`
-        #[allow(clippy::unwrap_used)]
-        commitment.map(Option::unwrap)
-    };
+    let private_commitment: Vec<Integer> = (0..m)
+        .map(|_| phi.random_below_ref(&mut utils::external_rand(rng)).into())
+        .collect();
+    let commitment = parallelism
+        .map(&private_commitment, |a| {
+            data.t.pow_mod_ref(a, data.N).map(Integer::from)
+        })
+        .into_iter()
+        .collect::<Option<Vec<_>>>()
+        .ok_or(Reason::PowMod)?;
+    if commitment.len() != m {
+        return Err(Reason::CommitmentSize.into());
+    }
 
-    let challenge: Challenge<M> = derive_challenge(shared_state, data, &commitment);
+    let challenge = derive_challenge(shared_state, data, &commitment);
 
     let mut zs = private_commitment;
     for (z_ref, e) in zs.iter_mut().zip(&challenge.es) {
@@ -116,27 +115,225 @@ where
 
 /// Verify the proof. Derives determenistic challenge based on `shared_state`
 /// and `data`.
-pub fn verify<const M: usize, D>(
+///
+/// Proof must consist of exactly `m` repetitions, they are verified as specified by
+/// `parallelism`
+pub fn verify<D>(
     shared_state: D,
+    m: usize,
     data: Data,
-    proof: &Proof<M>,
+    proof: &Proof,
+    parallelism: &Parallelism,
 ) -> Result<(), InvalidProof>
 where
     D: Digest<OutputSize = U32>,
 {
-    let challenge: Challenge<M> = derive_challenge(shared_state, data, &proof.commitment);
-    for ((z, a), e) in proof.zs.iter().zip(&proof.commitment).zip(&challenge.es) {
-        let lhs: Integer = data.t.pow_mod_ref(z, data.N).ok_or(InvalidProof)?.into();
+    if proof.commitment.len() != m || proof.zs.len() != m {
+        return Err(InvalidProof);
+    }
+    let challenge = derive_challenge(shared_state, data, &proof.commitment);
+    let repetitions = proof
+        .zs
+        .iter()
+        .zip(&proof.commitment)
+        .zip(challenge.es)
+        .collect::<Vec<_>>();
+    let valid = parallelism.map(&repetitions, |((z, a), e)| {
+        let Some(lhs) = data.t.pow_mod_ref(z, data.N).map(Integer::from) else {
+            return false;
+        };
         if *e {
-            let rhs = (data.s * a).complete().modulo(data.N);
-            if lhs != rhs {
-                return Err(InvalidProof);
+            let rhs = (data.s * *a).complete().modulo(data.N);
+            lhs == rhs
+        } else {
+            lhs == **a
+        }
+    });
+    if valid.into_iter().all(|v| v) {
+        Ok(())
+    } else {
+        Err(InvalidProof)
+    }
+}
+
+/// Proof to be verified by [`verify_batch`]
+#[derive(Clone, Copy)]
+pub struct BatchItem<'a, D> {
+    /// Shared state the proof was produced with, same as in [`verify`]
+    pub shared_state: D,
+    pub data: Data<'a>,
+    pub proof: &'a Proof,
+}
+
+/// Verify many proofs at once, possibly produced by different provers
+///
+/// All repetitions of the proof share the same base `t` and modulus `N`, so instead of `m`
+/// independent exponentiations, powers $t^{16^k} \bmod N$ are computed once per proof, and then
+/// each $t^{z_i}$ is evaluated by Yao's method in roughly a quarter of multiplications needed for
+/// regular exponentiation. Repetitions of all proofs are checked as specified by `parallelism`.
+///
+/// Each repetition is still checked exactly. We intentionally don't aggregate repetitions via random
+/// linear combination: $\Z_N^*$ of prover-chosen $N$ may have elements of small order (e.g. $-1$),
+/// and such check would accept invalid proof with probability $1/2$.
+///
+/// Returns `Ok(())` if all proofs are valid. Otherwise, falls back to verifying each proof separately
+/// via [`verify`] and returns indexes of invalid proofs, so the provers can be blamed.
+pub fn verify_batch<D>(
+    m: usize,
+    items: &[BatchItem<D>],
+    parallelism: &Parallelism,
+) -> Result<(), Vec<usize>>
+where
+    D: Digest<OutputSize = U32> + Clone,
+{
+    if verify_batch_fast(m, items, parallelism) {
+        return Ok(());
+    }
+    let invalid = items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| {
+            verify(
+                item.shared_state.clone(),
+                m,
+                item.data,
+                item.proof,
+                parallelism,
+            )
+            .is_err()
+        })
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    if invalid.is_empty() {
+        // Fast path never rejects valid proofs, but we don't want to rely on that
+        Ok(())
+    } else {
+        Err(invalid)
+    }
+}
+
+/// Returns `true` if all proofs are valid
+fn verify_batch_fast<D>(m: usize, items: &[BatchItem<D>], parallelism: &Parallelism) -> bool
+where
+    D: Digest<OutputSize = U32> + Clone,
+{
+    let mut repetitions = vec![];
+    for (index, item) in items.iter().enumerate() {
+        if item.proof.commitment.len() != m || item.proof.zs.len() != m || *item.data.N <= 1 {
+            return false;
+        }
+        let challenge =
+            derive_challenge(item.shared_state.clone(), item.data, &item.proof.commitment);
+        repetitions.extend(
+            item.proof
+                .zs
+                .iter()
+                .zip(&item.proof.commitment)
+                .zip(challenge.es)
+                .map(|((z, a), e)| (index, z, a, e)),
+        );
+    }
+
+    let data = items.iter().map(|item| item.data).collect::<Vec<_>>();
+    let tables = parallelism.map(&data, |data| FixedBase::new(data.t, data.N));
+    let valid = parallelism.map(&repetitions, |(index, z, a, e)| {
+        let data = &data[*index];
+        let lhs = match tables[*index].pow(z) {
+            Some(lhs) => lhs,
+            // Exponent is out of range of the table, which doesn't happen for honest prover
+            None => match data.t.pow_mod_ref(z, data.N) {
+                Some(lhs) => lhs.into(),
+                None => return false,
+            },
+        };
+        if *e {
+            let rhs = (data.s * *a).complete().modulo(data.N);
+            lhs == rhs
+        } else {
+            lhs == **a
+        }
+    });
+    valid.into_iter().all(|v| v)
+}
+
+/// Powers $t^{16^k} \bmod N$ of fixed base $t$ for exponentiation with many different exponents
+struct FixedBase<'a> {
+    N: &'a Integer,
+    powers: Vec<Integer>,
+}
+
+impl<'a> FixedBase<'a> {
+    /// Precomputes powers for exponents up to bit length of `N`
+    ///
+    /// `N` must be greater than 1
+    fn new(t: &Integer, N: &'a Integer) -> Self {
+        let size = usize::try_from(N.significant_bits().div_ceil(4)).unwrap_or(usize::MAX);
+        let mut powers = Vec::with_capacity(size);
+        let mut power = t.clone().modulo(N);
+        for _ in 0..size {
+            let mut next = power.clone();
+            for _ in 0..4 {
+                next.square_mut();
+                next %= N;
+            }
+            powers.push(std::mem::replace(&mut power, next));
+        }
+        Self { N, powers }
+    }
+
+    /// Computes $t^{exp} \bmod N$
+    ///
+    /// Returns `None` if exponent is negative or too large for the precomputed table
+    fn pow(&self, exp: &Integer) -> Option<Integer> {
+        if exp.cmp0().is_lt() {
+            return None;
+        }
+        let mut digits = exp
+            .to_digits::<u8>(rug::integer::Order::Lsf)
+            .into_iter()
+            .flat_map(|byte| [byte & 0xf, byte >> 4])
+            .collect::<Vec<_>>();
+        while digits.last() == Some(&0) {
+            digits.pop();
+        }
+        if digits.len() > self.powers.len() {
+            return None;
+        }
+
+        // buckets[d - 1] = product of t^{16^k} for all k such that k-th digit is d
+        let mut buckets: [Option<Integer>; 15] = Default::default();
+        for (digit, power) in digits.into_iter().zip(&self.powers) {
+            if digit == 0 {
+                continue;
+            }
+            match &mut buckets[usize::from(digit - 1)] {
+                Some(bucket) => {
+                    *bucket *= power;
+                    *bucket %= self.N;
+                }
+                bucket @ None => *bucket = Some(power.clone()),
+            }
+        }
+
+        // result = prod_{d=1}^{15} buckets[d - 1]^d
+        let mut acc: Option<Integer> = None;
+        let mut result = Integer::from(1);
+        for bucket in buckets.iter().rev() {
+            match (&mut acc, bucket) {
+                (Some(acc), Some(bucket)) => {
+                    *acc *= bucket;
+                    *acc %= self.N;
+                }
+                (acc @ None, Some(bucket)) => *acc = Some(bucket.clone()),
+                (_, None) => (),
+            }
+            if let Some(acc) = &acc {
+                result *= acc;
+                result %= self.N;
             }
-        } else if lhs != *a {
-            return Err(InvalidProof);
         }
+        Some(result)
     }
-    Ok(())
 }
 
 #[derive(Debug, Error)]
@@ -147,6 +344,8 @@ pub struct ZkError(#[from] Reason);
 enum Reason {
     #[error("pow mod undefined")]
     PowMod,
+    #[error("commitment has unexpected size")]
+    CommitmentSize,
 }
 
 /// Witness that proof is invalid
@@ -156,15 +355,145 @@ pub struct InvalidProof;
 // running with M=64 completed in 1.22 on my machine in debug build
 #[cfg(test)]
 mod test {
+    use digest::Digest;
     use paillier_zk::{
         rug::{Complete, Integer},
         IntegerExt,
     };
 
-    use crate::utils;
+    use crate::utils::{self, Parallelism};
 
     #[test]
     fn passing() {
+        passing_with(&Parallelism::Sequential)
+    }
+
+    #[test]
+    fn failing() {
+        failing_with(&Parallelism::Sequential)
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn passing_parallel() {
+        passing_with(&Parallelism::ThreadPool(None))
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn failing_parallel() {
+        failing_with(&Parallelism::ThreadPool(None))
+    }
+
+    #[test]
+    fn batch() {
+        batch_with(&Parallelism::Sequential)
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn batch_parallel() {
+        batch_with(&Parallelism::ThreadPool(None))
+    }
+
+    #[test]
+    fn fixed_base_pow() {
+        let mut rng = rand_core::OsRng;
+        let p = utils::generate_blum_prime(&mut rng, 256);
+        let q = utils::generate_blum_prime(&mut rng, 256);
+        let n = (&p * &q).complete();
+        let t = Integer::gen_invertible(&n, &mut rng);
+
+        let table = super::FixedBase::new(&t, &n);
+        for exp in [
+            Integer::ZERO,
+            Integer::from(1),
+            Integer::from(16),
+            (&n - 1u8).complete(),
+        ]
+        .into_iter()
+        .chain((0..10).map(|_| {
+            n.random_below_ref(&mut utils::external_rand(&mut rng))
+                .into()
+        })) {
+            let expected: Integer = t.pow_mod_ref(&exp, &n).unwrap().into();
+            assert_eq!(table.pow(&exp), Some(expected));
+        }
+        // Exponents out of range of the table are not supported
+        assert_eq!(table.pow(&Integer::from(-1)), None);
+        assert_eq!(table.pow(&(Integer::from(1) << 600)), None);
+    }
+
+    /// Generates ring-pedersen parameters `(N, φ(N), λ, s, t)`
+    fn generate_params(
+        rng: &mut impl rand_core::RngCore,
+    ) -> (Integer, Integer, Integer, Integer, Integer) {
+        let p = utils::generate_blum_prime(rng, 256);
+        let q = utils::generate_blum_prime(rng, 256);
+        let n = (&p * &q).complete();
+        let phi = (&p - 1u8).complete() * (&q - 1u8).complete();
+
+        let r = Integer::gen_invertible(&n, rng);
+        let lambda = phi.random_below_ref(&mut utils::external_rand(rng)).into();
+        let t = r.square().modulo(&n);
+        let s = t.pow_mod_ref(&lambda, &n).unwrap().into();
+        (n, phi, lambda, s, t)
+    }
+
+    fn batch_with(parallelism: &Parallelism) {
+        let mut rng = rand_core::OsRng;
+        let shared_state = |j: u8| sha2::Sha256::default().chain_update([j]);
+
+        let mut params = (0..3)
+            .map(|_| generate_params(&mut rng))
+            .collect::<Vec<_>>();
+        let proofs = (0u8..)
+            .zip(&params)
+            .map(|(j, (n, phi, lambda, s, t))| {
+                let data = super::Data { N: n, s, t };
+                super::prove(
+                    shared_state(j),
+                    &mut rng,
+                    16,
+                    data,
+                    phi,
+                    lambda,
+                    parallelism,
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+        fn batch_items<'a>(
+            params: &'a [(Integer, Integer, Integer, Integer, Integer)],
+            proofs: &'a [super::Proof],
+        ) -> Vec<super::BatchItem<'a, sha2::Sha256>> {
+            (0u8..)
+                .zip(params.iter().zip(proofs))
+                .map(|(j, ((n, _, _, s, t), proof))| super::BatchItem {
+                    shared_state: sha2::Sha256::default().chain_update([j]),
+                    data: super::Data { N: n, s, t },
+                    proof,
+                })
+                .collect()
+        }
+
+        super::verify_batch(16, &batch_items(&params, &proofs), parallelism)
+            .expect("proofs should pass");
+        // Proofs with another amount of repetitions are rejected
+        assert_eq!(
+            super::verify_batch(17, &batch_items(&params, &proofs), parallelism),
+            Err(vec![0, 1, 2])
+        );
+
+        // Only the prover with invalid parameters is blamed
+        params[1].3 += 1;
+        assert_eq!(
+            super::verify_batch(16, &batch_items(&params, &proofs), parallelism),
+            Err(vec![1])
+        );
+    }
+
+    fn passing_with(parallelism: &Parallelism) {
         let mut rng = rand_core::OsRng;
         let shared_state = sha2::Sha256::default();
 
@@ -186,13 +515,23 @@ mod test {
             t: &t,
         };
 
-        let proof: super::Proof<16> =
-            super::prove(shared_state.clone(), &mut rng, data, &phi, &lambda).unwrap();
-        super::verify(shared_state, data, &proof).expect("proof should pass");
+        let proof = super::prove(
+            shared_state.clone(),
+            &mut rng,
+            16,
+            data,
+            &phi,
+            &lambda,
+            parallelism,
+        )
+        .unwrap();
+        super::verify(shared_state.clone(), 16, data, &proof, parallelism)
+            .expect("proof should pass");
+        // Proof with another amount of repetitions is rejected
+        assert!(super::verify(shared_state, 17, data, &proof, parallelism).is_err());
     }
 
-    #[test]
-    fn failing() {
+    fn failing_with(parallelism: &Parallelism) {
         let mut rng = rand_core::OsRng;
         let shared_state = sha2::Sha256::default();
 
@@ -215,10 +554,43 @@ mod test {
             t: &t,
         };
 
-        let proof: super::Proof<16> =
-            super::prove(shared_state.clone(), &mut rng, data, &phi, &lambda).unwrap();
-        if super::verify(shared_state, data, &proof).is_ok() {
+        let proof = super::prove(
+            shared_state.clone(),
+            &mut rng,
+            16,
+            data,
+            &phi,
+            &lambda,
+            parallelism,
+        )
+        .unwrap();
+        if super::verify(shared_state, 16, data, &proof, parallelism).is_ok() {
             panic!("proof should fail");
         }
     }
+
+    #[test]
+    fn challenge_bits_are_not_truncated() {
+        let n = Integer::from(u64::MAX);
+        let (s, t) = (Integer::from(4), Integer::from(2));
+        let data = super::Data {
+            N: &n,
+            s: &s,
+            t: &t,
+        };
+        let commitment: [Integer; 256] = std::array::from_fn(|_| Integer::new());
+        for j in 0u8..8 {
+            let shared_state = <sha2::Sha256 as digest::Digest>::new_with_prefix([j]);
+            let challenge = super::derive_challenge(shared_state, data, &commitment);
+            assert_eq!(challenge.es.len(), 256);
+            // Each chunk of 32 bits is taken from its own random word, so none of them can
+            // be all false unless with negligible probability
+            for chunk in challenge.es.chunks(32) {
+                assert!(
+                    chunk.iter().any(|e| *e),
+                    "chunk of challenge bits is all false"
+                );
+            }
+        }
+    }
 }
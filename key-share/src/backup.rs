@@ -0,0 +1,284 @@
+//! Verifiable backup of key shares
+//!
+//! Secret share $x_i$ of a single signer can be backed up to $m$ offline custodians such that any
+//! $k$ of them are able to restore it, while fewer than $k$ custodians learn nothing about $x_i$.
+//!
+//! Backup is based on Feldman VSS: signer samples a polynomial $f(x)$ of degree $k-1$ with
+//! $f(0) = x_i$, and gives $y_j = f(j+1)$ to $\jth$ custodian along with commitment to the
+//! polynomial $F(x) = G \cdot f(x)$. Constant term of the commitment is $X_i$, the signer's entry
+//! in [`public_shares`](crate::DirtyKeyInfo::public_shares), so each custodian can
+//! [verify](DirtyBackupPiece::verify) that its piece belongs to the key without reconstructing the
+//! key share. On [restoration](restore), the key share is validated against the existing key info.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use key_share::backup;
+//! use key_share::CoreKeyShare;
+//!
+//! fn backup_and_restore<E: generic_ec::Curve>(
+//!     key_share: &CoreKeyShare<E>,
+//! ) -> Result<CoreKeyShare<E>, backup::BackupError> {
+//!     let mut rng = rand_core::OsRng;
+//!     // Any 2 out of 3 custodians can restore the key share
+//!     let pieces = backup::backup(key_share, 2, 3, &mut rng)?;
+//!
+//!     // Each custodian verifies the received piece against public key info
+//!     for piece in &pieces {
+//!         piece.verify(&key_share.key_info)?;
+//!     }
+//!
+//!     // Any 2 pieces are enough to restore the key share
+//!     backup::restore(key_share.as_ref(), &pieces[1..])
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+use generic_ec_zkp::polynomial::{lagrange_coefficient, Polynomial};
+
+use crate::{CoreKeyShare, DirtyKeyInfo, KeyInfo, Valid, Validate};
+
+/// Piece of the backup held by a single custodian
+///
+/// Type alias to [`DirtyBackupPiece`] wrapped into [`Valid<T>`](Valid), meaning that the piece
+/// has been validated to be consistent with the commitment it carries. Note that it doesn't
+/// guarantee that the piece belongs to any specific key, use [`DirtyBackupPiece::verify`] for that.
+pub type BackupPiece<E> = Valid<DirtyBackupPiece<E>>;
+
+/// Public commitment to the backup
+///
+/// Same for all custodians of the backup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct BackupCommitment<E: Curve> {
+    /// Index of the signer whose key share is backed up
+    pub i: u16,
+    /// Amount of custodians $m$
+    pub custodians: u16,
+    /// Commitment to the backup polynomial $[F_0, \dots, F_{k-1}]$
+    ///
+    /// Amount of coefficients is the amount of custodians $k$ required to restore the key share.
+    /// $F_0$ must be equal to public share $X_i$ of the signer.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "serde_with::As::<Vec<generic_ec::serde::Compact>>")
+    )]
+    pub coefs: Vec<NonZero<Point<E>>>,
+}
+
+/// Dirty (unvalidated) piece of the backup
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct DirtyBackupPiece<E: Curve> {
+    /// Index of the custodian, $0 \le j < m$
+    pub j: u16,
+    /// Public commitment to the backup
+    pub commitment: BackupCommitment<E>,
+    /// Secret piece $y_j = f(j+1)$
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "serde_with::As::<generic_ec::serde::Compact>")
+    )]
+    pub y: SecretScalar<E>,
+}
+
+impl<E: Curve> BackupCommitment<E> {
+    /// Amount of custodians $k$ required to restore the key share
+    pub fn min_custodians(&self) -> usize {
+        self.coefs.len()
+    }
+
+    /// Checks that the commitment is anchored to public share of $\ith$ signer in the key info
+    pub fn verify(&self, key_info: &DirtyKeyInfo<E>) -> Result<(), BackupError> {
+        let public_share = key_info
+            .public_shares
+            .get(usize::from(self.i))
+            .ok_or(Reason::SignerIndexOutOfBounds)?;
+        if self.coefs.first() != Some(public_share) {
+            return Err(Reason::CommitmentDoesntMatchKey.into());
+        }
+        Ok(())
+    }
+
+    /// Evaluates $F(x)$
+    fn value(&self, x: &Scalar<E>) -> Point<E> {
+        self.coefs
+            .iter()
+            .rev()
+            .fold(Point::zero(), |acc, coef| acc * x + coef)
+    }
+}
+
+impl<E: Curve> DirtyBackupPiece<E> {
+    /// Checks that the piece belongs to the key share of the signer within given key info
+    ///
+    /// Can be used by a custodian to verify the received piece without reconstructing
+    /// the key share.
+    pub fn verify(&self, key_info: &DirtyKeyInfo<E>) -> Result<(), BackupError> {
+        self.commitment.verify(key_info)
+    }
+}
+
+impl<E: Curve> Validate for DirtyBackupPiece<E> {
+    type Error = BackupError;
+
+    fn is_valid(&self) -> Result<(), Self::Error> {
+        let k = self.commitment.min_custodians();
+        if k == 0 {
+            return Err(Reason::EmptyCommitment.into());
+        }
+        if k > usize::from(self.commitment.custodians) {
+            return Err(Reason::ThresholdTooLarge.into());
+        }
+        if self.j >= self.commitment.custodians {
+            return Err(Reason::CustodianIndexOutOfBounds.into());
+        }
+        let I_j = custodian_index(self.j)?;
+        if Point::generator() * &self.y != self.commitment.value(&I_j) {
+            return Err(Reason::PieceDoesntMatchCommitment.into());
+        }
+        Ok(())
+    }
+}
+
+/// Splits the key share into backup pieces for `custodians` custodians, any `min_custodians`
+/// of which can restore the key share
+///
+/// Returns one piece per custodian, $\jth$ piece must be given to $\jth$ custodian.
+pub fn backup<E: Curve>(
+    key_share: &impl AsRef<CoreKeyShare<E>>,
+    min_custodians: u16,
+    custodians: u16,
+    rng: &mut (impl rand_core::RngCore + rand_core::CryptoRng),
+) -> Result<Vec<BackupPiece<E>>, BackupError> {
+    if min_custodians == 0 {
+        return Err(Reason::EmptyCommitment.into());
+    }
+    if min_custodians > custodians {
+        return Err(Reason::ThresholdTooLarge.into());
+    }
+
+    let key_share = key_share.as_ref();
+    let f = Polynomial::sample_with_const_term(
+        rng,
+        usize::from(min_custodians) - 1,
+        key_share.x.clone(),
+    );
+    let commitment = BackupCommitment {
+        i: key_share.i,
+        custodians,
+        coefs: f.coefs().iter().map(|a| Point::generator() * a).collect(),
+    };
+    debug_assert_eq!(commitment.verify(&key_share.key_info).ok(), Some(()));
+
+    (0..custodians)
+        .map(|j| {
+            let mut y_j: Scalar<E> = f.value(&custodian_index::<E>(j)?);
+            let piece = DirtyBackupPiece {
+                j,
+                commitment: commitment.clone(),
+                y: SecretScalar::new(&mut y_j),
+            };
+            Valid::validate(piece).map_err(|err| err.into_error())
+        })
+        .collect()
+}
+
+/// Restores the key share from backup pieces
+///
+/// Requires pieces from at least $k$ distinct custodians. All pieces must be of the same backup,
+/// and the backup must belong to the key described by `key_info`. Restored key share is validated
+/// against `key_info`.
+pub fn restore<E: Curve>(
+    key_info: &KeyInfo<E>,
+    pieces: &[BackupPiece<E>],
+) -> Result<CoreKeyShare<E>, BackupError> {
+    let commitment = &pieces.first().ok_or(Reason::NotEnoughPieces)?.commitment;
+    if pieces.iter().any(|piece| piece.commitment != *commitment) {
+        return Err(Reason::PiecesOfDifferentBackups.into());
+    }
+    commitment.verify(key_info)?;
+
+    // Take `k` pieces of distinct custodians
+    let mut distinct_pieces: Vec<&BackupPiece<E>> = Vec::new();
+    for piece in pieces {
+        if distinct_pieces.len() == commitment.min_custodians() {
+            break;
+        }
+        if !distinct_pieces.iter().any(|p| p.j == piece.j) {
+            distinct_pieces.push(piece);
+        }
+    }
+    if distinct_pieces.len() < commitment.min_custodians() {
+        return Err(Reason::NotEnoughPieces.into());
+    }
+
+    let indexes = distinct_pieces
+        .iter()
+        .map(|piece| custodian_index(piece.j))
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut x = (0..indexes.len())
+        .zip(&distinct_pieces)
+        .map(|(l, piece)| {
+            let lambda_l = lagrange_coefficient(Scalar::zero(), l, &indexes)
+                .ok_or(Reason::CustodianIndexesNotDistinct)?;
+            Ok(lambda_l * &piece.y)
+        })
+        .sum::<Result<Scalar<E>, BackupError>>()?;
+    let x = NonZero::from_secret_scalar(SecretScalar::new(&mut x)).ok_or(Reason::ZeroShare)?;
+
+    CoreKeyShare::from_parts((commitment.i, DirtyKeyInfo::clone(key_info), x))
+        .map_err(|err| Reason::InvalidKeyShare(err.into_error()).into())
+}
+
+/// Index $j+1$ at which backup polynomial is evaluated for $\jth$ custodian
+fn custodian_index<E: Curve>(j: u16) -> Result<NonZero<Scalar<E>>, BackupError> {
+    NonZero::from_scalar(Scalar::from(u32::from(j) + 1))
+        .ok_or_else(|| Reason::DeriveCustodianIndex.into())
+}
+
+/// Error related to [key share backups](self)
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("key share backup error")]
+pub struct BackupError(#[cfg_attr(feature = "std", source)] Reason);
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum Reason {
+    #[displaydoc("at least one custodian must be required to restore the key share")]
+    EmptyCommitment,
+    #[displaydoc(
+        "amount of custodians required to restore the key share exceeds amount of custodians"
+    )]
+    ThresholdTooLarge,
+    #[displaydoc("custodian index is out of bounds")]
+    CustodianIndexOutOfBounds,
+    #[displaydoc("signer index is out of bounds")]
+    SignerIndexOutOfBounds,
+    #[displaydoc("deriving custodian index failed")]
+    DeriveCustodianIndex,
+    #[displaydoc("backup piece doesn't match the commitment")]
+    PieceDoesntMatchCommitment,
+    #[displaydoc("backup commitment doesn't match public share of the signer")]
+    CommitmentDoesntMatchKey,
+    #[displaydoc("backup pieces belong to different backups")]
+    PiecesOfDifferentBackups,
+    #[displaydoc("not enough pieces from distinct custodians to restore the key share")]
+    NotEnoughPieces,
+    #[displaydoc("custodian indexes are not pairwise distinct")]
+    CustodianIndexesNotDistinct,
+    #[displaydoc("restored secret share is zero")]
+    ZeroShare,
+    #[displaydoc("restored key share is invalid")]
+    InvalidKeyShare(#[cfg_attr(feature = "std", source)] crate::InvalidCoreShare),
+}
+
+impl From<Reason> for BackupError {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
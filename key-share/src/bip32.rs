@@ -0,0 +1,240 @@
+//! BIP32 serialization of extended public keys and derivation paths
+//!
+//! HD wallets support is based on [SLIP-10](slip_10), which is compatible with
+//! [BIP32](https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki) for secp256k1 curve.
+//! This module provides interoperability with wallets that use BIP32 formats:
+//! * [`DerivationPath`] can be parsed from strings like `m/44/60/0/0/5`. Only non-hardened
+//!   indexes are supported, as hardened derivation is not possible for TSS keys. Derivation path
+//!   can be used wherever a derivation path is expected, e.g. in
+//!   [`derive_child_public_key`](crate::DirtyCoreKeyShare::derive_child_public_key).
+//! * [`encode_xpub`] exports extended public key of secp256k1 key as Base58Check string (`xpub`,
+//!   `tpub`, `zpub`, etc.) with [SLIP-132](https://github.com/satoshilabs/slips/blob/master/slip-0132.md)
+//!   version bytes, which can be imported into a watch-only wallet.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use key_share::bip32::{DerivationPath, Version};
+//!
+//! # fn watch_only<E: generic_ec::Curve>(
+//! #     key_share: &key_share::CoreKeyShare<E>,
+//! # ) -> Result<(), key_share::bip32::Bip32Error> {
+//! let path: DerivationPath = "m/44/60/0".parse()?;
+//! let xpub = key_share.encode_xpub(Version::Xpub, &path)?;
+//! println!("Watch-only wallet: {xpub}");
+//! # Ok(()) }
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt, str::FromStr};
+
+use bitcoin_hashes::{hash160, Hash};
+use generic_ec::{Curve, Point};
+use slip_10::{ExtendedPublicKey, NonHardenedIndex};
+
+use crate::DirtyCoreKeyShare;
+
+/// Derivation path consisting of non-hardened indexes
+///
+/// Can be parsed from a string like `m/44/60/0/0/5`, and displayed in the same format.
+#[derive(Debug, Clone, Default)]
+pub struct DerivationPath(Vec<NonHardenedIndex>);
+
+impl DerivationPath {
+    /// Returns child indexes of the path
+    pub fn indexes(&self) -> &[NonHardenedIndex] {
+        &self.0
+    }
+}
+
+impl From<Vec<NonHardenedIndex>> for DerivationPath {
+    fn from(indexes: Vec<NonHardenedIndex>) -> Self {
+        Self(indexes)
+    }
+}
+
+impl FromIterator<NonHardenedIndex> for DerivationPath {
+    fn from_iter<T: IntoIterator<Item = NonHardenedIndex>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for DerivationPath {
+    type Item = NonHardenedIndex;
+    type IntoIter = alloc::vec::IntoIter<NonHardenedIndex>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a DerivationPath {
+    type Item = NonHardenedIndex;
+    type IntoIter = core::iter::Copied<core::slice::Iter<'a, NonHardenedIndex>>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter().copied()
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Bip32Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut segments = s.split('/');
+        if segments.next() != Some("m") {
+            return Err(Reason::MissingPrefix.into());
+        }
+        segments
+            .map(|segment| {
+                if segment.ends_with(['\'', 'h', 'H']) {
+                    return Err(Reason::HardenedIndex.into());
+                }
+                // `u32::from_str` also accepts leading `+` which is not allowed in derivation path
+                if segment.is_empty() || !segment.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(Reason::InvalidIndex.into());
+                }
+                let index: u32 = segment.parse().map_err(|_| Reason::InvalidIndex)?;
+                NonHardenedIndex::try_from(index).map_err(|_| Reason::HardenedIndex.into())
+            })
+            .collect()
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("m")?;
+        self.0
+            .iter()
+            .try_for_each(|index| write!(f, "/{}", u32::from(*index)))
+    }
+}
+
+/// Version bytes of serialized extended public key, as defined in SLIP-132
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    /// `xpub`: mainnet, P2PKH or P2SH
+    Xpub,
+    /// `ypub`: mainnet, P2WPKH nested in P2SH
+    Ypub,
+    /// `zpub`: mainnet, P2WPKH
+    Zpub,
+    /// `tpub`: testnet, P2PKH or P2SH
+    Tpub,
+    /// `upub`: testnet, P2WPKH nested in P2SH
+    Upub,
+    /// `vpub`: testnet, P2WPKH
+    Vpub,
+}
+
+impl Version {
+    /// Returns version bytes
+    pub fn to_bytes(self) -> [u8; 4] {
+        match self {
+            Self::Xpub => [0x04, 0x88, 0xb2, 0x1e],
+            Self::Ypub => [0x04, 0x9d, 0x7c, 0xb2],
+            Self::Zpub => [0x04, 0xb2, 0x47, 0x46],
+            Self::Tpub => [0x04, 0x35, 0x87, 0xcf],
+            Self::Upub => [0x04, 0x4a, 0x52, 0x62],
+            Self::Vpub => [0x04, 0x5f, 0x1c, 0xf6],
+        }
+    }
+}
+
+/// Encodes extended public key of a child key as Base58Check string
+///
+/// `master` is extended public key of the master key (see
+/// [`extended_public_key`](crate::DirtyCoreKeyShare::extended_public_key)), and `path` is derivation
+/// path of the child key. Depth, parent fingerprint and child number are derived from the path, so
+/// the output is the same as what a BIP32 wallet would produce for the same path. If `path` is empty,
+/// the master key is encoded.
+///
+/// Returns error if the path is longer than 255 indexes, or if the curve is not secp256k1: BIP32
+/// is only defined for secp256k1, wallets would misinterpret extended public key of any other
+/// curve.
+pub fn encode_xpub<E: Curve>(
+    version: Version,
+    master: &ExtendedPublicKey<E>,
+    path: &DerivationPath,
+) -> Result<String, Bip32Error> {
+    if E::CURVE_NAME != "secp256k1" {
+        return Err(Reason::UnsupportedCurve.into());
+    }
+    let depth = u8::try_from(path.0.len()).map_err(|_| Reason::PathTooLong)?;
+    let (parent_fingerprint, child_number, epub) = match path.0.split_last() {
+        None => ([0u8; 4], 0, *master),
+        Some((&child_index, parent_path)) => {
+            let parent =
+                slip_10::derive_child_public_key_with_path(master, parent_path.iter().copied());
+            let child = slip_10::derive_child_public_key(&parent, child_index);
+            (
+                fingerprint(&parent.public_key)?,
+                u32::from(child_index),
+                child,
+            )
+        }
+    };
+
+    let mut data = Vec::with_capacity(82);
+    data.extend_from_slice(&version.to_bytes());
+    data.push(depth);
+    data.extend_from_slice(&parent_fingerprint);
+    data.extend_from_slice(&child_number.to_be_bytes());
+    data.extend_from_slice(&epub.chain_code);
+    data.extend_from_slice(&compressed_public_key(&epub.public_key)?);
+
+    Ok(bs58::encode(data).with_check().into_string())
+}
+
+impl<E: Curve> DirtyCoreKeyShare<E> {
+    /// Encodes extended public key of a child key as Base58Check string, if it's HD key
+    ///
+    /// See [`encode_xpub`].
+    pub fn encode_xpub(
+        &self,
+        version: Version,
+        path: &DerivationPath,
+    ) -> Result<String, Bip32Error> {
+        let master = self.extended_public_key().ok_or(Reason::DisabledHd)?;
+        encode_xpub(version, &master, path)
+    }
+}
+
+fn compressed_public_key<E: Curve>(public_key: &Point<E>) -> Result<[u8; 33], Bip32Error> {
+    (*public_key.to_bytes(true))
+        .try_into()
+        .map_err(|_| Reason::UnsupportedCurve.into())
+}
+
+fn fingerprint<E: Curve>(public_key: &Point<E>) -> Result<[u8; 4], Bip32Error> {
+    let hash = hash160::Hash::hash(&compressed_public_key(public_key)?).into_inner();
+    let mut fingerprint = [0u8; 4];
+    fingerprint.copy_from_slice(&hash[..4]);
+    Ok(fingerprint)
+}
+
+/// Error related to BIP32 serialization
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("bip32 error")]
+pub struct Bip32Error(#[cfg_attr(feature = "std", source)] Reason);
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum Reason {
+    #[displaydoc("HD derivation is disabled for the key")]
+    DisabledHd,
+    #[displaydoc("derivation path must start with `m`")]
+    MissingPrefix,
+    #[displaydoc("invalid child index in derivation path")]
+    InvalidIndex,
+    #[displaydoc("hardened derivation is not supported")]
+    HardenedIndex,
+    #[displaydoc("derivation path is too long: depth can't exceed 255")]
+    PathTooLong,
+    #[displaydoc("BIP32 format is only defined for secp256k1 curve")]
+    UnsupportedCurve,
+}
+
+impl From<Reason> for Bip32Error {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
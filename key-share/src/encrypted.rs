@@ -0,0 +1,318 @@
+//! Encrypted storage of key shares
+//!
+//! Key share contains secret material that needs to be kept confidential at rest. This module seals
+//! a key share into [`EncryptedKeyShare`] envelope using ChaCha20-Poly1305 AEAD. Encryption key is
+//! either derived from a passphrase via Argon2id, or provided by the caller as a key-encryption key
+//! (KEK), e.g. the one that's managed by KMS or HSM.
+//!
+//! Public [key info](DirtyKeyInfo) is stored in the envelope unencrypted and is bound to the
+//! ciphertext as associated data: it can be inspected without decryption, but any modification of
+//! it makes decryption fail.
+//!
+//! Envelope is versioned and can be serialized with serde. Secret part of the envelope is encoded
+//! with [`ciborium`](https://docs.rs/ciborium) before encryption.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use key_share::encrypted::{EncryptedKeyShare, EncryptionKey};
+//! use key_share::CoreKeyShare;
+//!
+//! fn store<E: generic_ec::Curve>(
+//!     key_share: &CoreKeyShare<E>,
+//!     passphrase: &[u8],
+//! ) -> Result<EncryptedKeyShare<E>, key_share::encrypted::EncryptionError> {
+//!     let mut rng = rand_core::OsRng;
+//!     let key = EncryptionKey::Passphrase(passphrase);
+//!     let encrypted = EncryptedKeyShare::seal(key_share, key, &mut rng)?;
+//!     // Public key can be read without decrypting the key share
+//!     let _pk = encrypted.key_info().shared_public_key;
+//!
+//!     let _key_share: CoreKeyShare<E> = encrypted.open(key)?;
+//!     Ok(encrypted)
+//! }
+//! ```
+
+use alloc::vec::Vec;
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key,
+};
+use generic_ec::Curve;
+use rand_core::{CryptoRng, RngCore};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use crate::{DirtyCoreKeyShare, DirtyKeyInfo, Valid, Validate};
+
+/// Version of the envelope format
+///
+/// Version is written into every envelope, opening envelopes of unknown versions is rejected.
+pub const VERSION: u8 = 1;
+
+/// Size of key-encryption key in bytes
+pub const KEK_SIZE: usize = 32;
+
+/// Max Argon2id memory cost (in KiB) accepted when opening the envelope
+///
+/// KDF parameters are read from the envelope before it's authenticated, so they're bounded by
+/// 8 times [library defaults](argon2::Params::default). Otherwise, a tampered envelope could make
+/// [`EncryptedKeyShare::open`] allocate arbitrary amount of memory or run for arbitrary long time.
+pub const MAX_ARGON2_M_COST: u32 = 8 * argon2::Params::DEFAULT_M_COST;
+/// Max Argon2id amount of iterations accepted when opening the envelope
+///
+/// See [`MAX_ARGON2_M_COST`]
+pub const MAX_ARGON2_T_COST: u32 = 8 * argon2::Params::DEFAULT_T_COST;
+/// Max Argon2id degree of parallelism accepted when opening the envelope
+///
+/// See [`MAX_ARGON2_M_COST`]
+pub const MAX_ARGON2_P_COST: u32 = 8 * argon2::Params::DEFAULT_P_COST;
+
+/// Key used to seal or open [`EncryptedKeyShare`]
+#[derive(Clone, Copy)]
+pub enum EncryptionKey<'a> {
+    /// Encryption key is derived from the passphrase via Argon2id
+    Passphrase(&'a [u8]),
+    /// Key-encryption key provided by the caller
+    ///
+    /// Must be uniformly random and kept secret.
+    Kek(&'a [u8; KEK_SIZE]),
+}
+
+/// Key share that can be sealed into [`EncryptedKeyShare`]
+///
+/// Implemented for [`DirtyCoreKeyShare`] and any valid key share, `cggmp21` implements it for
+/// its key share as well.
+pub trait SealableKeyShare<E: Curve>: Serialize + DeserializeOwned {
+    /// Returns public key info of the key share
+    fn key_info(&self) -> &DirtyKeyInfo<E>;
+}
+
+impl<E: Curve> SealableKeyShare<E> for DirtyCoreKeyShare<E> {
+    fn key_info(&self) -> &DirtyKeyInfo<E> {
+        &self.key_info
+    }
+}
+
+impl<E: Curve, T> SealableKeyShare<E> for Valid<T>
+where
+    T: SealableKeyShare<E> + Validate,
+    <T as Validate>::Error: core::fmt::Display,
+{
+    fn key_info(&self) -> &DirtyKeyInfo<E> {
+        (**self).key_info()
+    }
+}
+
+/// Encrypted key share
+///
+/// Holds public key info in the clear and the whole key share encrypted. Can be obtained via
+/// [`EncryptedKeyShare::seal`]. Use [`EncryptedKeyShare::open`] to decrypt the key share.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct EncryptedKeyShare<E: Curve> {
+    version: u8,
+    key_info: DirtyKeyInfo<E>,
+    kdf: Kdf,
+    #[serde(with = "hex")]
+    nonce: [u8; 12],
+    #[serde(with = "hex")]
+    ciphertext: Vec<u8>,
+}
+
+/// Specifies how the encryption key is obtained
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+enum Kdf {
+    /// Key is derived from passphrase via Argon2id with given parameters
+    Argon2id {
+        #[serde(with = "hex")]
+        salt: [u8; 16],
+        m_cost: u32,
+        t_cost: u32,
+        p_cost: u32,
+    },
+    /// Key-encryption key is used as it is
+    Kek,
+}
+
+impl<E: Curve> EncryptedKeyShare<E> {
+    /// Encrypts the key share
+    ///
+    /// When key is derived from passphrase, Argon2id is used with [default parameters](argon2::Params::default).
+    pub fn seal<T, R>(
+        key_share: &T,
+        key: EncryptionKey,
+        rng: &mut R,
+    ) -> Result<Self, EncryptionError>
+    where
+        T: SealableKeyShare<E>,
+        R: RngCore + CryptoRng,
+    {
+        let kdf = match key {
+            EncryptionKey::Passphrase(_) => {
+                let mut salt = [0u8; 16];
+                rng.fill_bytes(&mut salt);
+                let params = argon2::Params::default();
+                Kdf::Argon2id {
+                    salt,
+                    m_cost: params.m_cost(),
+                    t_cost: params.t_cost(),
+                    p_cost: params.p_cost(),
+                }
+            }
+            EncryptionKey::Kek(_) => Kdf::Kek,
+        };
+        let mut nonce = [0u8; 12];
+        rng.fill_bytes(&mut nonce);
+        let key_info = key_share.key_info().clone();
+
+        let mut plaintext = Zeroizing::new(Vec::new());
+        ciborium::into_writer(key_share, &mut *plaintext)
+            .map_err(|_| EncryptionReason::SerializeKeyShare)?;
+        let associated_data = associated_data(VERSION, &key_info, &kdf)
+            .map_err(|_| EncryptionReason::SerializeKeyInfo)?;
+
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&*derive_key(&kdf, key)?));
+        let ciphertext = cipher
+            .encrypt(
+                &nonce.into(),
+                Payload {
+                    msg: &plaintext,
+                    aad: &associated_data,
+                },
+            )
+            .map_err(|_| EncryptionReason::Encrypt)?;
+
+        Ok(Self {
+            version: VERSION,
+            key_info,
+            kdf,
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// Decrypts the key share
+    ///
+    /// Returns error if the key is wrong, or if the envelope was modified.
+    pub fn open<T>(&self, key: EncryptionKey) -> Result<T, EncryptionError>
+    where
+        T: SealableKeyShare<E>,
+    {
+        if self.version != VERSION {
+            return Err(EncryptionReason::UnknownVersion(self.version).into());
+        }
+        let associated_data = associated_data(self.version, &self.key_info, &self.kdf)
+            .map_err(|_| EncryptionReason::SerializeKeyInfo)?;
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&*derive_key(&self.kdf, key)?));
+        let plaintext = cipher
+            .decrypt(
+                &self.nonce.into(),
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: &associated_data,
+                },
+            )
+            .map(Zeroizing::new)
+            .map_err(|_| EncryptionReason::Decrypt)?;
+        ciborium::from_reader(plaintext.as_slice())
+            .map_err(|_| EncryptionReason::DeserializeKeyShare.into())
+    }
+
+    /// Returns public key info of the encrypted key share
+    ///
+    /// Key info is not validated. It's authenticated only when the envelope is
+    /// [opened](Self::open).
+    pub fn key_info(&self) -> &DirtyKeyInfo<E> {
+        &self.key_info
+    }
+
+    /// Indicates whether the key share is encrypted under a passphrase (as opposed to key-encryption key)
+    pub fn is_passphrase_protected(&self) -> bool {
+        matches!(self.kdf, Kdf::Argon2id { .. })
+    }
+}
+
+/// Associated data that's bound to the ciphertext
+fn associated_data<E: Curve>(
+    version: u8,
+    key_info: &DirtyKeyInfo<E>,
+    kdf: &Kdf,
+) -> Result<Vec<u8>, ciborium::ser::Error<std::io::Error>> {
+    let mut bytes = Vec::new();
+    ciborium::into_writer(&(version, key_info, kdf), &mut bytes)?;
+    Ok(bytes)
+}
+
+/// Derives symmetric key used in AEAD
+fn derive_key(kdf: &Kdf, key: EncryptionKey) -> Result<Zeroizing<[u8; 32]>, EncryptionError> {
+    match (kdf, key) {
+        (
+            Kdf::Argon2id {
+                salt,
+                m_cost,
+                t_cost,
+                p_cost,
+            },
+            EncryptionKey::Passphrase(passphrase),
+        ) => {
+            // Parameters are not authenticated yet, they must be checked before running Argon2
+            if *m_cost > MAX_ARGON2_M_COST
+                || *t_cost > MAX_ARGON2_T_COST
+                || *p_cost > MAX_ARGON2_P_COST
+            {
+                return Err(EncryptionReason::KdfParamsTooLarge.into());
+            }
+            let params = argon2::Params::new(*m_cost, *t_cost, *p_cost, Some(32))
+                .map_err(|_| EncryptionReason::KdfParams)?;
+            let argon2 =
+                argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params);
+            let mut derived_key = Zeroizing::new([0u8; 32]);
+            argon2
+                .hash_password_into(passphrase, salt, &mut *derived_key)
+                .map_err(|_| EncryptionReason::KdfParams)?;
+            Ok(derived_key)
+        }
+        (Kdf::Kek, EncryptionKey::Kek(kek)) => Ok(Zeroizing::new(*kek)),
+        (Kdf::Argon2id { .. }, EncryptionKey::Kek(_)) => {
+            Err(EncryptionReason::KeyKind("passphrase").into())
+        }
+        (Kdf::Kek, EncryptionKey::Passphrase(_)) => {
+            Err(EncryptionReason::KeyKind("key-encryption key").into())
+        }
+    }
+}
+
+/// Error related to [encrypted key shares](EncryptedKeyShare)
+#[derive(Debug, displaydoc::Display, thiserror::Error)]
+#[displaydoc("key share encryption error")]
+pub struct EncryptionError(#[source] EncryptionReason);
+
+#[derive(Debug, displaydoc::Display, thiserror::Error)]
+enum EncryptionReason {
+    #[displaydoc("unknown version of the envelope: {0}")]
+    UnknownVersion(u8),
+    #[displaydoc("key share is encrypted under {0}")]
+    KeyKind(&'static str),
+    #[displaydoc("invalid key derivation parameters")]
+    KdfParams,
+    #[displaydoc("key derivation parameters exceed the limits")]
+    KdfParamsTooLarge,
+    #[displaydoc("serialize key share")]
+    SerializeKeyShare,
+    #[displaydoc("serialize key info")]
+    SerializeKeyInfo,
+    #[displaydoc("encryption failed")]
+    Encrypt,
+    #[displaydoc("decryption failed: wrong key or envelope was modified")]
+    Decrypt,
+    #[displaydoc("deserialize decrypted key share")]
+    DeserializeKeyShare,
+}
+
+impl From<EncryptionReason> for EncryptionError {
+    fn from(err: EncryptionReason) -> Self {
+        Self(err)
+    }
+}
@@ -28,12 +28,22 @@ use core::ops;
 use generic_ec::{serde::CurveName, Curve, NonZero, Point, Scalar, SecretScalar};
 use generic_ec_zkp::polynomial::lagrange_coefficient;
 
+#[cfg(feature = "backup")]
+pub mod backup;
+#[cfg(feature = "hd-wallets")]
+pub mod bip32;
+#[cfg(feature = "encrypted")]
+pub mod encrypted;
+#[cfg(feature = "pop")]
+pub mod pop;
 #[cfg(feature = "serde")]
 mod serde_fix;
 #[cfg(feature = "spof")]
 pub mod trusted_dealer;
 mod utils;
 mod valid;
+#[cfg(feature = "serde")]
+pub mod versioned;
 
 pub use self::valid::{Valid, Validate, ValidateError, ValidateFromParts};
 
@@ -97,14 +107,17 @@ use serde_with::As;
 ///   [extended_public_key](DirtyCoreKeyShare::extended_public_key) method).
 ///   * Setting `chain_code` to `None` disables HD wallets support for the key
 /// * Convenient methods are provided such as [derive_child_public_key](DirtyCoreKeyShare::derive_child_public_key)
+/// * Key share of a child key can be derived via [derive_child_share](DirtyCoreKeyShare::derive_child_share)
+/// * Derivation paths and extended public keys can be converted from/to BIP32 formats, see [`bip32`] module
 ///
 /// # Serialization format via `serde`
 /// We make our best effort to keep serialization format the same between the versions (even with breaking changes),
 /// and so far we've never introduced breaking change into the serialization format. This ensures that newer versions
 /// of library are able to deserialize the key shares produced by the old version version of the library.
 ///
-/// It's unlikely, but at some point, we might introduce a breaking change into the serialization format. In this case,
-/// we'll announce it and publish the migration instructions.
+/// Serialized key share carries explicit format version. Key shares serialized in older formats are upgraded to the
+/// current one on deserialization, and key share can be serialized in an older format to roll back to an older version
+/// of the library. See [`versioned`] module.
 ///
 /// Not every serde backend supports features that we use to ensure backwards compatibility. We require that field names
 /// are being serialized, that helps us adding new fields as the library grows. We strongly advise using either
@@ -131,31 +144,7 @@ impl<E: Curve> serde::Serialize for DirtyCoreKeyShare<E> {
     where
         S: serde::Serializer,
     {
-        // See [`crate::serde_fix`] module docs
-        let Self {
-            i,
-            key_info:
-                DirtyKeyInfo {
-                    curve,
-                    shared_public_key,
-                    public_shares,
-                    vss_setup,
-                    #[cfg(feature = "hd-wallets")]
-                    chain_code,
-                },
-            x,
-        } = &self;
-        serde_fix::ser::CoreKeyShare {
-            i,
-            curve,
-            shared_public_key,
-            public_shares,
-            vss_setup,
-            x,
-            #[cfg(feature = "hd-wallets")]
-            chain_code,
-        }
-        .serialize(serializer)
+        versioned::Versioned::current(self).serialize(serializer)
     }
 }
 
@@ -165,8 +154,12 @@ impl<'de, E: Curve> serde::Deserialize<'de> for DirtyCoreKeyShare<E> {
     where
         D: serde::Deserializer<'de>,
     {
+        use serde::de::Error;
+
         // See [`crate::serde_fix`] module docs
+        let key_share = serde::Deserialize::deserialize(deserializer)?;
         let serde_fix::de::CoreKeyShare {
+            version: _,
             curve,
             i,
             shared_public_key,
@@ -175,7 +168,8 @@ impl<'de, E: Curve> serde::Deserialize<'de> for DirtyCoreKeyShare<E> {
             x,
             #[cfg(feature = "hd-wallets")]
             chain_code,
-        } = serde::Deserialize::deserialize(deserializer)?;
+            epoch,
+        } = versioned::migrate(key_share).map_err(D::Error::custom)?;
         Ok(Self {
             i,
             key_info: DirtyKeyInfo {
@@ -185,6 +179,7 @@ impl<'de, E: Curve> serde::Deserialize<'de> for DirtyCoreKeyShare<E> {
                 vss_setup,
                 #[cfg(feature = "hd-wallets")]
                 chain_code,
+                epoch,
             },
             x,
         })
@@ -233,6 +228,15 @@ pub struct DirtyKeyInfo<E: Curve> {
     )]
     #[cfg_attr(feature = "udigest", udigest(with = utils::encoding::maybe_bytes))]
     pub chain_code: Option<slip_10::ChainCode>,
+    /// Epoch of the key shares
+    ///
+    /// Number of key refreshes performed since the key was generated. Key shares of the same
+    /// generation have the same epoch, it's `0` right after keygen.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "utils::is_zero")
+    )]
+    pub epoch: u64,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -384,6 +388,44 @@ fn validate_non_vss_key_info<E: Curve>(
 }
 
 impl<E: Curve> DirtyKeyInfo<E> {
+    /// Returns stable identifier of the key
+    ///
+    /// Identifier is a hash of the curve, shared public key, VSS setup and chain code. It's the same
+    /// for all signers sharing the key, and it doesn't change when key shares are refreshed, so it
+    /// can be used to index storage or to bind execution IDs to the key.
+    ///
+    /// Missing chain code is encoded the same way regardless of whether `hd-wallets` feature is
+    /// enabled, so a key without chain code has the same identifier in both cases.
+    #[cfg(feature = "udigest")]
+    pub fn key_id(&self) -> KeyId {
+        #[derive(udigest::Digestable)]
+        #[udigest(bound = "")]
+        struct KeyIdDigest<'a, E: Curve> {
+            curve: &'static str,
+            shared_public_key: NonZero<Point<E>>,
+            vss_setup: Option<(u16, &'a [NonZero<Scalar<E>>])>,
+            chain_code: Option<udigest::Bytes<&'a [u8]>>,
+        }
+
+        let digest = KeyIdDigest {
+            curve: E::CURVE_NAME,
+            shared_public_key: self.shared_public_key,
+            vss_setup: self
+                .vss_setup
+                .as_ref()
+                .map(|s| (s.min_signers, s.I.as_slice())),
+            #[cfg(feature = "hd-wallets")]
+            chain_code: self.chain_code.as_ref().map(|c| udigest::Bytes(&c[..])),
+            #[cfg(not(feature = "hd-wallets"))]
+            chain_code: None,
+        };
+        KeyId(
+            udigest::Tag::<sha2::Sha256>::new("dfns.key_share.key_id")
+                .digest(digest)
+                .into(),
+        )
+    }
+
     /// Returns share preimage associated with j-th signer
     ///
     /// * For additive shares, share preimage is defined as `j+1`
@@ -440,6 +482,72 @@ impl<E: Curve> DirtyCoreKeyShare<E> {
         )
         .map_err(HdError::InvalidPath)
     }
+
+    /// Derives child key share, if it's HD key
+    ///
+    /// Returns a key share of the child key derived from the master key using `derivation_path`:
+    /// shared public key and chain code are replaced with the ones of child extended public key,
+    /// and secret share and public shares are shifted accordingly. Signing with derived key share
+    /// doesn't require setting derivation path, and produces signatures valid under child public
+    /// key.
+    ///
+    /// All signers need to derive their key shares using the same derivation path.
+    pub fn derive_child_share<ChildIndex>(
+        &self,
+        derivation_path: impl IntoIterator<Item = ChildIndex>,
+    ) -> Result<CoreKeyShare<E>, HdError<<ChildIndex as TryInto<slip_10::NonHardenedIndex>>::Error>>
+    where
+        slip_10::NonHardenedIndex: TryFrom<ChildIndex>,
+    {
+        let mut epub = self.extended_public_key().ok_or(HdError::DisabledHd)?;
+        let mut shift = Scalar::<E>::zero();
+        for child_index in derivation_path {
+            let child_index = child_index.try_into().map_err(HdError::InvalidPath)?;
+            let derived = slip_10::derive_public_shift(&epub, child_index);
+            shift += derived.shift;
+            epub = derived.child_public_key;
+        }
+        let shift_pub = Point::generator() * shift;
+
+        // For VSS key, the shift is added to a free term of the polynomial, so it's added
+        // to all the shares. For additive key, the shift is added to the share of the
+        // first signer only.
+        let is_shifted = |j: u16| self.vss_setup.is_some() || j == 0;
+
+        let public_shares = (0u16..)
+            .zip(&self.public_shares)
+            .map(|(j, X_j)| {
+                if is_shifted(j) {
+                    NonZero::from_point(X_j + shift_pub)
+                } else {
+                    Some(*X_j)
+                }
+            })
+            .collect::<Option<Vec<_>>>()
+            .ok_or(HdError::InvalidChildShare)?;
+        let x = if is_shifted(self.i) {
+            NonZero::from_scalar(&self.x + shift)
+                .ok_or(HdError::InvalidChildShare)?
+                .into_secret()
+        } else {
+            self.x.clone()
+        };
+
+        let child_share = DirtyCoreKeyShare {
+            i: self.i,
+            key_info: DirtyKeyInfo {
+                curve: self.curve,
+                shared_public_key: NonZero::from_point(epub.public_key)
+                    .ok_or(HdError::InvalidChildShare)?,
+                public_shares,
+                vss_setup: self.vss_setup.clone(),
+                chain_code: Some(epub.chain_code),
+                epoch: self.epoch,
+            },
+            x,
+        };
+        Valid::validate(child_share).map_err(|_| HdError::InvalidChildShare)
+    }
 }
 
 impl<E: Curve> CoreKeyShare<E> {
@@ -486,6 +594,36 @@ impl<E: Curve> AsRef<CoreKeyShare<E>> for CoreKeyShare<E> {
     }
 }
 
+/// Stable identifier of the key
+///
+/// Obtained via [`DirtyKeyInfo::key_id`]. Displayed as hex string.
+#[cfg(feature = "udigest")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyId(#[cfg_attr(feature = "serde", serde(with = "hex"))] [u8; 32]);
+
+#[cfg(feature = "udigest")]
+impl KeyId {
+    /// Returns bytes representation of the key ID
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "udigest")]
+impl From<[u8; 32]> for KeyId {
+    fn from(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+}
+
+#[cfg(feature = "udigest")]
+impl core::fmt::Display for KeyId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.iter().try_for_each(|b| write!(f, "{b:02x}"))
+    }
+}
+
 /// Error indicating that key share is not valid
 #[derive(Debug, displaydoc::Display)]
 #[cfg_attr(feature = "std", derive(thiserror::Error))]
@@ -527,11 +665,14 @@ impl From<InvalidShareReason> for InvalidCoreShare {
 /// Error related to HD key derivation
 #[derive(Debug, displaydoc::Display)]
 #[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[non_exhaustive]
 pub enum HdError<E> {
     /// HD derivation is disabled for the key
     DisabledHd,
     /// derivation path is not valid
     InvalidPath(#[cfg_attr(feature = "std", source)] E),
+    /// derived child key share is not valid
+    InvalidChildShare,
 }
 
 impl<T> From<ValidateError<T, InvalidCoreShare>> for InvalidCoreShare {
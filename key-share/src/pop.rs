@@ -0,0 +1,142 @@
+//! Proof of possession of secret share
+//!
+//! Signer can prove that it holds a secret share $x_i$ matching its public share
+//! $X_i = x_i \cdot G$ listed in [`public_shares`](crate::DirtyKeyInfo::public_shares), without
+//! revealing anything about $x_i$. This lets an external auditor, who only knows public
+//! [key info](crate::KeyInfo), confirm that each signer really holds a valid share of the key.
+//!
+//! Proof is a non-interactive Schnorr proof of knowledge. Its challenge is bound to the
+//! [key ID](crate::DirtyKeyInfo::key_id), index and public share of the signer, and to a context
+//! chosen by the auditor. Auditor should pick a fresh context (e.g. a random nonce) for each audit,
+//! so proofs produced for one audit can't be replayed in another.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use key_share::pop;
+//! use key_share::{CoreKeyShare, KeyInfo};
+//!
+//! fn audit<E: generic_ec::Curve>(
+//!     key_share: &CoreKeyShare<E>,
+//!     key_info: &KeyInfo<E>,
+//! ) -> Result<(), pop::PopError> {
+//!     let mut rng = rand_core::OsRng;
+//!     // Nonce chosen by the auditor
+//!     let context = b"audit nonce";
+//!
+//!     // Signer proves possession of its secret share
+//!     let proof = key_share.prove_possession(context, &mut rng);
+//!
+//!     // Auditor verifies the proof against public key info
+//!     key_info.verify_possession(key_share.i, context, &proof)
+//! }
+//! ```
+
+use generic_ec::{Curve, Point, Scalar};
+use generic_ec_zkp::schnorr_pok;
+
+use crate::{DirtyCoreKeyShare, DirtyKeyInfo};
+
+/// Proof that signer possesses secret share matching its public share
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+pub struct ProofOfPossession<E: Curve> {
+    /// Commitment to the ephemeral secret
+    pub commit: Point<E>,
+    /// Response to the challenge
+    pub proof: Scalar<E>,
+}
+
+impl<E: Curve> DirtyCoreKeyShare<E> {
+    /// Proves possession of the secret share
+    ///
+    /// `context` must be the same as the one used by the verifier. See [module-level docs](self).
+    pub fn prove_possession(
+        &self,
+        context: &[u8],
+        rng: &mut (impl rand_core::RngCore + rand_core::CryptoRng),
+    ) -> ProofOfPossession<E> {
+        let public_share = Point::generator() * &self.x;
+        let (secret, commit) = schnorr_pok::prover_commits_ephemeral_secret::<E, _>(rng);
+        let challenge = challenge(&self.key_info, self.i, &public_share, context, &commit);
+        let proof = schnorr_pok::prove(&secret, &challenge, &self.x);
+        ProofOfPossession {
+            commit: commit.0,
+            proof: proof.0,
+        }
+    }
+}
+
+impl<E: Curve> DirtyKeyInfo<E> {
+    /// Verifies that $\ith$ signer possesses secret share matching its public share
+    ///
+    /// `context` must be the same as the one used by the prover. See [module-level docs](crate::pop).
+    pub fn verify_possession(
+        &self,
+        i: u16,
+        context: &[u8],
+        proof: &ProofOfPossession<E>,
+    ) -> Result<(), PopError> {
+        let public_share = self
+            .public_shares
+            .get(usize::from(i))
+            .ok_or(Reason::SignerIndexOutOfBounds)?;
+        let commit = schnorr_pok::Commit(proof.commit);
+        let challenge = challenge(self, i, public_share, context, &commit);
+        schnorr_pok::Proof(proof.proof)
+            .verify(&commit, &challenge, public_share)
+            .map_err(|_| Reason::InvalidProof.into())
+    }
+}
+
+/// Derives challenge of the proof via Fiat-Shamir transform
+fn challenge<E: Curve>(
+    key_info: &DirtyKeyInfo<E>,
+    i: u16,
+    public_share: &Point<E>,
+    context: &[u8],
+    commit: &schnorr_pok::Commit<E>,
+) -> schnorr_pok::Challenge<E> {
+    #[derive(udigest::Digestable)]
+    #[udigest(bound = "")]
+    struct ChallengeDigest<'a, E: Curve> {
+        key_id: udigest::Bytes<&'a [u8]>,
+        i: u16,
+        public_share: &'a Point<E>,
+        context: udigest::Bytes<&'a [u8]>,
+        commit: &'a Point<E>,
+    }
+
+    let key_id = key_info.key_id();
+    let hash = udigest::Tag::<sha2::Sha256>::new("dfns.key_share.pop").digest(ChallengeDigest {
+        key_id: udigest::Bytes(key_id.as_bytes()),
+        i,
+        public_share,
+        context: udigest::Bytes(context),
+        commit: &commit.0,
+    });
+    schnorr_pok::Challenge {
+        nonce: Scalar::from_be_bytes_mod_order(hash),
+    }
+}
+
+/// Error indicating that [proof of possession](self) is not valid
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("invalid proof of possession")]
+pub struct PopError(#[cfg_attr(feature = "std", source)] Reason);
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum Reason {
+    #[displaydoc("signer index is out of bounds")]
+    SignerIndexOutOfBounds,
+    #[displaydoc("proof doesn't match public share of the signer")]
+    InvalidProof,
+}
+
+impl From<Reason> for PopError {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
@@ -47,6 +47,9 @@ macro_rules! core_key_share {
 }
 
 core_key_share! {
+    /// Absent in legacy formats, see [`crate::versioned`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<u16>,
     pub curve: CurveName<E>,
     pub i: u16,
     #[serde(with = "As::<generic_ec::serde::Compact>")]
@@ -61,6 +64,9 @@ core_key_share! {
     #[serde(with = "As::<Option<crate::utils::HexOrBin>>")]
     pub chain_code: Option<slip_10::ChainCode>,
 
+    #[serde(default, skip_serializing_if = "crate::utils::is_zero")]
+    pub epoch: u64,
+
     #[serde(with = "As::<generic_ec::serde::Compact>")]
     pub x: NonZero<SecretScalar<E>>,
 }
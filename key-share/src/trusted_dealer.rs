@@ -174,6 +174,7 @@ impl<E: Curve> TrustedDealerBuilder<E> {
                         vss_setup: vss_setup.clone(),
                         #[cfg(feature = "hd-wallets")]
                         chain_code,
+                        epoch: 0,
                     },
                     x: x_i,
                 })
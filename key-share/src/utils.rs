@@ -4,12 +4,22 @@ mod hex_or_bin;
 #[cfg(all(feature = "serde", feature = "hd-wallets"))]
 pub use hex_or_bin::HexOrBin;
 
-// `hex` dependnecy is only needed when both `serde` and `hd-wallets` features are on.
-// However, we can't express that in Cargo.toml, so whenever `serde` feature is on and
-// `hd-wallets` is off, unused dependency is introduced.
-#[cfg(all(feature = "serde", not(feature = "hd-wallets")))]
+// `hex` dependnecy is only needed when `serde` feature is on along with any of `hd-wallets`,
+// `udigest`, or `encrypted` features. However, we can't express that in Cargo.toml, so otherwise
+// unused dependency is introduced.
+#[cfg(all(
+    feature = "serde",
+    not(any(feature = "hd-wallets", feature = "udigest", feature = "encrypted"))
+))]
 use hex as _;
 
+/// Used to skip serialization of zero epoch, so key shares that have never been
+/// refreshed are serialized in the same way as before epochs were introduced
+#[cfg(feature = "serde")]
+pub fn is_zero(x: &impl core::borrow::Borrow<u64>) -> bool {
+    *x.borrow() == 0
+}
+
 #[cfg(feature = "udigest")]
 pub mod encoding {
     pub fn curve_name<B: udigest::Buffer, E: generic_ec::Curve>(
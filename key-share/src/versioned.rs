@@ -0,0 +1,534 @@
+//! Versioned serialization of key shares
+//!
+//! Key shares are kept for a long time, so their serialization format evolves while key shares
+//! serialized by older versions of the library still need to be read. Key shares serialized by
+//! the current version of the library carry an explicit `version` field. Key shares produced by
+//! older versions (v0.1.1, v0.2.1, v0.2.3) don't have it: their [format](FormatVersion) is recognized
+//! by its layout, see [`FormatVersion::detect`].
+//!
+//! When a key share of older format is deserialized, it's upgraded to the current format via a
+//! sequence of migrations, one per each format change. Key share can also be serialized in an older
+//! format (e.g. to roll back to an older version of the library) via [`DirtyCoreKeyShare::versioned`],
+//! as long as it doesn't use features that the older format doesn't support.
+//!
+//! ## Example
+//! ```rust,no_run
+//! use key_share::{versioned::FormatVersion, CoreKeyShare};
+//! use serde::{ser::Error, Serialize};
+//!
+//! fn rollback<E: generic_ec::Curve, S: serde::Serializer>(
+//!     key_share: &CoreKeyShare<E>,
+//!     serializer: S,
+//! ) -> Result<S::Ok, S::Error> {
+//!     // Serialize the key share so it can be read by v0.2.3
+//!     let key_share = key_share
+//!         .versioned(FormatVersion::V0_2_3)
+//!         .map_err(S::Error::custom)?;
+//!     key_share.serialize(serializer)
+//! }
+//!
+//! fn version_of<'de, D: serde::Deserializer<'de>>(
+//!     serialized: D,
+//! ) -> Result<FormatVersion, D::Error> {
+//!     FormatVersion::detect(serialized)
+//! }
+//! ```
+
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+
+use generic_ec::{serde::CurveName, Curve, NonZero, Point, Scalar, SecretScalar};
+use serde::de::{self, IgnoredAny};
+use serde::Deserialize;
+use serde_with::As;
+
+use crate::{serde_fix, DirtyCoreKeyShare, DirtyKeyInfo};
+
+/// Version of key share serialization format
+///
+/// Legacy formats are named after the version of the library that introduced them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+pub enum FormatVersion {
+    /// Format of key shares produced by v0.1.1
+    ///
+    /// `vss_setup` is always present, it's `null` for non-threshold keys. VSS indexes are
+    /// serialized in uncompressed form, i.e. along with the curve name.
+    V0_1_1,
+    /// Format of key shares produced by v0.2.1
+    ///
+    /// Introduces `chain_code` which, same as `vss_setup`, is always present.
+    V0_2_1,
+    /// Format of key shares produced by v0.2.3
+    ///
+    /// `vss_setup` and `chain_code` are omitted when absent, VSS indexes are serialized in
+    /// compact form.
+    V0_2_3,
+    /// First format with explicit `version` field
+    ///
+    /// Introduces `epoch` of the key share, which is omitted when it's zero.
+    V1,
+}
+
+impl FormatVersion {
+    /// Format used by the current version of the library
+    pub const CURRENT: Self = Self::V1;
+
+    /// Returns value of `version` field
+    ///
+    /// Returns `None` for legacy formats that don't have the field.
+    pub fn number(self) -> Option<u16> {
+        match self {
+            Self::V0_1_1 | Self::V0_2_1 | Self::V0_2_3 => None,
+            Self::V1 => Some(1),
+        }
+    }
+
+    /// Returns format with given value of `version` field
+    ///
+    /// Returns `None` if the version is unknown
+    pub fn from_number(number: u16) -> Option<Self> {
+        match number {
+            1 => Some(Self::V1),
+            _ => None,
+        }
+    }
+
+    /// Detects format of serialized key share
+    ///
+    /// Accepts a serialized core key share, or a key share that contains it in `core` field (like
+    /// `cggmp21::KeyShare`). Key share itself is not deserialized or validated.
+    ///
+    /// Legacy formats are told apart by how they encode absent optional fields and VSS indexes.
+    pub fn detect<'de, D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        Layout::deserialize(deserializer)?
+            .format()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Format of serialized key share
+///
+/// Deserializing it from a serialized key share [detects](FormatVersion::detect) its format
+/// without deserializing the key share itself. Useful with serde backends that only provide
+/// functions like `from_slice::<T>()`, e.g. `ciborium::from_reader::<DetectedFormat, _>(bytes)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DetectedFormat(pub FormatVersion);
+
+impl<'de> Deserialize<'de> for DetectedFormat {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        FormatVersion::detect(deserializer).map(Self)
+    }
+}
+
+impl<E: Curve> DirtyCoreKeyShare<E> {
+    /// Returns serializable key share in specified format
+    ///
+    /// Returns error if the key share can't be represented in that format. E.g. key share
+    /// that was refreshed (has non-zero epoch) can only be serialized in [`FormatVersion::V1`]
+    /// or later.
+    pub fn versioned(&self, version: FormatVersion) -> Result<Versioned<'_, E>, VersionError> {
+        if version < FormatVersion::V1 && self.key_info.epoch != 0 {
+            return Err(Reason::NotSupported {
+                field: "epoch",
+                version,
+            }
+            .into());
+        }
+        #[cfg(feature = "hd-wallets")]
+        if version < FormatVersion::V0_2_1 && self.key_info.chain_code.is_some() {
+            return Err(Reason::NotSupported {
+                field: "chain_code",
+                version,
+            }
+            .into());
+        }
+        Ok(Versioned {
+            key_share: self,
+            version,
+        })
+    }
+}
+
+/// Key share serialized in specified format
+///
+/// Obtained via [`DirtyCoreKeyShare::versioned`]
+pub struct Versioned<'a, E: Curve> {
+    key_share: &'a DirtyCoreKeyShare<E>,
+    version: FormatVersion,
+}
+
+impl<'a, E: Curve> Versioned<'a, E> {
+    /// Key share in the current format
+    pub(crate) fn current(key_share: &'a DirtyCoreKeyShare<E>) -> Self {
+        Self {
+            key_share,
+            version: FormatVersion::CURRENT,
+        }
+    }
+
+    /// Format version the key share is serialized in
+    pub fn version(&self) -> FormatVersion {
+        self.version
+    }
+}
+
+impl<'a, E: Curve> serde::Serialize for Versioned<'a, E> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // See [`crate::serde_fix`] module docs
+        let DirtyCoreKeyShare {
+            i,
+            key_info:
+                DirtyKeyInfo {
+                    curve,
+                    shared_public_key,
+                    public_shares,
+                    vss_setup,
+                    #[cfg(feature = "hd-wallets")]
+                    chain_code,
+                    epoch,
+                },
+            x,
+        } = self.key_share;
+
+        let legacy_vss_setup = vss_setup.as_ref().map(|vss_setup| legacy::VssSetup {
+            min_signers: &vss_setup.min_signers,
+            I: &vss_setup.I,
+        });
+
+        match self.version {
+            FormatVersion::V0_1_1 => legacy::V0_1_1 {
+                curve,
+                i,
+                shared_public_key,
+                public_shares,
+                vss_setup: legacy_vss_setup,
+                x,
+            }
+            .serialize(serializer),
+            FormatVersion::V0_2_1 => legacy::V0_2_1 {
+                curve,
+                i,
+                shared_public_key,
+                public_shares,
+                vss_setup: legacy_vss_setup,
+                #[cfg(feature = "hd-wallets")]
+                chain_code,
+                #[cfg(not(feature = "hd-wallets"))]
+                chain_code: None,
+                x,
+            }
+            .serialize(serializer),
+            FormatVersion::V0_2_3 | FormatVersion::V1 => serde_fix::ser::CoreKeyShare {
+                version: &self.version.number(),
+                curve,
+                i,
+                shared_public_key,
+                public_shares,
+                vss_setup,
+                #[cfg(feature = "hd-wallets")]
+                chain_code,
+                epoch,
+                x,
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+/// Upgrades deserialized key share to the current format
+///
+/// Every change of the format must come with a migration step from the previous format.
+pub(crate) fn migrate<E: Curve>(
+    mut key_share: serde_fix::de::CoreKeyShare<E>,
+) -> Result<serde_fix::de::CoreKeyShare<E>, VersionError> {
+    let mut version = match key_share.version {
+        Some(number) => {
+            FormatVersion::from_number(number).ok_or(Reason::UnsupportedVersion(number))?
+        }
+        // Legacy formats only differ in how absent optional fields and VSS indexes are encoded, so
+        // they're indistinguishable after deserialization. Migration starts from the oldest of them, steps
+        // between legacy formats are no-op for key shares of newer legacy formats.
+        None => FormatVersion::V0_1_1,
+    };
+    loop {
+        (version, key_share) = match version {
+            // v0.2.1 introduced chain code. v0.1.1 key shares don't have it, and it defaults to `None`.
+            FormatVersion::V0_1_1 => (FormatVersion::V0_2_1, key_share),
+            // v0.2.3 started omitting absent `vss_setup` and `chain_code`, they default to `None`.
+            // VSS indexes are accepted in both uncompressed and compact forms.
+            FormatVersion::V0_2_1 => (FormatVersion::V0_2_3, key_share),
+            // v1 introduced `version` field and epoch. Legacy key shares were never refreshed, so
+            // epoch defaults to zero.
+            FormatVersion::V0_2_3 => (
+                FormatVersion::V1,
+                serde_fix::de::CoreKeyShare {
+                    version: FormatVersion::V1.number(),
+                    ..key_share
+                },
+            ),
+            FormatVersion::V1 => break,
+        };
+    }
+    debug_assert_eq!(version, FormatVersion::CURRENT);
+    Ok(key_share)
+}
+
+/// Serialization layouts of legacy formats
+mod legacy {
+    use super::*;
+
+    #[derive(serde::Serialize)]
+    #[serde(bound = "")]
+    pub struct V0_1_1<'a, E: Curve> {
+        pub curve: &'a CurveName<E>,
+        pub i: &'a u16,
+        #[serde(with = "As::<generic_ec::serde::Compact>")]
+        pub shared_public_key: &'a NonZero<Point<E>>,
+        #[serde(with = "As::<Vec<generic_ec::serde::Compact>>")]
+        pub public_shares: &'a Vec<NonZero<Point<E>>>,
+        pub vss_setup: Option<VssSetup<'a, E>>,
+        #[serde(with = "As::<generic_ec::serde::Compact>")]
+        pub x: &'a NonZero<SecretScalar<E>>,
+    }
+
+    #[derive(serde::Serialize)]
+    #[serde(bound = "")]
+    pub struct V0_2_1<'a, E: Curve> {
+        pub curve: &'a CurveName<E>,
+        pub i: &'a u16,
+        #[serde(with = "As::<generic_ec::serde::Compact>")]
+        pub shared_public_key: &'a NonZero<Point<E>>,
+        #[serde(with = "As::<Vec<generic_ec::serde::Compact>>")]
+        pub public_shares: &'a Vec<NonZero<Point<E>>>,
+        pub vss_setup: Option<VssSetup<'a, E>>,
+        #[cfg(feature = "hd-wallets")]
+        #[serde(with = "As::<Option<crate::utils::HexOrBin>>")]
+        pub chain_code: &'a Option<slip_10::ChainCode>,
+        /// Chain code is always absent when HD wallets support is disabled
+        #[cfg(not(feature = "hd-wallets"))]
+        pub chain_code: Option<()>,
+        #[serde(with = "As::<generic_ec::serde::Compact>")]
+        pub x: &'a NonZero<SecretScalar<E>>,
+    }
+
+    /// VSS setup with indexes serialized in uncompressed form
+    #[derive(serde::Serialize)]
+    #[serde(bound = "")]
+    pub struct VssSetup<'a, E: Curve> {
+        pub min_signers: &'a u16,
+        pub I: &'a Vec<NonZero<Scalar<E>>>,
+    }
+}
+
+/// Fields of serialized key share that determine its format
+#[derive(Default)]
+struct Layout {
+    version: Option<u16>,
+    /// `None` if the field is absent
+    vss_setup: Option<Field>,
+    /// `None` if the field is absent
+    chain_code: Option<Field>,
+}
+
+/// Encoding of a present optional field
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Null,
+    /// VSS setup with indexes in uncompressed form
+    LegacyVssSetup,
+    Value,
+}
+
+impl Layout {
+    fn format(&self) -> Result<FormatVersion, VersionError> {
+        if let Some(number) = self.version {
+            return FormatVersion::from_number(number)
+                .ok_or_else(|| Reason::UnsupportedVersion(number).into());
+        }
+        Ok(match (self.vss_setup, self.chain_code) {
+            // Only v0.2.1 writes chain code, and it always does
+            (Some(Field::Null | Field::LegacyVssSetup), Some(_)) | (_, Some(Field::Null)) => {
+                FormatVersion::V0_2_1
+            }
+            (Some(Field::Null | Field::LegacyVssSetup), None) => FormatVersion::V0_1_1,
+            _ => FormatVersion::V0_2_3,
+        })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Layout {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(LayoutVisitor)
+    }
+}
+
+struct LayoutVisitor;
+
+impl<'de> de::Visitor<'de> for LayoutVisitor {
+    type Value = Layout;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("serialized key share")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Layout, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut layout = Layout::default();
+        let mut core = None;
+        while let Some(field) = map.next_key::<String>()? {
+            match field.as_str() {
+                "version" => layout.version = Some(map.next_value()?),
+                "vss_setup" => {
+                    layout.vss_setup = Some(match map.next_value::<Option<VssSetupLayout>>()? {
+                        None => Field::Null,
+                        Some(VssSetupLayout { compact: false }) => Field::LegacyVssSetup,
+                        Some(VssSetupLayout { compact: true }) => Field::Value,
+                    })
+                }
+                "chain_code" => {
+                    layout.chain_code = Some(match map.next_value::<Option<IgnoredAny>>()? {
+                        None => Field::Null,
+                        Some(_) => Field::Value,
+                    })
+                }
+                "core" => core = Some(map.next_value::<Layout>()?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(core.unwrap_or(layout))
+    }
+}
+
+/// Serialized VSS setup
+struct VssSetupLayout {
+    /// Whether VSS indexes are in compact form
+    compact: bool,
+}
+
+impl<'de> serde::Deserialize<'de> for VssSetupLayout {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(VssSetupLayoutVisitor)
+    }
+}
+
+struct VssSetupLayoutVisitor;
+
+impl<'de> de::Visitor<'de> for VssSetupLayoutVisitor {
+    type Value = VssSetupLayout;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("vss setup")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<VssSetupLayout, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut compact = true;
+        while let Some(field) = map.next_key::<String>()? {
+            if field == "I" {
+                compact = map
+                    .next_value::<Vec<IndexLayout>>()?
+                    .iter()
+                    .all(|index| index.compact);
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(VssSetupLayout { compact })
+    }
+}
+
+/// Serialized VSS index
+struct IndexLayout {
+    /// Uncompressed index is a struct, compact one is a string or bytes
+    compact: bool,
+}
+
+impl<'de> serde::Deserialize<'de> for IndexLayout {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        deserializer.deserialize_any(IndexLayoutVisitor)
+    }
+}
+
+struct IndexLayoutVisitor;
+
+impl<'de> de::Visitor<'de> for IndexLayoutVisitor {
+    type Value = IndexLayout;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("vss index")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<IndexLayout, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        while map.next_entry::<IgnoredAny, IgnoredAny>()?.is_some() {}
+        Ok(IndexLayout { compact: false })
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<IndexLayout, A::Error>
+    where
+        A: de::SeqAccess<'de>,
+    {
+        while seq.next_element::<IgnoredAny>()?.is_some() {}
+        Ok(IndexLayout { compact: true })
+    }
+
+    fn visit_str<Err: de::Error>(self, _v: &str) -> Result<IndexLayout, Err> {
+        Ok(IndexLayout { compact: true })
+    }
+
+    fn visit_bytes<Err: de::Error>(self, _v: &[u8]) -> Result<IndexLayout, Err> {
+        Ok(IndexLayout { compact: true })
+    }
+}
+
+/// Error related to key share format version
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+#[displaydoc("{0}")]
+pub struct VersionError(Reason);
+
+#[derive(Debug, displaydoc::Display)]
+#[cfg_attr(feature = "std", derive(thiserror::Error))]
+enum Reason {
+    #[displaydoc("unsupported format version {0}: key share was serialized by a newer version of the library")]
+    UnsupportedVersion(u16),
+    #[displaydoc("`{field}` is not supported by format {version:?}")]
+    NotSupported {
+        field: &'static str,
+        version: FormatVersion,
+    },
+}
+
+impl From<Reason> for VersionError {
+    fn from(err: Reason) -> Self {
+        Self(err)
+    }
+}
@@ -249,9 +249,9 @@ async fn do_becnhmarks<L: SecurityLevel>(args: Args) {
                 .zip(aux_data.expect("aux data is not generated"))
                 .map(|(key_share, aux_data)| {
                     cggmp21::key_share::KeyShare::from_parts((key_share, aux_data))
+                        .expect("couldn't complete a share")
                 })
-                .collect::<Result<Vec<_>, _>>()
-                .expect("couldn't complete a share");
+                .collect::<Vec<_>>();
 
             let eid: [u8; 32] = rng.gen();
             let eid = ExecutionId::new(&eid);
@@ -1,7 +1,7 @@
 use anyhow::{bail, Context, Result};
 use cggmp21::supported_curves::{Secp256k1, Secp256r1, Stark};
 use cggmp21::{
-    security_level::{KeygenSecurityLevel, SecurityLevel128},
+    security_level::{KeygenSecurityLevel, SecurityLevel128, SecurityLevel192, SecurityLevel256},
     trusted_dealer,
 };
 use cggmp21_tests::{generate_blum_prime, PrecomputedKeyShares, PregeneratedPrimes};
@@ -12,7 +12,12 @@ fn main() -> Result<()> {
     match args() {
         Operation::GenShares => precompute_shares(),
         Operation::GenOldShares { out_dir } => generate_old_share(&out_dir),
-        Operation::GenPrimes => precompute_primes(),
+        Operation::GenPrimes { security_bits } => match security_bits {
+            128 => precompute_primes::<SecurityLevel128>(10),
+            192 => precompute_primes::<SecurityLevel192>(3),
+            256 => precompute_primes::<SecurityLevel256>(3),
+            _ => bail!("unsupported security level: {security_bits}"),
+        },
     }
 }
 
@@ -21,14 +26,20 @@ fn main() -> Result<()> {
 enum Operation {
     GenShares,
     GenOldShares { out_dir: std::path::PathBuf },
-    GenPrimes,
+    GenPrimes { security_bits: u32 },
 }
 
 fn args() -> Operation {
     use bpaf::Parser;
     let shares = bpaf::command("shares", bpaf::pure(Operation::GenShares).to_options())
         .help("Pregenerate key shares");
-    let primes = bpaf::command("primes", bpaf::pure(Operation::GenPrimes).to_options())
+    let security_bits = bpaf::long("security-level")
+        .help("security level of the primes: 128, 192, or 256")
+        .argument("BITS")
+        .fallback(128);
+    let primes = bpaf::construct!(Operation::GenPrimes { security_bits })
+        .to_options()
+        .command("primes")
         .help("Pregenerate primes for key refresh");
 
     let out_dir = bpaf::long("out-dir")
@@ -58,9 +69,9 @@ fn precompute_shares() -> Result<()> {
     Ok(())
 }
 
-fn precompute_primes() -> Result<()> {
+fn precompute_primes<L: cggmp21::security_level::SecurityLevel>(amount: usize) -> Result<()> {
     let mut rng = OsRng;
-    let json = PregeneratedPrimes::generate::<_, SecurityLevel128>(10, &mut rng).to_serialized()?;
+    let json = PregeneratedPrimes::generate::<_, L>(amount, &mut rng).to_serialized()?;
     println!("{json}");
     Ok(())
 }
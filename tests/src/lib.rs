@@ -15,6 +15,14 @@ lazy_static::lazy_static! {
         PregeneratedPrimes::from_serialized(
             include_str!("../../test-data/pregenerated_primes.json")
         ).unwrap();
+    pub static ref CACHED_PRIMES_192: PregeneratedPrimes =
+        PregeneratedPrimes::from_serialized(
+            include_str!("../../test-data/pregenerated_primes_192.json")
+        ).unwrap();
+    pub static ref CACHED_PRIMES_256: PregeneratedPrimes =
+        PregeneratedPrimes::from_serialized(
+            include_str!("../../test-data/pregenerated_primes_256.json")
+        ).unwrap();
 }
 
 pub struct PrecomputedKeyShares {
@@ -124,6 +132,19 @@ impl PregeneratedPrimes {
 
         Self { primes, bitsize }
     }
+
+    /// Returns pregenerated primes for security level `L`
+    ///
+    /// Panics if there are no pregenerated primes for `L`
+    pub fn cached<L>() -> &'static Self
+    where
+        L: cggmp21::security_level::SecurityLevel,
+    {
+        [&*CACHED_PRIMES, &*CACHED_PRIMES_192, &*CACHED_PRIMES_256]
+            .into_iter()
+            .find(|primes| primes.bitsize == 4 * L::SECURITY_BITS)
+            .expect("no pregenerated primes for the security level")
+    }
 }
 
 /// Generates a blum prime
@@ -173,3 +194,15 @@ pub fn random_derivation_path(rng: &mut impl rand::RngCore) -> Vec<u32> {
         .take(len)
         .collect::<Vec<_>>()
 }
+
+/// Returns `Display` of the error followed by `Display` of all its sources
+pub fn error_chain(err: &dyn std::error::Error) -> String {
+    let mut chain = err.to_string();
+    let mut source = err.source();
+    while let Some(err) = source {
+        chain += ": ";
+        chain += &err.to_string();
+        source = err.source();
+    }
+    chain
+}
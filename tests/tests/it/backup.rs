@@ -0,0 +1,107 @@
+#[generic_tests::define(attrs(test, test_case::case))]
+mod generic {
+    use generic_ec::{Curve, NonZero, Point, Scalar, SecretScalar};
+    use rand::seq::SliceRandom;
+    use rand_dev::DevRng;
+
+    use cggmp21::key_share::backup::{self, BackupPiece, DirtyBackupPiece};
+    use cggmp21::key_share::{IncompleteKeyShare, Validate};
+    use cggmp21::security_level::SecurityLevel128;
+
+    #[test_case::case(None, 3, 2, 3; "n3-k2-m3")]
+    #[test_case::case(Some(2), 3, 1, 1; "t2n3-k1-m1")]
+    #[test_case::case(Some(3), 5, 3, 5; "t3n5-k3-m5")]
+    fn backup_and_restore<E: Curve>(t: Option<u16>, n: u16, k: u16, m: u16) {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, false)
+            .expect("retrieve cached shares");
+
+        for share in &shares {
+            let core: &IncompleteKeyShare<E> = share.as_ref();
+            let mut pieces = backup::backup(share, k, m, &mut rng).unwrap();
+            assert_eq!(pieces.len(), usize::from(m));
+            for piece in &pieces {
+                piece.verify(&share.core.key_info).unwrap();
+                assert_eq!(piece.commitment.min_custodians(), usize::from(k));
+            }
+
+            // Pieces survive serialization
+            let serialized = serde_json::to_vec(&pieces).unwrap();
+            let deserialized: Vec<BackupPiece<E>> = serde_json::from_slice(&serialized).unwrap();
+            assert_eq!(serde_json::to_vec(&deserialized).unwrap(), serialized);
+
+            // Any `k` pieces restore the key share
+            pieces.shuffle(&mut rng);
+            let restored = backup::restore(core.as_ref(), &pieces[..usize::from(k)]).unwrap();
+            assert_eq!(restored.i, share.core.i);
+            assert_eq!(
+                serde_json::to_vec(&restored).unwrap(),
+                serde_json::to_vec(&share.core).unwrap()
+            );
+
+            if k > 1 {
+                // Less than `k` pieces are not enough, even if some of them are duplicated
+                let mut not_enough = pieces[..usize::from(k) - 1].to_vec();
+                not_enough.push(pieces[0].clone());
+                assert!(backup::restore(core.as_ref(), &not_enough).is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn backup_of_other_key_is_rejected<E: Curve>() {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(2), 3, false)
+            .expect("retrieve cached shares");
+        let other_shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(3), 3, false)
+            .expect("retrieve cached shares");
+
+        let core: &IncompleteKeyShare<E> = shares[0].as_ref();
+        let other_core: &IncompleteKeyShare<E> = other_shares[0].as_ref();
+
+        let pieces = backup::backup(&shares[0], 2, 3, &mut rng).unwrap();
+        // Piece doesn't belong to other signer of the same key, nor to another key
+        let mut other_signer = pieces[0].commitment.clone();
+        other_signer.i = 1;
+        assert!(other_signer.verify(&shares[0].core.key_info).is_err());
+        assert!(pieces[0].verify(&other_shares[0].core.key_info).is_err());
+        assert!(backup::restore(other_core.as_ref(), &pieces).is_err());
+
+        // Pieces of different backups of the same key share can't be mixed
+        let other_pieces = backup::backup(&shares[0], 2, 3, &mut rng).unwrap();
+        let mixed = [pieces[0].clone(), other_pieces[1].clone()];
+        assert!(backup::restore(core.as_ref(), &mixed).is_err());
+    }
+
+    #[test]
+    fn tampered_piece_is_invalid<E: Curve>() {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(2), 3, false)
+            .expect("retrieve cached shares");
+        let pieces = backup::backup(&shares[0], 2, 3, &mut rng).unwrap();
+
+        let mut piece: DirtyBackupPiece<E> = pieces[0].clone().into_inner();
+        piece.y = SecretScalar::new(&mut (piece.y.as_ref() + Scalar::one()));
+        assert!(piece.is_valid().is_err());
+
+        let mut piece: DirtyBackupPiece<E> = pieces[0].clone().into_inner();
+        piece.commitment.coefs[1] =
+            NonZero::from_point(*piece.commitment.coefs[1] + Point::generator()).unwrap();
+        assert!(piece.is_valid().is_err());
+
+        let mut piece: DirtyBackupPiece<E> = pieces[0].clone().into_inner();
+        piece.j = 3;
+        assert!(piece.is_valid().is_err());
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
@@ -0,0 +1,200 @@
+#[generic_tests::define(attrs(test, test_case::case, cfg_attr))]
+mod generic {
+    use generic_ec::Curve;
+
+    use cggmp21::key_share::{compact, DirtyKeyShare, Validate};
+    use cggmp21::security_level::SecurityLevel128;
+    use cggmp21::KeyShare;
+
+    fn assert_same_share<E: Curve>(
+        decoded: &KeyShare<E, SecurityLevel128>,
+        original: &KeyShare<E, SecurityLevel128>,
+    ) {
+        // Compact encoding doesn't preserve precomputed tables, so we compare serde
+        // encodings of the shares without them
+        let strip = |share: &KeyShare<E, SecurityLevel128>| {
+            let mut share: DirtyKeyShare<E, SecurityLevel128> = share.clone().into_inner();
+            share.aux.parties.iter_mut().for_each(|aux_j| {
+                aux_j.multiexp = None;
+                aux_j.crt = None;
+            });
+            serde_json::to_string(&share).unwrap()
+        };
+        assert_eq!(strip(decoded), strip(original));
+    }
+
+    #[test_case::case(None, 3, false; "n3")]
+    #[test_case::case(Some(2), 3, false; "t2n3")]
+    #[cfg_attr(feature = "hd-wallets", test_case::case(Some(3), 5, true; "t3n5-hd"))]
+    fn key_share_roundtrip<E: Curve>(t: Option<u16>, n: u16, hd_wallet: bool) {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, hd_wallet)
+            .expect("retrieve cached shares");
+
+        for share in &shares {
+            let encoded = compact::encode_key_share(share);
+            let decoded =
+                compact::decode_key_share::<E, SecurityLevel128>(&encoded, false).unwrap();
+            assert_same_share(&decoded, share);
+
+            // Compact encoding is smaller than serde encoding
+            let cbor = {
+                let mut bytes = vec![];
+                ciborium::into_writer(share, &mut bytes).unwrap();
+                bytes
+            };
+            assert!(encoded.len() < cbor.len());
+
+            let encoded_aux = compact::encode_aux_info(&share.aux);
+            let decoded_aux =
+                compact::decode_aux_info::<SecurityLevel128>(&encoded_aux, false).unwrap();
+            assert_eq!(compact::encode_aux_info(&decoded_aux), encoded_aux);
+        }
+    }
+
+    #[test_case::case(None, 3; "n3")]
+    #[test_case::case(Some(2), 3; "t2n3")]
+    fn detached_public_aux<E: Curve>(t: Option<u16>, n: u16) {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, false)
+            .expect("retrieve cached shares");
+
+        let encoded = shares
+            .iter()
+            .map(|share| compact::encode_key_share_detached(share))
+            .collect::<Vec<_>>();
+
+        // All signers share the same public aux
+        let public_aux = &encoded[0].1;
+        for (encoded_share, public_aux_i) in &encoded {
+            assert_eq!(public_aux_i.fingerprint(), public_aux.fingerprint());
+            assert_eq!(public_aux_i.encode(), public_aux.encode());
+            assert_eq!(
+                compact::detached_fingerprint(encoded_share).unwrap(),
+                public_aux.fingerprint()
+            );
+            assert!(encoded_share.len() < compact::encode_key_share(&shares[0]).len());
+        }
+
+        // Public aux survives roundtrip
+        let public_aux =
+            compact::PublicAux::<SecurityLevel128>::decode(&public_aux.encode()).unwrap();
+        assert_eq!(public_aux.fingerprint(), encoded[0].1.fingerprint());
+
+        for ((encoded_share, _), share) in encoded.iter().zip(&shares) {
+            let decoded =
+                compact::decode_key_share_detached::<E, _>(encoded_share, &public_aux, false)
+                    .unwrap();
+            assert_same_share(&decoded, share);
+        }
+
+        // Detached aux info
+        let (encoded_aux, public_aux_of_aux) = compact::encode_aux_info_detached(&shares[0].aux);
+        assert_eq!(public_aux_of_aux.fingerprint(), public_aux.fingerprint());
+        let decoded_aux =
+            compact::decode_aux_info_detached(&encoded_aux, &public_aux, false).unwrap();
+        assert_eq!(decoded_aux.p, shares[0].aux.p);
+        assert_eq!(decoded_aux.q, shares[0].aux.q);
+    }
+
+    #[test]
+    fn precomputed_tables_are_not_encoded<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 2, false)
+            .expect("retrieve cached shares");
+        let share = shares[0].clone();
+        let encoded = compact::encode_key_share(&share);
+
+        let mut share_with_tables = share.into_inner();
+        share_with_tables.precompute_crt().unwrap();
+        share_with_tables.aux.precompute_multiexp_tables().unwrap();
+        let share_with_tables = share_with_tables.validate().unwrap();
+        assert_eq!(compact::encode_key_share(&share_with_tables), encoded);
+
+        let decoded = compact::decode_key_share::<E, SecurityLevel128>(&encoded, false).unwrap();
+        assert!(decoded
+            .aux
+            .parties
+            .iter()
+            .all(|aux_j| aux_j.multiexp.is_none() && aux_j.crt.is_none()));
+
+        // Tables are recomputed if requested
+        let decoded = compact::decode_key_share::<E, SecurityLevel128>(&encoded, true).unwrap();
+        assert!(decoded
+            .aux
+            .parties
+            .iter()
+            .all(|aux_j| aux_j.multiexp.is_some()));
+        assert!(decoded.aux.parties[usize::from(decoded.core.i)]
+            .crt
+            .is_some());
+        assert_eq!(compact::encode_key_share(&decoded), encoded);
+
+        let decoded_aux = compact::decode_aux_info::<SecurityLevel128>(
+            &compact::encode_aux_info(&decoded.aux),
+            true,
+        )
+        .unwrap();
+        assert!(decoded_aux
+            .parties
+            .iter()
+            .all(|aux_j| aux_j.multiexp.is_some()));
+    }
+
+    #[test]
+    fn invalid_encodings_are_rejected<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let encoded = compact::encode_key_share(&shares[0]);
+
+        // Truncated input
+        assert!(compact::decode_key_share::<E, SecurityLevel128>(
+            &encoded[..encoded.len() - 1],
+            false
+        )
+        .is_err());
+        // Trailing bytes
+        let mut extended = encoded.clone();
+        extended.push(0);
+        assert!(compact::decode_key_share::<E, SecurityLevel128>(&extended, false).is_err());
+        // Unknown version
+        let mut wrong_version = encoded.clone();
+        wrong_version[0] = compact::VERSION + 1;
+        assert!(compact::decode_key_share::<E, SecurityLevel128>(&wrong_version, false).is_err());
+        // Wrong kind of value
+        assert!(compact::decode_aux_info::<SecurityLevel128>(&encoded, false).is_err());
+        // Not detached
+        assert!(compact::detached_fingerprint(&encoded).is_err());
+
+        // Detached share can't be decoded with public aux of another key
+        let other_shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 2, false)
+            .expect("retrieve cached shares");
+        let (encoded, _) = compact::encode_key_share_detached(&shares[0]);
+        let (_, other_public_aux) = compact::encode_key_share_detached(&other_shares[0]);
+        assert!(
+            compact::decode_key_share_detached::<E, _>(&encoded, &other_public_aux, false).is_err()
+        );
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
+
+#[test]
+fn key_share_of_wrong_curve_is_rejected() {
+    use cggmp21::key_share::compact;
+    use cggmp21::security_level::SecurityLevel128;
+    use cggmp21::supported_curves::{Secp256k1, Secp256r1};
+
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 2, false)
+        .expect("retrieve cached shares");
+    let encoded = compact::encode_key_share(&shares[0]);
+    assert!(compact::decode_key_share::<Secp256r1, SecurityLevel128>(&encoded, false).is_err());
+}
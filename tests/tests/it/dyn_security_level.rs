@@ -0,0 +1,78 @@
+#[generic_tests::define]
+mod generic {
+    use generic_ec::Curve;
+
+    use cggmp21::key_share::{AnyKeyShare, DynKeyShare, KeyShare, KeyShareVisitor};
+    use cggmp21::security_level::{
+        KeygenSecurityLevel, SecurityLevel, SecurityLevel128, SecurityLevel192,
+        SecurityLevelDescriptor,
+    };
+
+    struct SecurityBits;
+    impl<E: Curve> KeyShareVisitor<E> for SecurityBits {
+        type Output = u32;
+        fn visit<L: SecurityLevel>(self, _key_share: &KeyShare<E, L>) -> u32 {
+            L::SECURITY_BITS
+        }
+    }
+
+    #[test]
+    fn security_level_is_determined_on_load<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let serialized = serde_json::to_vec(&shares[0]).unwrap();
+
+        let share: DynKeyShare<E> = serde_json::from_slice(&serialized).unwrap();
+        assert_eq!(
+            share.security_level(),
+            SecurityLevelDescriptor::of::<SecurityLevel128>()
+        );
+        assert_eq!(share.visit(SecurityBits), SecurityLevel128::SECURITY_BITS);
+        assert_eq!(share.shared_public_key(), shares[0].shared_public_key());
+
+        // Dynamic key share is serialized the same way as regular key share
+        assert_eq!(serde_json::to_vec(&share).unwrap(), serialized);
+
+        let share = share.downcast::<SecurityLevel192>().err().unwrap();
+        let share = share.downcast::<SecurityLevel128>().ok().unwrap();
+        assert_eq!(share.core.i, shares[0].core.i);
+    }
+
+    #[test]
+    fn security_level_mismatch_is_detected_on_load<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let serialized = serde_json::to_vec(&shares[0]).unwrap();
+
+        assert!(serde_json::from_slice::<KeyShare<E, SecurityLevel128>>(&serialized).is_ok());
+        assert!(serde_json::from_slice::<KeyShare<E, SecurityLevel192>>(&serialized).is_err());
+    }
+
+    #[test]
+    fn dyn_key_share_requires_descriptor<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let mut serialized = serde_json::to_value(&shares[0]).unwrap();
+        serialized["aux"]
+            .as_object_mut()
+            .unwrap()
+            .remove("security_level")
+            .unwrap();
+
+        // Legacy key shares without descriptor can be loaded only with known security level
+        assert!(
+            serde_json::from_value::<KeyShare<E, SecurityLevel128>>(serialized.clone()).is_ok()
+        );
+        assert!(serde_json::from_value::<DynKeyShare<E>>(serialized).is_err());
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
@@ -0,0 +1,412 @@
+#[generic_tests::define(attrs(tokio::test, test_case::case, cfg_attr))]
+mod generic {
+    use futures::SinkExt;
+    use generic_ec::{coords::HasAffineX, Curve, NonZero, Point, Scalar, SecretScalar};
+    use rand::{seq::SliceRandom, CryptoRng, Rng, RngCore, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+    use rand_dev::DevRng;
+    use round_based::{simulation::Simulation, Delivery, MpcParty, Outgoing};
+    use serde::Serialize;
+    use sha2::{Digest, Sha256};
+
+    use cggmp21::echo_broadcast::{Authenticator, EchoBroadcast, EchoMsg};
+    use cggmp21::key_share::AnyKeyShare;
+    use cggmp21::keygen::{msg::threshold::MsgRound1, ThresholdMsg};
+    use cggmp21::signing::{msg::Msg as SigningMsg, DataToSign};
+    use cggmp21::{security_level::SecurityLevel128, ExecutionId};
+
+    fn hash_msg<M: Serialize>(msg: &M) -> sha2::digest::Output<Sha256> {
+        Sha256::digest(serde_json::to_vec(msg).unwrap())
+    }
+
+    /// Schnorr signatures with deterministic nonces
+    struct Schnorr<E: Curve> {
+        secret_key: SecretScalar<E>,
+        public_keys: Vec<Point<E>>,
+    }
+
+    impl<E: Curve> Schnorr<E> {
+        /// Generates keys of `n` parties, returns authenticator for each of them
+        fn generate(rng: &mut (impl RngCore + CryptoRng), n: u16) -> Vec<Self> {
+            let secret_keys = (0..n)
+                .map(|_| NonZero::<SecretScalar<E>>::random(rng).into_inner())
+                .collect::<Vec<_>>();
+            let public_keys = secret_keys
+                .iter()
+                .map(|sk| Point::generator() * sk)
+                .collect::<Vec<_>>();
+            secret_keys
+                .into_iter()
+                .map(|secret_key| Self {
+                    secret_key,
+                    public_keys: public_keys.clone(),
+                })
+                .collect()
+        }
+
+        fn challenge(nonce: &Point<E>, public_key: &Point<E>, data: &[u8]) -> Scalar<E> {
+            let hash = Sha256::new()
+                .chain_update(nonce.to_bytes(true))
+                .chain_update(public_key.to_bytes(true))
+                .chain_update(data)
+                .finalize();
+            Scalar::from_be_bytes_mod_order(hash)
+        }
+    }
+
+    impl<E: Curve> Authenticator for Schnorr<E> {
+        type Signature = (Point<E>, Scalar<E>);
+
+        fn sign(&self, data: &[u8]) -> Self::Signature {
+            let k = Scalar::from_be_bytes_mod_order(
+                Sha256::new()
+                    .chain_update(self.secret_key.as_ref().to_be_bytes())
+                    .chain_update(data)
+                    .finalize(),
+            );
+            let nonce = Point::generator() * k;
+            let public_key = Point::generator() * &self.secret_key;
+            let e = Self::challenge(&nonce, &public_key, data);
+            (nonce, k + e * &self.secret_key)
+        }
+
+        fn verify(&self, signer: u16, data: &[u8], (nonce, s): &Self::Signature) -> bool {
+            let Some(public_key) = self.public_keys.get(usize::from(signer)) else {
+                return false;
+            };
+            let e = Self::challenge(nonce, public_key, data);
+            Point::generator() * s == nonce + public_key * e
+        }
+    }
+
+    #[test_case::case(2, 3; "t2n3")]
+    #[test_case::case(3, 5; "t3n5")]
+    #[tokio::test]
+    async fn keygen_over_echo_broadcast<E: Curve>(t: u16, n: u16) {
+        let mut rng = DevRng::new();
+
+        let mut simulation =
+            Simulation::<EchoMsg<ThresholdMsg<E, SecurityLevel128, Sha256>, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for i in 0..n {
+            let party = simulation.add_party();
+            let party = MpcParty::connected(EchoBroadcast::new(i, n, party.delivery, hash_msg));
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                cggmp21::keygen(eid, i, n)
+                    .set_threshold(t)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+
+        for (i, key_share) in (0u16..).zip(&key_shares) {
+            assert_eq!(key_share.i, i);
+            assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+            assert_eq!(key_share.public_shares, key_shares[0].public_shares);
+            assert_eq!(
+                Point::<E>::generator() * &key_share.x,
+                key_share.public_shares[usize::from(i)]
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn equivocation_is_detected<E: Curve>() {
+        let mut rng = DevRng::new();
+        let n = 3;
+
+        let mut simulation =
+            Simulation::<EchoMsg<ThresholdMsg<E, SecurityLevel128, Sha256>, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let malicious_party = simulation.add_party();
+
+        let mut outputs = vec![];
+        for i in 1..n {
+            let party = simulation.add_party();
+            let party = MpcParty::connected(EchoBroadcast::new(i, n, party.delivery, hash_msg));
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(2)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        // Party 0 sends different commitments to party 1 and 2
+        let (_incomings, mut outgoings) = malicious_party.delivery.split();
+        for j in 1..n {
+            let mut commitment = sha2::digest::Output::<Sha256>::default();
+            rng.fill_bytes(&mut commitment);
+            outgoings
+                .send(Outgoing::p2p(
+                    j,
+                    EchoMsg::Send {
+                        seq: 0,
+                        msg: ThresholdMsg::Round1(MsgRound1 { commitment }),
+                        signature: (),
+                    },
+                ))
+                .await
+                .unwrap();
+        }
+
+        for result in futures::future::join_all(outputs).await {
+            let Err(err) = result else {
+                panic!("equivocation wasn't detected")
+            };
+            let err = format!("{err:?}");
+            assert!(err.contains("Equivocation { sender: 0"), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn message_out_of_window_is_rejected<E: Curve>() {
+        let mut rng = DevRng::new();
+        let n = 3;
+
+        let mut simulation =
+            Simulation::<EchoMsg<ThresholdMsg<E, SecurityLevel128, Sha256>, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let malicious_party = simulation.add_party();
+
+        let mut outputs = vec![];
+        for i in 1..n {
+            let party = simulation.add_party();
+            let party = MpcParty::connected(EchoBroadcast::new(i, n, party.delivery, hash_msg));
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(2)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        // Party 0 sends a message with sequence number far ahead, which would otherwise
+        // be stored until it's echoed
+        let (_incomings, mut outgoings) = malicious_party.delivery.split();
+        let mut commitment = sha2::digest::Output::<Sha256>::default();
+        rng.fill_bytes(&mut commitment);
+        outgoings
+            .send(Outgoing::broadcast(EchoMsg::Send {
+                seq: 1_000_000,
+                msg: ThresholdMsg::Round1(MsgRound1 { commitment }),
+                signature: (),
+            }))
+            .await
+            .unwrap();
+
+        for result in futures::future::join_all(outputs).await {
+            let Err(err) = result else {
+                panic!("message out of window wasn't rejected")
+            };
+            let err = format!("{err:?}");
+            assert!(err.contains("UnexpectedMessage { sender: 0 }"), "{err}");
+        }
+    }
+
+    #[tokio::test]
+    async fn keygen_over_signed_echo_broadcast<E: Curve>() {
+        let mut rng = DevRng::new();
+        let n = 3;
+
+        let mut simulation = Simulation::<
+            EchoMsg<ThresholdMsg<E, SecurityLevel128, Sha256>, Sha256, (Point<E>, Scalar<E>)>,
+        >::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for (i, auth) in (0..).zip(Schnorr::<E>::generate(&mut rng, n)) {
+            let party = simulation.add_party();
+            let party = MpcParty::connected(EchoBroadcast::with_signatures(
+                eid,
+                i,
+                n,
+                party.delivery,
+                hash_msg,
+                auth,
+            ));
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                cggmp21::keygen(eid, i, n)
+                    .set_threshold(2)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        let key_shares = futures::future::try_join_all(outputs)
+            .await
+            .expect("keygen failed");
+        for key_share in &key_shares {
+            assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+        }
+    }
+
+    #[test_case::case(true; "equivocation")]
+    #[test_case::case(false; "invalid_signature")]
+    #[tokio::test]
+    async fn signed_echoes_identify_misbehaving_party<E: Curve>(sign_correctly: bool) {
+        let mut rng = DevRng::new();
+        let n = 3;
+
+        let mut simulation = Simulation::<
+            EchoMsg<ThresholdMsg<E, SecurityLevel128, Sha256>, Sha256, (Point<E>, Scalar<E>)>,
+        >::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut auths = Schnorr::<E>::generate(&mut rng, n);
+        let malicious_auth = auths.remove(0);
+        let malicious_party = simulation.add_party();
+
+        let mut outputs = vec![];
+        for (i, auth) in (1..).zip(auths) {
+            let party = simulation.add_party();
+            let party = MpcParty::connected(EchoBroadcast::with_signatures(
+                eid,
+                i,
+                n,
+                party.delivery,
+                hash_msg,
+                auth,
+            ));
+            let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+
+            outputs.push(async move {
+                cggmp21::keygen::<E>(eid, i, n)
+                    .set_threshold(2)
+                    .start(&mut party_rng, party)
+                    .await
+            })
+        }
+
+        // Party 0 sends different commitments to party 1 and 2. It either signs both of them,
+        // or sends a signature of different data.
+        let (_incomings, mut outgoings) = malicious_party.delivery.split();
+        for j in 1..n {
+            let mut commitment = sha2::digest::Output::<Sha256>::default();
+            rng.fill_bytes(&mut commitment);
+            let msg = ThresholdMsg::Round1(MsgRound1 { commitment });
+            let signature = if sign_correctly {
+                // Same encoding of signed data as used by echo broadcast
+                let data = [
+                    b"dfns.cggmp21.echo_broadcast".as_slice(),
+                    &u64::try_from(eid.as_bytes().len()).unwrap().to_be_bytes(),
+                    eid.as_bytes(),
+                    &0u16.to_be_bytes(),
+                    &0u64.to_be_bytes(),
+                    &hash_msg(&msg),
+                ]
+                .concat();
+                malicious_auth.sign(&data)
+            } else {
+                malicious_auth.sign(b"something else")
+            };
+            outgoings
+                .send(Outgoing::p2p(
+                    j,
+                    EchoMsg::Send {
+                        seq: 0,
+                        msg,
+                        signature,
+                    },
+                ))
+                .await
+                .unwrap();
+        }
+
+        for result in futures::future::join_all(outputs).await {
+            let Err(err) = result else {
+                panic!("misbehaviour wasn't detected")
+            };
+            let err = format!("{err:?}");
+            if sign_correctly {
+                assert!(err.contains("SignedEquivocation { sender: 0 }"), "{err}");
+            } else {
+                assert!(err.contains("InvalidSignature { sender: 0 }"), "{err}");
+            }
+        }
+    }
+
+    #[test_case::case(None, 3; "n3")]
+    #[test_case::case(Some(3), 5; "t3n5")]
+    #[tokio::test]
+    async fn signing_over_echo_broadcast<E: Curve>(t: Option<u16>, n: u16)
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let mut rng = DevRng::new();
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, false)
+            .expect("retrieve cached shares");
+
+        let mut simulation = Simulation::<EchoMsg<SigningMsg<E, Sha256>, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut message_to_sign = [0u8; 100];
+        rng.fill_bytes(&mut message_to_sign);
+        let message_to_sign = DataToSign::digest::<Sha256>(&message_to_sign);
+
+        let t = shares[0].min_signers();
+        let mut participants = (0..n).collect::<Vec<_>>();
+        participants.shuffle(&mut rng);
+        let participants = &participants[..usize::from(t)];
+        let participants_shares = participants.iter().map(|i| &shares[usize::from(*i)]);
+
+        let mut outputs = vec![];
+        for (i, share) in (0..).zip(participants_shares) {
+            let party = simulation.add_party();
+            let party = MpcParty::connected(EchoBroadcast::new(i, t, party.delivery, hash_msg));
+            let mut party_rng = rng.fork();
+
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            });
+        }
+
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        signatures[0]
+            .verify(&shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s| *s == signatures[0]));
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
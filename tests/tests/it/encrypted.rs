@@ -0,0 +1,117 @@
+#[generic_tests::define(attrs(test))]
+mod generic {
+    use generic_ec::Curve;
+    use rand::RngCore;
+    use rand_dev::DevRng;
+
+    use cggmp21::key_share::encrypted::{self, EncryptedKeyShare, EncryptionKey};
+    use cggmp21::key_share::IncompleteKeyShare;
+    use cggmp21::{security_level::SecurityLevel128, KeyShare};
+
+    #[test]
+    fn seal_and_open<E: Curve>() {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(2), 3, false)
+            .expect("retrieve cached shares");
+        let share = &shares[0];
+
+        let mut kek = [0u8; 32];
+        rng.fill_bytes(&mut kek);
+        for key in [
+            EncryptionKey::Passphrase(b"correct horse battery staple"),
+            EncryptionKey::Kek(&kek),
+        ] {
+            let encrypted = EncryptedKeyShare::seal(share, key, &mut rng).unwrap();
+            assert_eq!(
+                encrypted.is_passphrase_protected(),
+                matches!(key, EncryptionKey::Passphrase(_))
+            );
+            // Public part is accessible without decryption
+            assert_eq!(
+                encrypted.key_info().shared_public_key,
+                share.shared_public_key
+            );
+
+            // Envelope survives serialization
+            let serialized = serde_json::to_vec(&encrypted).unwrap();
+            let encrypted: EncryptedKeyShare<E> = serde_json::from_slice(&serialized).unwrap();
+
+            let opened: KeyShare<E, SecurityLevel128> = encrypted.open(key).unwrap();
+            assert_eq!(
+                serde_json::to_vec(&opened).unwrap(),
+                serde_json::to_vec(share).unwrap()
+            );
+        }
+
+        // Core share can be sealed too
+        let encrypted =
+            EncryptedKeyShare::seal(&share.core, EncryptionKey::Kek(&kek), &mut rng).unwrap();
+        let core: IncompleteKeyShare<E> = encrypted.open(EncryptionKey::Kek(&kek)).unwrap();
+        assert_eq!(
+            serde_json::to_vec(&core).unwrap(),
+            serde_json::to_vec(&share.core).unwrap()
+        );
+    }
+
+    #[test]
+    fn wrong_key_or_modified_envelope_is_rejected<E: Curve>() {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let share = &shares[0];
+
+        let key = EncryptionKey::Passphrase(b"passphrase");
+        let encrypted = EncryptedKeyShare::seal(share, key, &mut rng).unwrap();
+        let open = |encrypted: &EncryptedKeyShare<E>, key: EncryptionKey<'_>| {
+            encrypted.open::<KeyShare<E, SecurityLevel128>>(key)
+        };
+
+        assert!(open(&encrypted, EncryptionKey::Passphrase(b"another passphrase")).is_err());
+        assert!(open(&encrypted, EncryptionKey::Kek(&[0u8; 32])).is_err());
+
+        // Public key info is authenticated
+        let mut modified = serde_json::to_value(&encrypted).unwrap();
+        let public_shares = modified["key_info"]["public_shares"]
+            .as_array_mut()
+            .unwrap();
+        public_shares.swap(0, 1);
+        let modified: EncryptedKeyShare<E> = serde_json::from_value(modified).unwrap();
+        assert!(open(&modified, key).is_err());
+
+        // Unknown version is rejected
+        let mut modified = serde_json::to_value(&encrypted).unwrap();
+        modified["version"] = 2.into();
+        let modified: EncryptedKeyShare<E> = serde_json::from_value(modified).unwrap();
+        assert!(open(&modified, key).is_err());
+
+        // Unauthenticated KDF parameters are bounded
+        for (param, limit) in [
+            ("m_cost", encrypted::MAX_ARGON2_M_COST),
+            ("t_cost", encrypted::MAX_ARGON2_T_COST),
+            ("p_cost", encrypted::MAX_ARGON2_P_COST),
+        ] {
+            let mut modified = serde_json::to_value(&encrypted).unwrap();
+            modified["kdf"][param] = (limit + 1).into();
+            let modified: EncryptedKeyShare<E> = serde_json::from_value(modified).unwrap();
+            let Err(err) = open(&modified, key) else {
+                panic!("envelope with {param} above the limit was opened")
+            };
+            let err = cggmp21_tests::error_chain(&err);
+            assert!(
+                err.contains("key derivation parameters exceed the limits"),
+                "{err}"
+            );
+        }
+
+        assert!(open(&encrypted, key).is_ok());
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
@@ -0,0 +1,219 @@
+#[generic_tests::define(attrs(tokio::test, test_case::case))]
+mod generic {
+    use generic_ec::{coords::HasAffineX, Curve, Point};
+    use rand::seq::SliceRandom;
+    use rand::{Rng, RngCore};
+    use rand_dev::DevRng;
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    use cggmp21::key_share::bip32::DerivationPath;
+    use cggmp21::key_share::{reconstruct_secret_key, AnyKeyShare, HdError};
+    use cggmp21::signing::{msg::Msg, DataToSign};
+    use cggmp21::{security_level::SecurityLevel128, ExecutionId};
+
+    #[test_case::case(None, 3; "n3")]
+    #[test_case::case(Some(2), 3; "t2n3")]
+    #[test_case::case(Some(3), 5; "t3n5")]
+    #[tokio::test]
+    async fn derive_child_share<E: Curve>(t: Option<u16>, n: u16) {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, true)
+            .expect("retrieve cached shares");
+
+        let path = cggmp21_tests::random_derivation_path(&mut rng);
+        let path_str = path
+            .iter()
+            .fold(String::from("m"), |acc, i| format!("{acc}/{i}"));
+        println!("Derivation path: {path_str}");
+        let parsed_path: DerivationPath = path_str.parse().unwrap();
+        assert_eq!(parsed_path.to_string(), path_str);
+
+        let child_epub = shares[0].derive_child_public_key(&parsed_path).unwrap();
+
+        let child_shares = shares
+            .iter()
+            .map(|share| share.derive_child_share(&parsed_path).unwrap())
+            .collect::<Vec<_>>();
+        for (child_share, share) in child_shares.iter().zip(&shares) {
+            assert_eq!(child_share.core.i, share.core.i);
+            assert_eq!(child_share.shared_public_key, child_epub.public_key);
+            assert_eq!(child_share.chain_code, Some(child_epub.chain_code));
+            assert_eq!(child_share.vss_setup, share.vss_setup);
+            assert_eq!(child_share.public_shares, child_shares[0].public_shares);
+        }
+
+        let child_sk = reconstruct_secret_key(&child_shares).unwrap();
+        assert_eq!(Point::generator() * &child_sk, child_epub.public_key);
+
+        // Deriving a child share step by step gives the same share
+        let (first, rest) = path.split_at(1);
+        let step_by_step = shares[0]
+            .derive_child_share(first.iter().copied())
+            .unwrap()
+            .derive_child_share(rest.iter().copied())
+            .unwrap();
+        assert_eq!(
+            serde_json::to_vec(&step_by_step).unwrap(),
+            serde_json::to_vec(&child_shares[0]).unwrap()
+        );
+    }
+
+    #[test_case::case(None, 3; "n3")]
+    #[test_case::case(Some(2), 3; "t2n3")]
+    #[tokio::test]
+    async fn signing_with_child_share<E: Curve>(t: Option<u16>, n: u16)
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, true)
+            .expect("retrieve cached shares");
+
+        let path = cggmp21_tests::random_derivation_path(&mut rng);
+        println!("Derivation path: {path:?}");
+        let child_shares = shares
+            .iter()
+            .map(|share| share.derive_child_share(path.iter().copied()).unwrap())
+            .collect::<Vec<_>>();
+        let public_key = child_shares[0].shared_public_key;
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut original_message_to_sign = [0u8; 100];
+        rng.fill_bytes(&mut original_message_to_sign);
+        let message_to_sign = DataToSign::digest::<Sha256>(&original_message_to_sign);
+
+        let t = child_shares[0].min_signers();
+        let mut participants = (0..n).collect::<Vec<_>>();
+        participants.shuffle(&mut rng);
+        let participants = &participants[..usize::from(t)];
+        println!("Signers: {participants:?}");
+        let participants_shares = participants.iter().map(|i| &child_shares[usize::from(*i)]);
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let mut outputs = vec![];
+        for (i, share) in (0..).zip(participants_shares) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+
+            outputs.push(async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            });
+        }
+
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        signatures[0]
+            .verify(&public_key, &message_to_sign)
+            .expect("signature is not valid");
+        assert!(signatures.iter().all(|s_i| signatures[0] == *s_i));
+    }
+
+    #[tokio::test]
+    async fn non_hd_share_cant_be_derived<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(2), 3, false)
+            .expect("retrieve cached shares");
+        assert!(matches!(
+            shares[0].derive_child_share([1u32, 2]),
+            Err(HdError::DisabledHd)
+        ));
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
+
+#[test]
+fn parse_derivation_path() {
+    use cggmp21::key_share::bip32::DerivationPath;
+
+    let path: DerivationPath = "m/44/60/0/0/5".parse().unwrap();
+    let indexes = path.indexes().iter().map(|i| u32::from(*i));
+    assert!(indexes.eq([44, 60, 0, 0, 5]));
+    assert_eq!(path.to_string(), "m/44/60/0/0/5");
+
+    let root: DerivationPath = "m".parse().unwrap();
+    assert!(root.indexes().is_empty());
+    assert_eq!(root.to_string(), "m");
+
+    for invalid in [
+        "",
+        "44/60",
+        "M/44",
+        "m/",
+        "m//1",
+        "m/+1",
+        "m/-1",
+        "m/a",
+        "m/44'",
+        "m/44h",
+        "m/2147483648",
+        "m/4294967296",
+    ] {
+        assert!(
+            invalid.parse::<DerivationPath>().is_err(),
+            "{invalid:?} must be rejected"
+        );
+    }
+}
+
+/// Test vector 2 from BIP32
+#[test]
+fn encode_xpub_test_vector() {
+    use cggmp21::key_share::bip32::{self, Version};
+    use cggmp21::supported_curves::Secp256k1;
+    use generic_ec::Point;
+
+    let public_key =
+        hex::decode("03cbcaa9c98c877a26977d00825c956a238e8dddfbd322cce4f74b0b5bd6ace4a7").unwrap();
+    let chain_code =
+        hex::decode("60499f801b896d83179a4374aeb7822aaeaceaa0db1f85ee3e904c4defbd9689").unwrap();
+    let master = cggmp21::slip_10::ExtendedPublicKey {
+        public_key: Point::<Secp256k1>::from_bytes(public_key).unwrap(),
+        chain_code: chain_code.try_into().unwrap(),
+    };
+
+    let xpub = bip32::encode_xpub(Version::Xpub, &master, &"m".parse().unwrap()).unwrap();
+    assert_eq!(xpub, "xpub661MyMwAqRbcFW31YEwpkMuc5THy2PSt5bDMsktWQcFF8syAmRUapSCGu8ED9W6oDMSgv6Zz8idoc4a6mr8BDzTJY47LJhkJ8UB7WEGuduB");
+
+    let xpub = bip32::encode_xpub(Version::Xpub, &master, &"m/0".parse().unwrap()).unwrap();
+    assert_eq!(xpub, "xpub69H7F5d8KSRgmmdJg2KhpAK8SR3DjMwAdkxj3ZuxV27CprR9LgpeyGmXUbC6wb7ERfvrnKZjXoUmmDznezpbZb7ap6r1D3tgFxHmwMkQTPH");
+
+    // Version bytes determine the prefix
+    for (version, prefix) in [
+        (Version::Ypub, "ypub"),
+        (Version::Zpub, "zpub"),
+        (Version::Tpub, "tpub"),
+        (Version::Upub, "upub"),
+        (Version::Vpub, "vpub"),
+    ] {
+        let encoded = bip32::encode_xpub(version, &master, &"m/0/1".parse().unwrap()).unwrap();
+        assert!(
+            encoded.starts_with(prefix),
+            "{encoded} must start with {prefix}"
+        );
+    }
+    // Other curves can't be encoded, even if their points have the same size
+    let master = cggmp21::slip_10::ExtendedPublicKey {
+        public_key: Point::<cggmp21::supported_curves::Secp256r1>::generator().to_point(),
+        chain_code: master.chain_code,
+    };
+    let err = bip32::encode_xpub(Version::Xpub, &master, &"m".parse().unwrap()).unwrap_err();
+    assert!(
+        cggmp21_tests::error_chain(&err).contains("only defined for secp256k1"),
+        "{err}"
+    );
+}
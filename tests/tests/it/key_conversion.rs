@@ -0,0 +1,130 @@
+#[generic_tests::define(attrs(tokio::test, test_case::case))]
+mod generic {
+    use generic_ec::{Curve, Scalar};
+    use rand::{seq::SliceRandom, Rng};
+    use rand_dev::DevRng;
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    use cggmp21::key_conversion::{self, msg::Msg};
+    use cggmp21::key_share::{reconstruct_secret_key, IncompleteKeyShare};
+    use cggmp21::{security_level::SecurityLevel128, ExecutionId};
+
+    #[test_case::case(2, 3, 2; "t2n3")]
+    #[test_case::case(3, 5, 3; "t3n5")]
+    #[test_case::case(3, 5, 4; "t3n5-4signers")]
+    #[tokio::test]
+    async fn to_additive<E: Curve>(t: u16, n: u16, k: usize) {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let sk = reconstruct_secret_key(&shares).unwrap();
+
+        let mut signers = (0..n).collect::<Vec<_>>();
+        signers.shuffle(&mut rng);
+        let signers = &signers[..k];
+        println!("Signers: {signers:?}");
+
+        let converted = signers
+            .iter()
+            .map(|&j| key_conversion::to_additive(&shares[usize::from(j)], signers).unwrap())
+            .collect::<Vec<_>>();
+        for (i, key_share) in (0u16..).zip(&converted) {
+            assert_eq!(key_share.i, i);
+            assert_eq!(key_share.n(), u16::try_from(k).unwrap());
+            assert_eq!(key_share.min_signers(), key_share.n());
+            assert!(key_share.vss_setup.is_none());
+            assert_eq!(key_share.shared_public_key, shares[0].shared_public_key);
+            assert_eq!(key_share.public_shares, converted[0].public_shares);
+        }
+        assert_eq!(
+            AsRef::<Scalar<E>>::as_ref(&reconstruct_secret_key(&converted).unwrap()),
+            AsRef::<Scalar<E>>::as_ref(&sk)
+        );
+    }
+
+    #[test_case::case(2, true; "n2")]
+    #[test_case::case(3, true; "n3")]
+    #[test_case::case(3, false; "n3-unreliable")]
+    #[test_case::case(5, true; "n5")]
+    #[tokio::test]
+    async fn to_threshold<E: Curve>(n: u16, reliable_broadcast: bool) {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, n, false)
+            .expect("retrieve cached shares");
+        let sk = reconstruct_secret_key(&shares).unwrap();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let mut outputs = vec![];
+        for share in &shares {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            outputs.push(async move {
+                cggmp21::to_threshold(eid)
+                    .enforce_reliable_broadcast(reliable_broadcast)
+                    .start(&mut party_rng, party, share)
+                    .await
+            });
+        }
+        let converted = futures::future::try_join_all(outputs).await.unwrap();
+
+        for (key_share, original) in converted.iter().zip(shares.iter()) {
+            assert_eq!(key_share.i, original.core.i);
+            assert_eq!(key_share.min_signers(), n);
+            assert_eq!(key_share.shared_public_key, original.shared_public_key);
+            assert_eq!(key_share.public_shares, converted[0].public_shares);
+            assert_eq!(key_share.vss_setup, converted[0].vss_setup);
+        }
+        assert_eq!(
+            AsRef::<Scalar<E>>::as_ref(&reconstruct_secret_key(&converted).unwrap()),
+            AsRef::<Scalar<E>>::as_ref(&sk)
+        );
+
+        // Converting it back yields additive key shares of the same key
+        let signers = (0..n).collect::<Vec<_>>();
+        let additive = converted
+            .iter()
+            .map(|key_share| key_conversion::to_additive(key_share, &signers).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            AsRef::<Scalar<E>>::as_ref(&reconstruct_secret_key(&additive).unwrap()),
+            AsRef::<Scalar<E>>::as_ref(&sk)
+        );
+    }
+
+    #[tokio::test]
+    async fn invalid_args_are_rejected<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(3), 5, false)
+            .expect("retrieve cached shares");
+        let key_share: &IncompleteKeyShare<E> = shares[0].as_ref();
+
+        assert!(key_conversion::to_additive(key_share, &[0, 1, 2]).is_ok());
+        // Not enough signers
+        assert!(key_conversion::to_additive(key_share, &[0, 1]).is_err());
+        // Signers are not distinct
+        assert!(key_conversion::to_additive(key_share, &[0, 1, 1]).is_err());
+        // Key share doesn't belong to any of signers
+        assert!(key_conversion::to_additive(key_share, &[1, 2, 3]).is_err());
+        // Signer index is out of bounds
+        assert!(key_conversion::to_additive(key_share, &[0, 1, 5]).is_err());
+
+        // Additive key share can't be converted to additive
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        assert!(key_conversion::to_additive(&shares[0], &[0, 1, 2]).is_err());
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
@@ -0,0 +1,88 @@
+#[generic_tests::define(attrs(test, test_case::case))]
+mod generic {
+    use generic_ec::Curve;
+
+    use cggmp21::security_level::SecurityLevel128;
+
+    #[test_case::case(None, 3; "n3")]
+    #[test_case::case(Some(2), 3; "t2n3")]
+    fn all_signers_agree_on_key_id_and_fingerprint<E: Curve>(t: Option<u16>, n: u16) {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, false)
+            .expect("retrieve cached shares");
+
+        let key_id = shares[0].core.key_id();
+        let fingerprint = shares[0].share_fingerprint();
+        for share in &shares[1..] {
+            assert_eq!(share.core.key_id(), key_id);
+            assert_eq!(share.share_fingerprint(), fingerprint);
+        }
+
+        // Key ID doesn't depend on epoch, but fingerprint does
+        let mut refreshed = shares[0].clone().into_inner();
+        refreshed.core.key_info.epoch += 1;
+        assert_eq!(refreshed.core.key_id(), key_id);
+        assert_ne!(refreshed.share_fingerprint(), fingerprint);
+
+        // Another key has another ID
+        let other_shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 2, false)
+            .expect("retrieve cached shares");
+        assert_ne!(other_shares[0].core.key_id(), key_id);
+        assert_ne!(other_shares[0].share_fingerprint(), fingerprint);
+
+        // Display format
+        let hex = fingerprint.to_string();
+        assert_eq!(hex.len(), 64);
+        let short = format!("{fingerprint:#}");
+        assert_eq!(short.len(), 19);
+        assert_eq!(short.replace('-', ""), hex[..16]);
+        assert_eq!(key_id.to_string().len(), 64);
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
+
+#[test]
+fn key_id_is_pinned() {
+    use cggmp21::key_share::DirtyKeyInfo;
+    use cggmp21::supported_curves::Secp256k1;
+    use generic_ec::{NonZero, Point, Scalar};
+
+    let point = |x: u64| NonZero::from_point(Point::generator() * Scalar::from(x)).unwrap();
+    let mut key_info = DirtyKeyInfo::<Secp256k1> {
+        curve: Default::default(),
+        shared_public_key: point(3),
+        public_shares: vec![point(1), point(2)],
+        vss_setup: None,
+        #[cfg(feature = "hd-wallets")]
+        chain_code: None,
+        epoch: 0,
+    };
+    let key_id = key_info.key_id();
+    assert_eq!(
+        key_id.to_string(),
+        "f9b749df543282da7967a531018772752f807119fbde9b1c70c9c4a8d87fc578"
+    );
+
+    // Missing chain code is encoded the same way with and without `hd-wallets` feature,
+    // so the pinned value above holds in both cases. Different chain codes give different IDs.
+    #[cfg(feature = "hd-wallets")]
+    {
+        key_info.chain_code = Some([1u8; 32]);
+        let key_id1 = key_info.key_id();
+        assert_ne!(key_id1, key_id);
+        key_info.chain_code = Some([2u8; 32]);
+        assert_ne!(key_info.key_id(), key_id1);
+        assert_ne!(key_info.key_id(), key_id);
+        key_info.chain_code = None;
+    }
+
+    key_info.epoch = 1;
+    assert_eq!(key_info.key_id(), key_id);
+}
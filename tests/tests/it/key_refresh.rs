@@ -1,4 +1,4 @@
-#[generic_tests::define(attrs(tokio::test, test_case::case))]
+#[generic_tests::define(attrs(tokio::test, test_case::case, cfg_attr))]
 mod generic {
     use generic_ec::Point;
     use rand::seq::SliceRandom;
@@ -7,17 +7,21 @@ mod generic {
     use sha2::Sha256;
 
     use cggmp21::{
-        key_share::{DirtyKeyShare, Validate},
+        key_share::{AnyKeyShare, DirtyKeyShare, Validate},
         security_level::SecurityLevel128,
         ExecutionId,
     };
 
-    #[test_case::case(3, false; "n3")]
-    #[test_case::case(5, false; "n5")]
-    #[test_case::case(5, true; "n5-reliable")]
+    #[test_case::case(3, false, false; "n3")]
+    #[test_case::case(5, false, false; "n5")]
+    #[test_case::case(5, true, false; "n5-reliable")]
+    #[cfg_attr(feature = "parallel", test_case::case(3, false, true; "n3-parallel"))]
     #[tokio::test]
-    async fn key_refresh_works<E: generic_ec::Curve>(n: u16, reliable_broadcast: bool)
-    where
+    async fn key_refresh_works<E: generic_ec::Curve>(
+        n: u16,
+        reliable_broadcast: bool,
+        parallel: bool,
+    ) where
         Point<E>: generic_ec::coords::HasAffineX<E>,
     {
         let mut rng = rand_dev::DevRng::new();
@@ -42,10 +46,13 @@ mod generic {
             let mut party_rng = rng.fork();
             let pregenerated_data = primes.next().expect("Can't fetch primes");
             async move {
-                cggmp21::key_refresh(eid, share, pregenerated_data)
-                    .enforce_reliable_broadcast(reliable_broadcast)
-                    .start(&mut party_rng, party)
-                    .await
+                let refresh = cggmp21::key_refresh(eid, share, pregenerated_data)
+                    .enforce_reliable_broadcast(reliable_broadcast);
+                #[cfg(feature = "parallel")]
+                let refresh = refresh.parallel(parallel);
+                #[cfg(not(feature = "parallel"))]
+                assert!(!parallel);
+                refresh.start(&mut party_rng, party).await
             }
         });
 
@@ -80,8 +87,31 @@ mod generic {
                 key_share.core.shared_public_key,
                 shares[0].core.shared_public_key
             );
+            assert_eq!(key_share.epoch(), shares[0].epoch() + 1);
         }
 
+        // epoch is omitted from serialized share only if it's zero
+        let serialized = serde_json::to_string(&shares[0]).unwrap();
+        assert!(!serialized.contains("epoch"));
+        let serialized = serde_json::to_string(&key_shares[0]).unwrap();
+        assert!(serialized.contains("epoch"));
+        let deserialized: cggmp21::KeyShare<E, SecurityLevel128> =
+            serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.epoch(), key_shares[0].epoch());
+
+        // all parties derive the same eid for the next refresh, and it changes after refresh
+        let next_eid = cggmp21::key_refresh::next_refresh_execution_id::<_, Sha256>(&key_shares[0]);
+        for key_share in &key_shares {
+            assert_eq!(
+                cggmp21::key_refresh::next_refresh_execution_id::<_, Sha256>(key_share),
+                next_eid
+            );
+        }
+        assert_ne!(
+            cggmp21::key_refresh::next_refresh_execution_id::<_, Sha256>(&shares[0]),
+            next_eid
+        );
+
         #[cfg(feature = "hd-wallets")]
         for key_share in &key_shares {
             assert_eq!(key_share.chain_code, shares[0].chain_code);
@@ -117,12 +147,17 @@ mod generic {
         }
     }
 
-    #[test_case::case(2, 3, false; "t2n3")]
-    #[test_case::case(3, 5, false; "t3n5")]
-    #[test_case::case(3, 5, true; "t3n5-reliable")]
+    #[test_case::case(2, 3, false, false; "t2n3")]
+    #[test_case::case(3, 5, false, false; "t3n5")]
+    #[test_case::case(3, 5, true, false; "t3n5-reliable")]
+    #[cfg_attr(feature = "parallel", test_case::case(3, 5, false, true; "t3n5-parallel"))]
     #[tokio::test]
-    async fn aux_gen_works<E: generic_ec::Curve>(t: u16, n: u16, reliable_broadcast: bool)
-    where
+    async fn aux_gen_works<E: generic_ec::Curve>(
+        t: u16,
+        n: u16,
+        reliable_broadcast: bool,
+        parallel: bool,
+    ) where
         Point<E>: generic_ec::coords::HasAffineX<E>,
     {
         let mut rng = rand_dev::DevRng::new();
@@ -145,10 +180,13 @@ mod generic {
             let mut party_rng = rng.fork();
             let pregenerated_data = primes.next().expect("Can't fetch primes");
             async move {
-                cggmp21::aux_info_gen(eid, i, n, pregenerated_data)
-                    .enforce_reliable_broadcast(reliable_broadcast)
-                    .start(&mut party_rng, party)
-                    .await
+                let aux_gen = cggmp21::aux_info_gen(eid, i, n, pregenerated_data)
+                    .enforce_reliable_broadcast(reliable_broadcast);
+                #[cfg(feature = "parallel")]
+                let aux_gen = aux_gen.parallel(parallel);
+                #[cfg(not(feature = "parallel"))]
+                assert!(!parallel);
+                aux_gen.start(&mut party_rng, party).await
             }
         });
 
@@ -160,7 +198,7 @@ mod generic {
 
         let key_shares = shares
             .into_iter()
-            .zip(aux_infos.into_iter())
+            .zip(aux_infos)
             .map(|(share, aux)| {
                 DirtyKeyShare {
                     core: share.into_inner().core,
@@ -208,6 +246,120 @@ mod generic {
         }
     }
 
+    #[test_case::case(3, 5, &[1], false; "t3n5-refresh1")]
+    #[test_case::case(3, 5, &[3, 0], true; "t3n5-refresh2-reliable")]
+    #[tokio::test]
+    async fn partial_aux_refresh_works<E: generic_ec::Curve>(
+        t: u16,
+        n: u16,
+        refreshing: &'static [u16],
+        reliable_broadcast: bool,
+    ) where
+        Point<E>: generic_ec::coords::HasAffineX<E>,
+    {
+        let mut rng = rand_dev::DevRng::new();
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+
+        // Perform partial refresh
+
+        let mut simulation =
+            Simulation::<cggmp21::key_refresh::PartialAuxMsg<Sha256, SecurityLevel128>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let outputs = (0..n).zip(&shares).map(|(i, share)| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let refresh = cggmp21::partial_aux_refresh(eid, i, share, refreshing)
+                .enforce_reliable_broadcast(reliable_broadcast);
+            let refresh = if refreshing.contains(&i) {
+                refresh.set_pregenerated_primes(primes.next().expect("Can't fetch primes"))
+            } else {
+                refresh
+            };
+            async move { refresh.start(&mut party_rng, party).await }
+        });
+
+        let aux_infos = futures::future::try_join_all(outputs)
+            .await
+            .expect("partial aux refresh failed");
+
+        // validate aux info
+
+        for (i, (aux, share)) in (0u16..).zip(aux_infos.iter().zip(&shares)) {
+            for (j, (new, old)) in (0u16..).zip(aux.parties.iter().zip(&share.aux.parties)) {
+                assert_eq!(new.N, aux_infos[0].parties[usize::from(j)].N);
+                assert_eq!(new.s, aux_infos[0].parties[usize::from(j)].s);
+                assert_eq!(new.t, aux_infos[0].parties[usize::from(j)].t);
+                if refreshing.contains(&j) {
+                    assert_ne!(new.N, old.N);
+                } else {
+                    assert_eq!(new.N, old.N);
+                    assert_eq!(new.s, old.s);
+                    assert_eq!(new.t, old.t);
+                }
+            }
+            assert_eq!(
+                cggmp21::rug::Integer::from(&aux.p * &aux.q),
+                aux.parties[usize::from(i)].N
+            );
+        }
+
+        let key_shares = shares
+            .into_iter()
+            .zip(aux_infos)
+            .map(|(share, aux)| {
+                DirtyKeyShare {
+                    core: share.into_inner().core,
+                    aux: aux.into_inner(),
+                }
+                .validate()
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // attempt to sign with new shares and verify the signature, refreshing
+        // parties are among signers
+
+        let mut simulation = Simulation::<cggmp21::signing::msg::Msg<E, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let message_to_sign = cggmp21::signing::DataToSign::digest::<Sha256>(&[42; 100]);
+
+        let mut participants = refreshing.to_vec();
+        participants.extend((0..n).filter(|j| !refreshing.contains(j)));
+        participants.truncate(usize::from(t));
+        let participants = &participants;
+        println!("Signers: {participants:?}");
+        let participants_shares = participants.iter().map(|i| &key_shares[usize::from(*i)]);
+
+        let outputs = participants_shares.zip(0..).map(|(share, i)| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::signing(eid, i, participants, share)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            }
+        });
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+
+        for signature in &signatures {
+            signature
+                .verify(&key_shares[0].core.shared_public_key, &message_to_sign)
+                .expect("signature is not valid");
+        }
+    }
+
     #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
     mod secp256r1 {}
     #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
@@ -215,3 +367,183 @@ mod generic {
     #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
     mod stark {}
 }
+
+mod pregenerated_primes {
+    use cggmp21::{
+        define_security_level, key_refresh::PregeneratedPrimes, security_level::SecurityLevel128,
+        ExecutionId,
+    };
+
+    /// Dummy security level that enables fast primes generation
+    #[derive(Clone)]
+    struct DummyLevel;
+    define_security_level!(DummyLevel {
+        security_bits = 32,
+        epsilon = 64,
+        ell = 128,
+        ell_prime = 128,
+        m = 128,
+        q = (cggmp21::rug::Integer::ONE.clone() << 128) - 1,
+    });
+
+    #[test]
+    fn validation_detects_invalid_primes() {
+        let mut rng = rand_dev::DevRng::new();
+
+        let primes = PregeneratedPrimes::<DummyLevel>::generate(&mut rng);
+        primes.validate().unwrap();
+
+        // p = q
+        let (p, _q) = primes.clone().split();
+        let equal = PregeneratedPrimes::<DummyLevel>::new(p.clone(), p).unwrap();
+        assert!(equal.validate().is_err());
+
+        // Blum primes which are not safe primes
+        let blum_primes = cggmp21_tests::CACHED_PRIMES
+            .iter::<SecurityLevel128>()
+            .next()
+            .unwrap();
+        assert!(blum_primes.validate().is_err());
+
+        // Primes are too small for the security level. Deserialization doesn't check that
+        let too_small: PregeneratedPrimes<SecurityLevel128> =
+            serde_json::from_str(&serde_json::to_string(&primes).unwrap()).unwrap();
+        assert!(too_small.validate().is_err());
+    }
+
+    #[tokio::test]
+    async fn aux_gen_fails_locally_on_invalid_primes() {
+        type Msg = cggmp21::key_refresh::AuxOnlyMsg<sha2::Sha256, SecurityLevel128>;
+        let mut rng = rand_dev::DevRng::new();
+
+        let blum_primes = cggmp21_tests::CACHED_PRIMES
+            .iter::<SecurityLevel128>()
+            .next()
+            .unwrap();
+
+        // Protocol must fail before any message is received
+        let incoming = futures::stream::pending::<
+            Result<round_based::Incoming<Msg>, std::convert::Infallible>,
+        >();
+        let outgoing = futures::sink::drain::<round_based::Outgoing<Msg>>();
+        let party = round_based::MpcParty::connected((incoming, outgoing));
+
+        let result = cggmp21::aux_info_gen(ExecutionId::new(b"eid"), 0, 3, blum_primes)
+            .validate_primes(true)
+            .start(&mut rng, party)
+            .await;
+        let Err(err) = result else {
+            panic!("invalid primes weren't detected")
+        };
+        let err = cggmp21_tests::error_chain(&err);
+        assert!(err.contains("pregenerated primes are invalid"), "{err}");
+    }
+}
+
+mod partial_aux_refresh {
+    use rand::Rng;
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    use cggmp21::{
+        key_share::AuxInfo, security_level::SecurityLevel128, supported_curves::Secp256k1,
+        ExecutionId,
+    };
+
+    #[tokio::test]
+    async fn mismatched_aux_info_is_detected() {
+        let mut rng = rand_dev::DevRng::new();
+        let (t, n) = (3, 5);
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<Secp256k1, SecurityLevel128>(Some(t), n, false)
+            .expect("retrieve cached shares");
+        let mut aux_infos = shares
+            .iter()
+            .map(|s| AsRef::<AuxInfo>::as_ref(s).clone())
+            .collect::<Vec<_>>();
+
+        // Last party has different view on aux data of other parties
+        let mut dirty = aux_infos[4].clone().into_inner();
+        dirty.parties.swap(0, 1);
+        aux_infos[4] = cggmp21::key_share::Validate::validate(dirty).unwrap();
+
+        let refreshing = &[2];
+        let mut simulation =
+            Simulation::<cggmp21::key_refresh::PartialAuxMsg<Sha256, SecurityLevel128>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
+
+        let outputs = (0..n).zip(&aux_infos).map(|(i, aux)| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            let refresh = cggmp21::partial_aux_refresh(eid, i, aux, refreshing);
+            let refresh = if refreshing.contains(&i) {
+                refresh.set_pregenerated_primes(primes.next().unwrap())
+            } else {
+                refresh
+            };
+            async move { refresh.start(&mut party_rng, party).await }
+        });
+        let results = futures::future::join_all(outputs).await;
+        for result in results {
+            let Err(err) = result else {
+                panic!("mismatched aux info wasn't detected")
+            };
+            let err = cggmp21_tests::error_chain(&err);
+            assert!(
+                err.contains("party started the protocol with different aux info"),
+                "{err}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn invalid_args_are_rejected() {
+        let mut rng = rand_dev::DevRng::new();
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<Secp256k1, SecurityLevel128>(Some(3), 5, false)
+            .expect("retrieve cached shares");
+        let aux = &shares[0];
+        let eid = ExecutionId::new(b"eid");
+
+        let refreshing_sets: [(&[u16], &str); 5] = [
+            (&[], "list of refreshing parties is empty"),
+            (&[5], "refreshing party index is out of bounds"),
+            (&[1, 1], "list of refreshing parties contains duplicates"),
+            (
+                &[0],
+                "pregenerated primes are required for the refreshing party",
+            ),
+            (
+                &[1],
+                "pregenerated primes are provided, but party is not refreshing its aux info",
+            ),
+        ];
+        for (k, (refreshing, reason)) in refreshing_sets.into_iter().enumerate() {
+            let refresh = cggmp21::partial_aux_refresh(eid, 0, aux, refreshing);
+            // Last set doesn't include party 0, so providing primes is an error
+            let refresh = if k == 4 {
+                refresh.set_pregenerated_primes(cggmp21_tests::CACHED_PRIMES.iter().next().unwrap())
+            } else {
+                refresh
+            };
+
+            type Msg = cggmp21::key_refresh::PartialAuxMsg<Sha256, SecurityLevel128>;
+            let incoming = futures::stream::pending::<
+                Result<round_based::Incoming<Msg>, std::convert::Infallible>,
+            >();
+            let outgoing = futures::sink::drain::<round_based::Outgoing<Msg>>();
+            let party = round_based::MpcParty::connected((incoming, outgoing));
+
+            let Err(err) = refresh.start(&mut rng, party).await else {
+                panic!("invalid args weren't detected: {refreshing:?}")
+            };
+            let err = cggmp21_tests::error_chain(&err);
+            assert!(err.contains("invalid arguments"), "{err}");
+            assert!(err.contains(reason), "{err}");
+        }
+    }
+}
@@ -7,6 +7,7 @@ mod generic {
     use round_based::simulation::Simulation;
     use sha2::Sha256;
 
+    use cggmp21::keygen::transcript::{verify_keygen_transcript, KeygenTranscript, TranscriptMsg};
     use cggmp21::keygen::{NonThresholdMsg, ThresholdMsg};
     use cggmp21::{
         key_share::reconstruct_secret_key, security_level::SecurityLevel128, ExecutionId,
@@ -155,6 +156,267 @@ mod generic {
         assert_eq!(Point::generator() * sk, key_shares[0].shared_public_key);
     }
 
+    #[test_case::case(None, false, false; "n3")]
+    #[test_case::case(None, true, false; "n3-reliable")]
+    #[test_case::case(Some(2), false, false; "t2n3")]
+    #[test_case::case(Some(2), true, false; "t2n3-reliable")]
+    #[cfg_attr(feature = "hd-wallets", test_case::case(None, true, true; "n3-reliable-hd"))]
+    #[cfg_attr(feature = "hd-wallets", test_case::case(Some(2), true, true; "t2n3-reliable-hd"))]
+    #[tokio::test]
+    async fn keygen_transcript_verifies<E: Curve>(
+        t: Option<u16>,
+        reliable_broadcast: bool,
+        hd_wallet: bool,
+    ) {
+        #[cfg(not(feature = "hd-wallets"))]
+        assert!(!hd_wallet);
+
+        let mut rng = DevRng::new();
+        let n = 3;
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut transcripts = (0..n)
+            .map(|_| KeygenTranscript::<E>::new())
+            .collect::<Vec<_>>();
+        let key_shares = if let Some(t) = t {
+            let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+            let outputs = (0..n).zip(&mut transcripts).map(|(i, transcript)| {
+                let party = simulation.add_party();
+                let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+                async move {
+                    let keygen = cggmp21::keygen(eid, i, n)
+                        .enforce_reliable_broadcast(reliable_broadcast)
+                        .set_threshold(t)
+                        .set_transcript_recorder(transcript);
+                    #[cfg(feature = "hd-wallets")]
+                    let keygen = keygen.hd_wallet(hd_wallet);
+                    keygen.start(&mut party_rng, party).await
+                }
+            });
+            futures::future::try_join_all(outputs).await
+        } else {
+            let mut simulation = Simulation::<NonThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+            let outputs = (0..n).zip(&mut transcripts).map(|(i, transcript)| {
+                let party = simulation.add_party();
+                let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+                async move {
+                    let keygen = cggmp21::keygen(eid, i, n)
+                        .enforce_reliable_broadcast(reliable_broadcast)
+                        .set_transcript_recorder(transcript);
+                    #[cfg(feature = "hd-wallets")]
+                    let keygen = keygen.hd_wallet(hd_wallet);
+                    keygen.start(&mut party_rng, party).await
+                }
+            });
+            futures::future::try_join_all(outputs).await
+        }
+        .expect("keygen failed");
+
+        for (transcript, key_share) in transcripts.iter().zip(&key_shares) {
+            assert_eq!(transcript.reliability_check.is_some(), reliable_broadcast);
+
+            // Transcript survives serialization
+            let serialized = serde_json::to_vec(transcript).unwrap();
+            let transcript: KeygenTranscript<E> = serde_json::from_slice(&serialized).unwrap();
+
+            verify_keygen_transcript::<E, SecurityLevel128, Sha256>(
+                &transcript,
+                &key_share.key_info,
+            )
+            .unwrap();
+
+            // Transcript doesn't match another key
+            let mut other_key_info = key_share.key_info.clone();
+            other_key_info.public_shares.swap(0, 1);
+            assert!(verify_keygen_transcript::<E, SecurityLevel128, Sha256>(
+                &transcript,
+                &other_key_info
+            )
+            .is_err());
+
+            // Tampered transcript is rejected
+            let mut tampered = transcript.clone();
+            let proof = tampered
+                .messages
+                .iter_mut()
+                .find_map(|m| match &mut m.msg {
+                    TranscriptMsg::SchnorrProof { sch_proof } => Some(sch_proof),
+                    _ => None,
+                })
+                .unwrap();
+            proof.0 += generic_ec::Scalar::one();
+            assert!(verify_keygen_transcript::<E, SecurityLevel128, Sha256>(
+                &tampered,
+                &key_share.key_info
+            )
+            .is_err());
+        }
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
+
+#[cfg(feature = "hd-wallets")]
+#[generic_tests::define(attrs(tokio::test, test_case::case))]
+mod chain_code {
+    use generic_ec::Curve;
+    use rand::{Rng, SeedableRng};
+    use rand_chacha::ChaCha20Rng;
+    use rand_dev::DevRng;
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    use cggmp21::keygen::transcript::{verify_keygen_transcript, KeygenTranscript};
+    use cggmp21::keygen::{NonThresholdMsg, ThresholdMsg};
+    use cggmp21::{key_share::IncompleteKeyShare, security_level::SecurityLevel128, ExecutionId};
+
+    /// Runs keygen where party `i` uses chain code `chain_codes[i]` (or derives it from
+    /// execution id if it's `None`)
+    async fn run_keygen<E: Curve>(
+        rng: &mut DevRng,
+        eid: ExecutionId<'_>,
+        t: Option<u16>,
+        chain_codes: &[Option<[u8; 32]>],
+    ) -> Vec<(
+        Result<IncompleteKeyShare<E>, cggmp21::KeygenError>,
+        KeygenTranscript<E>,
+    )> {
+        let n: u16 = chain_codes.len().try_into().unwrap();
+        let mut transcripts = (0..n)
+            .map(|_| KeygenTranscript::<E>::new())
+            .collect::<Vec<_>>();
+
+        let results = if let Some(t) = t {
+            let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+            let outputs = (0..n).zip(&mut transcripts).zip(chain_codes).map(
+                |((i, transcript), chain_code)| {
+                    let party = simulation.add_party();
+                    let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+                    async move {
+                        let keygen = cggmp21::keygen(eid, i, n)
+                            .set_threshold(t)
+                            .set_transcript_recorder(transcript);
+                        let keygen = match chain_code {
+                            Some(chain_code) => keygen.set_chain_code(*chain_code),
+                            None => keygen.derive_chain_code_from_execution_id(),
+                        };
+                        keygen.start(&mut party_rng, party).await
+                    }
+                },
+            );
+            futures::future::join_all(outputs).await
+        } else {
+            let mut simulation = Simulation::<NonThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+            let outputs = (0..n).zip(&mut transcripts).zip(chain_codes).map(
+                |((i, transcript), chain_code)| {
+                    let party = simulation.add_party();
+                    let mut party_rng = ChaCha20Rng::from_seed(rng.gen());
+                    async move {
+                        let keygen = cggmp21::keygen(eid, i, n).set_transcript_recorder(transcript);
+                        let keygen = match chain_code {
+                            Some(chain_code) => keygen.set_chain_code(*chain_code),
+                            None => keygen.derive_chain_code_from_execution_id(),
+                        };
+                        keygen.start(&mut party_rng, party).await
+                    }
+                },
+            );
+            futures::future::join_all(outputs).await
+        };
+
+        results.into_iter().zip(transcripts).collect()
+    }
+
+    #[test_case::case(None; "n3")]
+    #[test_case::case(Some(2); "t2n3")]
+    #[tokio::test]
+    async fn keygen_with_fixed_chain_code<E: Curve>(t: Option<u16>) {
+        let mut rng = DevRng::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let chain_code: [u8; 32] = rng.gen();
+
+        let outputs = run_keygen::<E>(&mut rng, eid, t, &[Some(chain_code); 3]).await;
+        for (key_share, transcript) in outputs {
+            let key_share = key_share.expect("keygen failed");
+            assert_eq!(key_share.chain_code, Some(chain_code));
+            assert!(transcript.fixed_chain_code);
+            verify_keygen_transcript::<E, SecurityLevel128, Sha256>(
+                &transcript,
+                &key_share.key_info,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test_case::case(None; "n3")]
+    #[test_case::case(Some(2); "t2n3")]
+    #[tokio::test]
+    async fn chain_code_derived_from_execution_id<E: Curve>(t: Option<u16>) {
+        let mut rng = DevRng::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut chain_codes = vec![];
+        for _ in 0..2 {
+            let outputs = run_keygen::<E>(&mut rng, eid, t, &[None; 3]).await;
+            for (key_share, transcript) in outputs {
+                let key_share = key_share.expect("keygen failed");
+                verify_keygen_transcript::<E, SecurityLevel128, Sha256>(
+                    &transcript,
+                    &key_share.key_info,
+                )
+                .unwrap();
+                chain_codes.push(key_share.chain_code.expect("chain code is missing"));
+            }
+        }
+
+        // Both executions result into the same chain code
+        assert!(chain_codes.iter().all(|c| *c == chain_codes[0]));
+
+        // Different execution id results into different chain code
+        let another_eid: [u8; 32] = rng.gen();
+        let outputs =
+            run_keygen::<E>(&mut rng, ExecutionId::new(&another_eid), t, &[None; 3]).await;
+        let key_share = outputs[0].0.as_ref().expect("keygen failed");
+        assert_ne!(key_share.chain_code, Some(chain_codes[0]));
+    }
+
+    #[test_case::case(None; "n3")]
+    #[test_case::case(Some(2); "t2n3")]
+    #[tokio::test]
+    async fn chain_code_mismatch_is_detected<E: Curve>(t: Option<u16>) {
+        let mut rng = DevRng::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let chain_code: [u8; 32] = rng.gen();
+        let another_chain_code: [u8; 32] = rng.gen();
+
+        let outputs = run_keygen::<E>(
+            &mut rng,
+            eid,
+            t,
+            &[Some(chain_code), Some(chain_code), Some(another_chain_code)],
+        )
+        .await;
+        for (result, _) in outputs {
+            let Err(err) = result else {
+                panic!("chain code mismatch wasn't detected")
+            };
+            assert!(format!("{err:?}").contains("ChainCodeMismatch"), "{err:?}");
+        }
+    }
+
     #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
     mod secp256k1 {}
     #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
@@ -1,7 +1,28 @@
+#[cfg(feature = "backup")]
+mod backup;
+mod compact;
+mod dyn_security_level;
+mod echo_broadcast;
+#[cfg(feature = "encrypted")]
+mod encrypted;
+#[cfg(feature = "hd-wallets")]
+mod hd_wallets;
+mod key_conversion;
+mod key_id;
 mod key_refresh;
 mod keygen;
+mod non_interactive_keygen;
 mod old_shares;
 mod pipeline;
+#[cfg(feature = "pop")]
+mod pop;
+#[cfg(feature = "prime-pool")]
+mod prime_pool;
+mod share_recovery;
+mod shared_aux;
 mod signing;
+mod split;
 mod stark_prehashed;
 mod trusted_dealer;
+mod versioned;
+mod zeroize;
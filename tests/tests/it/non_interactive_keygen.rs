@@ -0,0 +1,212 @@
+#[generic_tests::define(attrs(test, test_case::case))]
+mod generic {
+    use generic_ec::{Curve, Point, Scalar};
+    use rand::{seq::SliceRandom, Rng};
+    use rand_dev::DevRng;
+
+    use cggmp21::key_share::{reconstruct_secret_key, IncompleteKeyShare};
+    use cggmp21::keygen::non_interactive::{
+        Dealing, DecryptionKey, EncryptionKey, NonInteractiveKeygen,
+    };
+    use cggmp21::ExecutionId;
+
+    struct Setup<E: Curve> {
+        decryption_keys: Vec<DecryptionKey<E>>,
+        encryption_keys: Vec<EncryptionKey<E>>,
+    }
+
+    fn setup<E: Curve>(rng: &mut DevRng, n: u16) -> Setup<E> {
+        let decryption_keys = (0..n)
+            .map(|_| DecryptionKey::<E>::generate(rng))
+            .collect::<Vec<_>>();
+        let encryption_keys = decryption_keys
+            .iter()
+            .map(|dk| dk.encryption_key())
+            .collect::<Vec<_>>();
+        Setup {
+            decryption_keys,
+            encryption_keys,
+        }
+    }
+
+    fn check_key_shares<E: Curve>(rng: &mut DevRng, t: u16, key_shares: &[IncompleteKeyShare<E>]) {
+        for (i, key_share) in (0u16..).zip(key_shares) {
+            assert_eq!(key_share.i, i);
+            assert_eq!(key_share.shared_public_key, key_shares[0].shared_public_key);
+            assert_eq!(key_share.public_shares, key_shares[0].public_shares);
+            assert_eq!(
+                Point::<E>::generator() * &key_share.x,
+                key_share.public_shares[usize::from(i)]
+            );
+        }
+
+        let t_shares = key_shares
+            .choose_multiple(rng, t.into())
+            .cloned()
+            .collect::<Vec<_>>();
+        let sk = reconstruct_secret_key(&t_shares).unwrap();
+        assert_eq!(Point::generator() * sk, key_shares[0].shared_public_key);
+    }
+
+    #[test_case::case(2, 3; "t2n3")]
+    #[test_case::case(3, 5; "t3n5")]
+    #[test_case::case(5, 5; "t5n5")]
+    fn non_interactive_keygen_works<E: Curve>(t: u16, n: u16) {
+        let mut rng = DevRng::new();
+        let Setup {
+            decryption_keys,
+            encryption_keys,
+        } = setup::<E>(&mut rng, n);
+
+        let eid: [u8; 32] = rng.gen();
+        let keygen =
+            NonInteractiveKeygen::new(ExecutionId::new(&eid), t, &encryption_keys).unwrap();
+
+        let dealings = (0..n)
+            .map(|i| Some(keygen.deal(&mut rng, i).unwrap()))
+            .collect::<Vec<_>>();
+
+        // Dealings survive serialization
+        let dealings: Vec<Option<Dealing<E>>> =
+            serde_json::from_slice(&serde_json::to_vec(&dealings).unwrap()).unwrap();
+
+        for (j, dealing) in (0..).zip(&dealings) {
+            keygen.verify_dealing(j, dealing.as_ref().unwrap()).unwrap();
+        }
+
+        let key_shares = (0..n)
+            .zip(&decryption_keys)
+            .map(|(i, dk)| keygen.finalize(i, dk, &dealings).unwrap())
+            .collect::<Vec<_>>();
+        check_key_shares(&mut rng, t, &key_shares);
+    }
+
+    #[test]
+    fn share_encrypted_to_wrong_key_is_detected<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 4);
+        let Setup {
+            decryption_keys,
+            encryption_keys,
+        } = setup::<E>(&mut rng, n);
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let keygen = NonInteractiveKeygen::new(eid, t, &encryption_keys).unwrap();
+
+        let mut dealings = (0..n)
+            .map(|i| Some(keygen.deal(&mut rng, i).unwrap()))
+            .collect::<Vec<_>>();
+
+        // Malicious dealer encrypts share of the victim to a wrong key. Anyone can
+        // detect that the encrypted share is invalid.
+        let malicious_dealer = 2;
+        let victim: u16 = 1;
+        let mut wrong_keys = encryption_keys.clone();
+        wrong_keys[usize::from(victim)] = DecryptionKey::<E>::generate(&mut rng).encryption_key();
+        let malicious_dealing = NonInteractiveKeygen::new(eid, t, &wrong_keys)
+            .unwrap()
+            .deal(&mut rng, malicious_dealer)
+            .unwrap();
+        let err = keygen
+            .verify_dealing(malicious_dealer, &malicious_dealing)
+            .unwrap_err();
+        assert!(
+            format!("{:?}", err).contains("InvalidEncryptedShare { dealer: 2, recipient: 1 }"),
+            "{err:?}"
+        );
+        dealings[usize::from(malicious_dealer)] = Some(malicious_dealing);
+
+        assert_eq!(keygen.qualified_dealers(&dealings).unwrap(), [0, 1, 3]);
+        let key_shares = (0..n)
+            .zip(&decryption_keys)
+            .map(|(i, dk)| keygen.finalize(i, dk, &dealings).unwrap())
+            .collect::<Vec<_>>();
+        check_key_shares(&mut rng, t, &key_shares);
+    }
+
+    #[test]
+    fn not_enough_qualified_dealers<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (3, 4);
+        let Setup {
+            decryption_keys,
+            encryption_keys,
+        } = setup::<E>(&mut rng, n);
+
+        let eid: [u8; 32] = rng.gen();
+        let keygen =
+            NonInteractiveKeygen::new(ExecutionId::new(&eid), t, &encryption_keys).unwrap();
+
+        // Only 2 out of 4 parties posted a dealing
+        let dealings = (0..n)
+            .map(|i| (i < 2).then(|| keygen.deal(&mut rng, i).unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(keygen.qualified_dealers(&dealings).unwrap(), [0, 1]);
+
+        for (i, dk) in (0..n).zip(&decryption_keys) {
+            let Err(err) = keygen.finalize(i, dk, &dealings) else {
+                panic!("key share must not be finalized with less than t dealings")
+            };
+            assert!(
+                format!("{:?}", err).contains("NotEnoughQualifiedDealers"),
+                "{err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn invalid_dealing_is_excluded<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 6);
+        let Setup {
+            decryption_keys,
+            encryption_keys,
+        } = setup::<E>(&mut rng, n);
+
+        let eid: [u8; 32] = rng.gen();
+        let keygen =
+            NonInteractiveKeygen::new(ExecutionId::new(&eid), t, &encryption_keys).unwrap();
+
+        let mut dealings = (0..n)
+            .map(|i| Some(keygen.deal(&mut rng, i).unwrap()))
+            .collect::<Vec<_>>();
+        // Dealing of party 0 has an invalid proof
+        dealings[0].as_mut().unwrap().sch_proof.0 += Scalar::one();
+        // Encrypted shares in dealing of party 1 are swapped, so they don't match Feldman commitment
+        dealings[1].as_mut().unwrap().encrypted_shares.swap(0, 1);
+        // Encrypted bit in dealing of party 5 is modified, so it doesn't encrypt 0 or 1
+        dealings[5].as_mut().unwrap().encrypted_shares[2].bits[7].C +=
+            Point::generator() * Scalar::<E>::from(2);
+        // Party 3 didn't post a dealing
+        dealings[3] = None;
+
+        assert!(keygen
+            .verify_dealing(0, dealings[0].as_ref().unwrap())
+            .is_err());
+        assert!(keygen
+            .verify_dealing(1, dealings[1].as_ref().unwrap())
+            .is_err());
+        // Dealing of party 2 posted on behalf of party 4
+        assert!(keygen
+            .verify_dealing(4, dealings[2].as_ref().unwrap())
+            .is_err());
+        assert!(keygen
+            .verify_dealing(5, dealings[5].as_ref().unwrap())
+            .is_err());
+        assert_eq!(keygen.qualified_dealers(&dealings).unwrap(), [2, 4]);
+
+        let key_shares = (0..n)
+            .zip(&decryption_keys)
+            .map(|(i, dk)| keygen.finalize(i, dk, &dealings).unwrap())
+            .collect::<Vec<_>>();
+        check_key_shares(&mut rng, t, &key_shares);
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
@@ -1,9 +1,16 @@
+use cggmp21::key_share::versioned::{DetectedFormat, FormatVersion};
+
 static OLD_SHARES: include_dir::Dir =
     include_dir::include_dir!("$CARGO_MANIFEST_DIR/../test-data/old-shares");
 
 #[test]
 fn deserialize_old_shares() {
-    for share in OLD_SHARES.files() {
+    // Shares are grouped into directories by version of the library that produced them
+    let shares = OLD_SHARES
+        .files()
+        .chain(OLD_SHARES.dirs().flat_map(|dir| dir.files()));
+    let mut checked = 0;
+    for share in shares {
         let file_name = share
             .path()
             .file_name()
@@ -22,7 +29,9 @@ fn deserialize_old_shares() {
         } else {
             panic!("couldn't figure out the curve from the share name {file_name}")
         }
+        checked += 1;
     }
+    assert_ne!(checked, 0, "no old shares found");
 }
 
 fn deserialize_old_share_on_curve<E: generic_ec::Curve>(share: &include_dir::File) {
@@ -33,13 +42,84 @@ fn deserialize_old_share_on_curve<E: generic_ec::Curve>(share: &include_dir::Fil
         .to_str()
         .expect("key share ext is not valid utf8");
 
-    let _: cggmp21::IncompleteKeyShare<E> = match ext {
-        "json" => serde_json::from_slice(share.contents()).expect("deserialize share"),
+    let path = share.path().to_str().expect("share path is not valid utf8");
+    let expected_version = expected_version(path);
+    // Chain code is dropped on deserialization when HD wallets support is disabled, so such key
+    // shares are not serialized back to the same bytes
+    let same_bytes_expected = cfg!(feature = "hd-wallets") || !path.contains("hd-true");
+
+    match ext {
+        "json" => {
+            let version =
+                FormatVersion::detect(&mut serde_json::Deserializer::from_slice(share.contents()))
+                    .expect("detect version");
+            assert_eq!(version, expected_version, "{path}");
+
+            let key_share: cggmp21::IncompleteKeyShare<E> =
+                serde_json::from_slice(share.contents()).expect("deserialize share");
+
+            // Key share can be serialized back in the same format
+            let serialized =
+                serde_json::to_value(key_share.versioned(version).expect("versioned")).unwrap();
+            let original: serde_json::Value = serde_json::from_slice(share.contents()).unwrap();
+            if same_bytes_expected {
+                assert_eq!(serialized, original, "{path}");
+            }
+        }
         "cbor" => {
-            let bytes =
-                hex::decode(share.contents()).expect("cbor key share has invalid hex encoding");
-            ciborium::from_reader(bytes.as_slice()).expect("deserialize share")
+            let bytes = share.contents();
+            let DetectedFormat(version) = ciborium::from_reader(bytes).expect("detect version");
+            assert_eq!(version, expected_version, "{path}");
+
+            let key_share: cggmp21::IncompleteKeyShare<E> =
+                ciborium::from_reader(bytes).expect("deserialize share");
+
+            // Key share can be serialized back in the same format
+            let mut serialized = vec![];
+            ciborium::into_writer(&key_share.versioned(version).unwrap(), &mut serialized).unwrap();
+            if same_bytes_expected {
+                assert_eq!(serialized, bytes, "{path}");
+            }
         }
         _ => panic!("unknown extension {ext}"),
     };
 }
+
+/// Returns version of key share format expected to be detected based on its path
+fn expected_version(path: &str) -> FormatVersion {
+    if path.starts_with("v0.1.1/") {
+        FormatVersion::V0_1_1
+    } else if path.starts_with("v0.2.1/") {
+        FormatVersion::V0_2_1
+    } else if path.starts_with("v0.2.3/") {
+        FormatVersion::V0_2_3
+    } else {
+        panic!("unknown version of the share {path}")
+    }
+}
+
+#[test]
+fn crt_params_are_serialized_as_crt_exp() {
+    use cggmp21::fast_paillier::utils::CrtExp;
+    use cggmp21::key_share::CrtParams;
+
+    let (p, q) = cggmp21_tests::CACHED_PRIMES
+        .iter::<cggmp21::security_level::SecurityLevel128>()
+        .next()
+        .expect("no cached primes")
+        .split();
+
+    // Previous versions of the library stored `CrtExp` in the key share
+    let crt_exp = serde_json::to_value(CrtExp::build_n(&p, &q).unwrap()).unwrap();
+    let crt: CrtParams = serde_json::from_value(crt_exp.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&crt).unwrap(), crt_exp);
+    assert_eq!(
+        serde_json::to_value(CrtParams::new(&p, &q).unwrap()).unwrap(),
+        crt_exp
+    );
+
+    // Inconsistent parameters are rejected
+    let mut modified = crt_exp;
+    modified["n"] = modified["n1"].clone();
+    assert!(serde_json::from_value::<CrtParams>(modified).is_err());
+}
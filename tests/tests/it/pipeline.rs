@@ -9,7 +9,7 @@ mod generic {
     use cggmp21::{
         key_share::{AnyKeyShare, IncompleteKeyShare, KeyShare},
         keygen::ThresholdMsg,
-        security_level::SecurityLevel128,
+        security_level::{SecurityLevel, SecurityLevel128, SecurityLevel192, SecurityLevel256},
         ExecutionId,
     };
 
@@ -18,16 +18,39 @@ mod generic {
     #[cfg_attr(feature = "hd-wallets", test_case::case(3, 5, true; "t3n5-hd"))]
     #[tokio::test]
     async fn full_pipeline_works<E: Curve>(t: u16, n: u16, hd_enabled: bool)
+    where
+        Point<E>: generic_ec::coords::HasAffineX<E>,
+    {
+        full_pipeline::<E, SecurityLevel128>(t, n, hd_enabled).await
+    }
+
+    #[tokio::test]
+    async fn full_pipeline_works_with_security_level_192<E: Curve>()
+    where
+        Point<E>: generic_ec::coords::HasAffineX<E>,
+    {
+        full_pipeline::<E, SecurityLevel192>(2, 3, false).await
+    }
+
+    #[tokio::test]
+    async fn full_pipeline_works_with_security_level_256<E: Curve>()
+    where
+        Point<E>: generic_ec::coords::HasAffineX<E>,
+    {
+        full_pipeline::<E, SecurityLevel256>(2, 3, false).await
+    }
+
+    async fn full_pipeline<E: Curve, L: SecurityLevel>(t: u16, n: u16, hd_enabled: bool)
     where
         Point<E>: generic_ec::coords::HasAffineX<E>,
     {
         let mut rng = DevRng::new();
-        let incomplete_shares = run_keygen(t, n, hd_enabled, &mut rng).await;
-        let shares = run_aux_gen(incomplete_shares, &mut rng).await;
+        let incomplete_shares = run_keygen::<E, L>(t, n, hd_enabled, &mut rng).await;
+        let shares = run_aux_gen::<E, L>(incomplete_shares, &mut rng).await;
         run_signing(&shares, hd_enabled, &mut rng).await;
     }
 
-    async fn run_keygen<E>(
+    async fn run_keygen<E, L>(
         t: u16,
         n: u16,
         hd_enabled: bool,
@@ -35,11 +58,12 @@ mod generic {
     ) -> Vec<IncompleteKeyShare<E>>
     where
         E: Curve,
+        L: SecurityLevel,
     {
         #[cfg(not(feature = "hd-wallets"))]
         assert!(!hd_enabled);
 
-        let mut simulation = Simulation::<ThresholdMsg<E, SecurityLevel128, Sha256>>::new();
+        let mut simulation = Simulation::<ThresholdMsg<E, L, Sha256>>::new();
 
         let eid: [u8; 32] = rng.gen();
         let eid = ExecutionId::new(&eid);
@@ -50,7 +74,9 @@ mod generic {
             let mut party_rng = rng.fork();
 
             outputs.push(async move {
-                let keygen = cggmp21::keygen(eid, i, n).set_threshold(t);
+                let keygen = cggmp21::keygen(eid, i, n)
+                    .set_threshold(t)
+                    .set_security_level::<L>();
 
                 #[cfg(feature = "hd-wallets")]
                 let keygen = keygen.hd_wallet(hd_enabled);
@@ -64,18 +90,18 @@ mod generic {
             .expect("keygen failed")
     }
 
-    async fn run_aux_gen<E>(
+    async fn run_aux_gen<E, L>(
         shares: Vec<IncompleteKeyShare<E>>,
         rng: &mut DevRng,
-    ) -> Vec<KeyShare<E>>
+    ) -> Vec<KeyShare<E, L>>
     where
         E: Curve,
+        L: SecurityLevel,
     {
-        let mut primes = cggmp21_tests::CACHED_PRIMES.iter();
         let n = shares.len().try_into().unwrap();
+        let mut primes = cggmp21_tests::PregeneratedPrimes::cached::<L>().iter::<L>();
 
-        let mut simulation =
-            Simulation::<cggmp21::key_refresh::AuxOnlyMsg<Sha256, SecurityLevel128>>::new();
+        let mut simulation = Simulation::<cggmp21::key_refresh::AuxOnlyMsg<Sha256, L>>::new();
 
         let eid: [u8; 32] = rng.gen();
         let eid = ExecutionId::new(&eid);
@@ -97,16 +123,20 @@ mod generic {
 
         shares
             .into_iter()
-            .zip(aux_infos.into_iter())
+            .zip(aux_infos)
             .map(|(core, aux)| {
                 KeyShare::from_parts((core, aux)).expect("Couldn't make share from parts")
             })
             .collect()
     }
 
-    async fn run_signing<E>(shares: &[KeyShare<E>], random_derivation_path: bool, rng: &mut DevRng)
-    where
+    async fn run_signing<E, L>(
+        shares: &[KeyShare<E, L>],
+        random_derivation_path: bool,
+        rng: &mut DevRng,
+    ) where
         E: Curve,
+        L: SecurityLevel,
         Point<E>: generic_ec::coords::HasAffineX<E>,
     {
         #[cfg(not(feature = "hd-wallets"))]
@@ -0,0 +1,85 @@
+#[generic_tests::define(attrs(test, test_case::case))]
+mod generic {
+    use generic_ec::{Curve, Point, Scalar};
+    use rand::Rng;
+    use rand_dev::DevRng;
+
+    use cggmp21::key_share::pop::ProofOfPossession;
+    use cggmp21::security_level::SecurityLevel128;
+
+    #[test_case::case(None, 3; "n3")]
+    #[test_case::case(Some(2), 3; "t2n3")]
+    #[test_case::case(Some(3), 5; "t3n5")]
+    fn prove_and_verify_possession<E: Curve>(t: Option<u16>, n: u16) {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, false)
+            .expect("retrieve cached shares");
+        let key_info = &shares[0].core.key_info;
+
+        let context: [u8; 32] = rng.gen();
+        for share in &shares {
+            let proof = share.prove_possession(&context, &mut rng);
+            key_info
+                .verify_possession(share.core.i, &context, &proof)
+                .unwrap();
+
+            // Proof survives serialization
+            let serialized = serde_json::to_vec(&proof).unwrap();
+            let deserialized: ProofOfPossession<E> = serde_json::from_slice(&serialized).unwrap();
+            key_info
+                .verify_possession(share.core.i, &context, &deserialized)
+                .unwrap();
+
+            // Proof is bound to the context and to the signer
+            assert!(key_info
+                .verify_possession(share.core.i, b"another context", &proof)
+                .is_err());
+            assert!(key_info
+                .verify_possession((share.core.i + 1) % n, &context, &proof)
+                .is_err());
+            assert!(key_info.verify_possession(n, &context, &proof).is_err());
+        }
+    }
+
+    #[test]
+    fn invalid_proof_is_rejected<E: Curve>() {
+        let mut rng = DevRng::new();
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(2), 3, false)
+            .expect("retrieve cached shares");
+        let other_shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(3), 3, false)
+            .expect("retrieve cached shares");
+        let context = b"audit";
+
+        let proof = shares[0].prove_possession(context, &mut rng);
+        // Proof doesn't verify against another key
+        assert!(other_shares[0]
+            .core
+            .key_info
+            .verify_possession(0, context, &proof)
+            .is_err());
+
+        // Tampered proof doesn't verify
+        let mut tampered = proof.clone();
+        tampered.proof += Scalar::one();
+        assert!(shares[0].verify_possession(0, context, &tampered).is_err());
+        let mut tampered = proof;
+        tampered.commit += Point::generator();
+        assert!(shares[0].verify_possession(0, context, &tampered).is_err());
+
+        // Signer that doesn't know the secret share can't produce a valid proof
+        let mut forged = shares[1].clone().into_inner();
+        forged.core.i = 0;
+        let proof = forged.core.prove_possession(context, &mut rng);
+        assert!(shares[0].verify_possession(0, context, &proof).is_err());
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
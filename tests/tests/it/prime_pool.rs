@@ -0,0 +1,168 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use cggmp21::key_refresh::prime_pool::{CancellationToken, PrimePool};
+use cggmp21::rug::{integer::IsPrime, Integer};
+use cggmp21::security_level::SecurityLevel128;
+use cggmp21::{define_security_level, PregeneratedPrimes};
+use rand::Rng;
+use rand_dev::DevRng;
+
+/// Dummy security level that enables fast primes generation
+#[derive(Clone)]
+struct DummyLevel;
+define_security_level!(DummyLevel {
+    security_bits = 32,
+    epsilon = 64,
+    ell = 128,
+    ell_prime = 128,
+    m = 128,
+    q = (cggmp21::rug::Integer::ONE.clone() << 128) - 1,
+});
+
+fn is_safe_prime(p: &Integer) -> bool {
+    let q = (p.clone() - 1u32) >> 1u32;
+    p.is_probably_prime(25) != IsPrime::No && q.is_probably_prime(25) != IsPrime::No
+}
+
+fn check_primes(primes: PregeneratedPrimes<DummyLevel>) -> (Integer, Integer) {
+    let (p, q) = primes.split();
+    assert!(is_safe_prime(&p));
+    assert!(is_safe_prime(&q));
+    assert_eq!(p.significant_bits(), 4 * 32);
+    assert_eq!(q.significant_bits(), 4 * 32);
+    (p, q)
+}
+
+fn temp_file(rng: &mut DevRng) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("cggmp21-prime-pool-{}.bin", rng.gen::<u64>()))
+}
+
+fn wait_until_full(pool: &PrimePool<DummyLevel>) {
+    while pool.progress().available < pool.progress().target {
+        std::thread::sleep(std::time::Duration::from_millis(10))
+    }
+}
+
+#[test]
+fn pool_generates_primes() {
+    let mut rng = DevRng::new();
+
+    let reported = Arc::new(AtomicUsize::new(0));
+    let pool = PrimePool::<DummyLevel>::builder(3)
+        .set_threads(2)
+        .on_progress({
+            let reported = reported.clone();
+            move |progress| {
+                assert_eq!(progress.target, 3);
+                assert!(progress.available <= 3);
+                reported.fetch_add(1, Ordering::Relaxed);
+            }
+        })
+        .start(&mut rng)
+        .unwrap();
+
+    let mut primes = vec![];
+    for _ in 0..5 {
+        let (p, q) = check_primes(pool.wait_take().unwrap());
+        primes.extend([p, q]);
+    }
+    primes.sort();
+    primes.dedup();
+    assert_eq!(primes.len(), 10);
+
+    // Progress is reported at least on every take
+    assert!(reported.load(Ordering::Relaxed) >= 5);
+}
+
+#[test]
+fn persisted_primes_are_handed_out_once() {
+    let mut rng = DevRng::new();
+    let path = temp_file(&mut rng);
+    let key: [u8; 32] = rng.gen();
+
+    // Generate primes and persist them
+    let pool = PrimePool::<DummyLevel>::builder(3)
+        .persist_to(&path, key)
+        .start(&mut rng)
+        .unwrap();
+    wait_until_full(&pool);
+    drop(pool);
+
+    // Restarted pool with zero target doesn't generate new primes, but loads persisted ones
+    let pool = PrimePool::<DummyLevel>::builder(0)
+        .persist_to(&path, key)
+        .start(&mut rng)
+        .unwrap();
+    assert_eq!(pool.progress().available, 3);
+    let taken = check_primes(pool.take().unwrap().unwrap());
+    drop(pool);
+
+    // Taken primes are removed from the disk
+    let pool = PrimePool::<DummyLevel>::builder(0)
+        .persist_to(&path, key)
+        .start(&mut rng)
+        .unwrap();
+    assert_eq!(pool.progress().available, 2);
+    for _ in 0..2 {
+        let primes = check_primes(pool.take().unwrap().unwrap());
+        assert_ne!(primes, taken);
+    }
+    assert!(pool.take().unwrap().is_none());
+    drop(pool);
+
+    let pool = PrimePool::<DummyLevel>::builder(0)
+        .persist_to(&path, key)
+        .start(&mut rng)
+        .unwrap();
+    assert_eq!(pool.progress().available, 0);
+    drop(pool);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn persisted_pool_cannot_be_opened_with_wrong_key_or_security_level() {
+    let mut rng = DevRng::new();
+    let path = temp_file(&mut rng);
+    let key: [u8; 32] = rng.gen();
+
+    let pool = PrimePool::<DummyLevel>::builder(1)
+        .persist_to(&path, key)
+        .start(&mut rng)
+        .unwrap();
+    wait_until_full(&pool);
+    drop(pool);
+
+    let wrong_key: [u8; 32] = rng.gen();
+    assert!(PrimePool::<DummyLevel>::builder(0)
+        .persist_to(&path, wrong_key)
+        .start(&mut rng)
+        .is_err());
+    assert!(PrimePool::<SecurityLevel128>::builder(0)
+        .persist_to(&path, key)
+        .start(&mut rng)
+        .is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn pool_can_be_cancelled() {
+    let mut rng = DevRng::new();
+
+    let cancel = CancellationToken::new();
+    // Generating primes for 128 bits security level takes a while, so we cancel before they're generated
+    let pool = PrimePool::<SecurityLevel128>::builder(1)
+        .set_threads(1)
+        .set_cancellation_token(cancel.clone())
+        .start(&mut rng)
+        .unwrap();
+
+    std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        cancel.cancel()
+    });
+    assert!(pool.wait_take().is_err());
+    drop(pool)
+}
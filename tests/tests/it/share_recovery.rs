@@ -0,0 +1,138 @@
+#[generic_tests::define(attrs(tokio::test, test_case::case, cfg_attr))]
+mod generic {
+    use generic_ec::{Curve, Point, Scalar};
+    use rand::{seq::SliceRandom, Rng};
+    use rand_dev::DevRng;
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    use cggmp21::key_share::{IncompleteKeyShare, Validate};
+    use cggmp21::share_recovery::msg::Msg;
+    use cggmp21::{security_level::SecurityLevel128, trusted_dealer, ExecutionId};
+
+    fn generate_shares<E: Curve>(
+        rng: &mut DevRng,
+        t: u16,
+        n: u16,
+        hd_wallet: bool,
+    ) -> Vec<IncompleteKeyShare<E>> {
+        #[cfg(not(feature = "hd-wallets"))]
+        assert!(!hd_wallet);
+
+        let dealer = trusted_dealer::builder::<E, SecurityLevel128>(n).set_threshold(Some(t));
+        #[cfg(feature = "hd-wallets")]
+        let dealer = dealer.hd_wallet(hd_wallet);
+        dealer.generate_core_shares(rng).unwrap()
+    }
+
+    /// Runs share recovery protocol, returns outputs of helpers and the lost party
+    async fn run_recovery<E: Curve>(
+        rng: &mut DevRng,
+        lost_party: u16,
+        helpers: &[u16],
+        helpers_shares: &[IncompleteKeyShare<E>],
+        lost_party_key_info: &cggmp21::key_share::KeyInfo<E>,
+    ) -> (
+        Vec<Result<(), cggmp21::ShareRecoveryError>>,
+        Result<IncompleteKeyShare<E>, cggmp21::ShareRecoveryError>,
+    ) {
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+
+        let mut outputs = vec![];
+        for share in helpers_shares {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            outputs.push(async move {
+                cggmp21::share_recovery(eid, lost_party, helpers)
+                    .help(&mut party_rng, party, share)
+                    .await
+            });
+        }
+
+        let party = simulation.add_party();
+        let recovery =
+            cggmp21::share_recovery(eid, lost_party, helpers).recover(party, lost_party_key_info);
+
+        futures::future::join(futures::future::join_all(outputs), recovery).await
+    }
+
+    #[test_case::case(2, 3, 2, false; "t2n3")]
+    #[test_case::case(3, 5, 3, false; "t3n5")]
+    #[test_case::case(3, 5, 4, false; "t3n5-4helpers")]
+    #[test_case::case(5, 7, 6, false; "t5n7-6helpers")]
+    #[cfg_attr(feature = "hd-wallets", test_case::case(3, 5, 3, true; "t3n5-hd"))]
+    #[tokio::test]
+    async fn share_recovery_works<E: Curve>(t: u16, n: u16, h: usize, hd_wallet: bool) {
+        let mut rng = DevRng::new();
+        let shares = generate_shares::<E>(&mut rng, t, n, hd_wallet);
+
+        let mut parties = (0..n).collect::<Vec<_>>();
+        parties.shuffle(&mut rng);
+        let lost_party = parties[0];
+        let helpers = &parties[1..=h];
+        println!("Lost party: {lost_party}, helpers: {helpers:?}");
+
+        let helpers_shares = helpers
+            .iter()
+            .map(|j| shares[usize::from(*j)].clone())
+            .collect::<Vec<_>>();
+        // Lost party only knows public key info
+        let key_info = shares[0].key_info.clone().validate().unwrap();
+
+        let (helpers_outputs, recovered) =
+            run_recovery(&mut rng, lost_party, helpers, &helpers_shares, &key_info).await;
+        for output in helpers_outputs {
+            output.unwrap()
+        }
+        let recovered = recovered.unwrap();
+
+        let original = &shares[usize::from(lost_party)];
+        assert_eq!(recovered.i, lost_party);
+        assert_eq!(
+            AsRef::<Scalar<E>>::as_ref(&recovered.x),
+            AsRef::<Scalar<E>>::as_ref(&original.x)
+        );
+        assert_eq!(
+            Point::generator() * &recovered.x,
+            recovered.public_shares[usize::from(lost_party)]
+        );
+        assert_eq!(recovered.shared_public_key, original.shared_public_key);
+        assert_eq!(recovered.public_shares, original.public_shares);
+        assert_eq!(recovered.vss_setup, original.vss_setup);
+        #[cfg(feature = "hd-wallets")]
+        assert_eq!(recovered.chain_code, original.chain_code);
+    }
+
+    #[tokio::test]
+    async fn mismatched_key_info_is_detected<E: Curve>() {
+        let mut rng = DevRng::new();
+        let (t, n) = (2, 3);
+        let shares = generate_shares::<E>(&mut rng, t, n, false);
+        let other_shares = generate_shares::<E>(&mut rng, t, n, false);
+
+        // Lost party is given key info of another key
+        let key_info = other_shares[0].key_info.clone().validate().unwrap();
+        let (helpers_outputs, recovered) =
+            run_recovery(&mut rng, 0, &[1, 2], &shares[1..], &key_info).await;
+
+        for output in helpers_outputs {
+            let err = format!("{:?}", output.unwrap_err());
+            assert!(err.contains("MismatchedKeyInfo"), "{err}");
+        }
+        let Err(err) = recovered else {
+            panic!("mismatched key info wasn't detected")
+        };
+        let err = format!("{err:?}");
+        assert!(err.contains("MismatchedKeyInfo"), "{err}");
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
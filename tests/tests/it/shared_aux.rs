@@ -0,0 +1,129 @@
+#[generic_tests::define(attrs(test, tokio::test, test_case::case))]
+mod generic {
+    use std::sync::Arc;
+
+    use generic_ec::{coords::HasAffineX, Curve, Point};
+    use rand::{Rng, RngCore};
+    use rand_dev::DevRng;
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    use cggmp21::key_share::{KeyShareRef, SharedAuxStore, Validate};
+    use cggmp21::signing::{msg::Msg, DataToSign, SigningBuilder};
+    use cggmp21::{security_level::SecurityLevel128, ExecutionId};
+
+    #[test_case::case(None, 3; "n3")]
+    #[test_case::case(Some(2), 3; "t2n3")]
+    #[tokio::test]
+    async fn two_keys_share_aux<E: Curve>(t: Option<u16>, n: u16)
+    where
+        Point<E>: HasAffineX<E>,
+    {
+        let mut rng = DevRng::new();
+
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, false)
+            .expect("retrieve cached shares");
+        // Another key that uses the same aux info
+        let other_cores = cggmp21::trusted_dealer::builder::<E, SecurityLevel128>(n)
+            .set_threshold(t)
+            .generate_core_shares(&mut rng)
+            .unwrap();
+
+        let mut other_shares = vec![];
+        for (share, other_core) in shares.iter().zip(other_cores) {
+            let mut store = SharedAuxStore::<SecurityLevel128>::new();
+            let share = store.share_aux(share.clone()).unwrap();
+            let other_share = store
+                .key_share(other_core, &share.aux_fingerprint())
+                .unwrap();
+
+            // Both keys refer to the same copy of aux info
+            assert_eq!(store.len(), 1);
+            assert!(Arc::ptr_eq(&share.aux, &other_share.aux));
+            // Inserting the same aux info again doesn't duplicate it
+            store.insert((*share.aux).clone()).unwrap();
+            assert_eq!(store.len(), 1);
+
+            other_shares.push(other_share);
+        }
+
+        // Shared aux info can be used in signing
+        let mut simulation = Simulation::<Msg<E, Sha256>>::new();
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let mut message = [0u8; 100];
+        rng.fill_bytes(&mut message);
+        let message_to_sign = DataToSign::digest::<Sha256>(&message);
+
+        let t = t.unwrap_or(n);
+        let participants = &(0..t).collect::<Vec<_>>();
+        let mut outputs = vec![];
+        for (i, share) in (0..).zip(&other_shares[..usize::from(t)]) {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            outputs.push(async move {
+                SigningBuilder::<E, SecurityLevel128>::new_with_shared_aux(
+                    eid,
+                    i,
+                    participants,
+                    share,
+                )
+                .sign(&mut party_rng, party, message_to_sign)
+                .await
+            });
+        }
+        let signatures = futures::future::try_join_all(outputs)
+            .await
+            .expect("signing failed");
+        signatures[0]
+            .verify(&other_shares[0].shared_public_key, &message_to_sign)
+            .expect("signature is not valid");
+
+        // Key share ref can be converted back into regular key share
+        let key_share = other_shares[0].to_key_share().validate().unwrap();
+        assert_eq!(
+            key_share.shared_public_key,
+            other_shares[0].shared_public_key
+        );
+        assert_eq!(key_share.aux.p, other_shares[0].aux.p);
+    }
+
+    #[test]
+    fn aux_of_another_party_is_rejected<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let aux = |j: usize| Arc::new(shares[j].aux.clone().validate().unwrap());
+        let core = |j: usize| shares[j].core.clone().validate().unwrap();
+
+        // Aux info of another party can't be bound to the core share
+        assert!(KeyShareRef::<E, SecurityLevel128>::from_parts((core(0), aux(1))).is_err());
+        assert!(KeyShareRef::<E, SecurityLevel128>::from_parts((core(0), aux(0))).is_ok());
+
+        // Aux info generated for another amount of parties is rejected too
+        let other_shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 2, false)
+            .expect("retrieve cached shares");
+        let other_aux = Arc::new(other_shares[0].aux.clone().validate().unwrap());
+        assert!(KeyShareRef::<E, SecurityLevel128>::from_parts((core(0), other_aux)).is_err());
+
+        // Store doesn't accept aux info of another party with the same public data
+        let mut store = SharedAuxStore::<SecurityLevel128>::new();
+        let fingerprint = store.insert((*aux(0)).clone()).unwrap();
+        assert!(store.insert((*aux(1)).clone()).is_err());
+        assert!(store.key_share(core(1), &fingerprint).is_err());
+        assert!(store.key_share(core(0), &fingerprint).is_ok());
+
+        // Unknown fingerprint
+        store.remove(&fingerprint).unwrap();
+        assert!(store.key_share(core(0), &fingerprint).is_err());
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
@@ -8,7 +8,7 @@ mod generic {
     use round_based::simulation::Simulation;
     use sha2::Sha256;
 
-    use cggmp21::key_share::AnyKeyShare;
+    use cggmp21::key_share::{AnyKeyShare, Validate};
     use cggmp21::signing::{msg::Msg, DataToSign};
     use cggmp21::{security_level::SecurityLevel128, ExecutionId};
 
@@ -132,6 +132,15 @@ mod generic {
         let shares = cggmp21_tests::CACHED_SHARES
             .get_shares::<E, SecurityLevel128>(t, n, hd_wallet)
             .expect("retrieve cached shares");
+        // Make sure that ZK proofs are verified correctly with precomputed CRT parameters
+        let shares = shares
+            .into_iter()
+            .map(|share| {
+                let mut share = share.into_inner();
+                share.precompute_crt().unwrap();
+                share.validate().unwrap()
+            })
+            .collect::<Vec<_>>();
 
         let mut simulation = Simulation::<Msg<E, Sha256>>::new();
 
@@ -225,3 +234,55 @@ mod generic {
     #[instantiate_tests(<cggmp21::supported_curves::Stark, cggmp21_tests::external_verifier::blockchains::StarkNet>)]
     mod stark {}
 }
+
+mod epoch {
+    use rand::Rng;
+    use rand_dev::DevRng;
+    use round_based::simulation::Simulation;
+    use sha2::Sha256;
+
+    use cggmp21::key_share::{AnyKeyShare, Validate};
+    use cggmp21::signing::{msg::Msg, DataToSign};
+    use cggmp21::supported_curves::Secp256k1;
+    use cggmp21::{security_level::SecurityLevel128, ExecutionId};
+
+    #[tokio::test]
+    async fn signing_fails_on_mismatched_epoch() {
+        let mut rng = DevRng::new();
+
+        let mut shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<Secp256k1, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        assert!(shares.iter().all(|s| s.epoch() == 0));
+
+        // Party 2 pretends to hold refreshed key share
+        let mut share = shares[2].clone().into_inner();
+        share.core.key_info.epoch += 1;
+        shares[2] = share.validate().unwrap();
+
+        let mut simulation = Simulation::<Msg<Secp256k1, Sha256>>::new();
+
+        let eid: [u8; 32] = rng.gen();
+        let eid = ExecutionId::new(&eid);
+        let message_to_sign = DataToSign::digest::<Sha256>(b"hello");
+        let participants = &[0, 1, 2];
+
+        let outputs = shares.iter().map(|share| {
+            let party = simulation.add_party();
+            let mut party_rng = rng.fork();
+            async move {
+                cggmp21::signing(eid, share.core.i, participants, share)
+                    .sign(&mut party_rng, party, message_to_sign)
+                    .await
+            }
+        });
+        let results = futures::future::join_all(outputs).await;
+
+        for result in results {
+            let Err(err) = result else {
+                panic!("signing must fail")
+            };
+            assert!(format!("{err:?}").contains("MismatchedEpoch"));
+        }
+    }
+}
@@ -0,0 +1,74 @@
+#[generic_tests::define(attrs(test, test_case::case))]
+mod generic {
+    use generic_ec::Curve;
+
+    use cggmp21::key_share::{split, Validate};
+    use cggmp21::security_level::SecurityLevel128;
+
+    #[test_case::case(None, 3; "n3")]
+    #[test_case::case(Some(2), 3; "t2n3")]
+    fn split_and_join<E: Curve>(t: Option<u16>, n: u16) {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(t, n, false)
+            .expect("retrieve cached shares");
+
+        for share in &shares {
+            let (public, secret) = split::split_key_share(share);
+            assert_eq!(public.commitment(), secret.commitment);
+            // Public half doesn't contain secrets
+            let public_json = serde_json::to_string(&public).unwrap();
+            assert!(!public_json.contains(&share.aux.p.to_string_radix(16)));
+            assert!(public.parties.iter().all(|aux_j| aux_j.crt.is_none()));
+
+            // Halves survive serialization
+            let public: split::PublicKeyShare<E, SecurityLevel128> =
+                serde_json::from_str(&public_json).unwrap();
+            let secret: split::SecretKeyShare<E> =
+                serde_json::from_str(&serde_json::to_string(&secret).unwrap()).unwrap();
+
+            let joined = split::join_key_share(public, secret).unwrap();
+            assert_eq!(
+                serde_json::to_string(&joined.core).unwrap(),
+                serde_json::to_string(&share.core).unwrap()
+            );
+            assert_eq!(joined.aux.p, share.aux.p);
+            assert_eq!(joined.aux.q, share.aux.q);
+
+            // Same for core share
+            let (public, secret) = split::split_core_share(&share.core.clone().validate().unwrap());
+            let joined = split::join_core_share(public, secret).unwrap();
+            assert_eq!(joined.shared_public_key, share.shared_public_key);
+        }
+    }
+
+    #[test]
+    fn halves_of_different_shares_cant_be_joined<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+
+        let (public0, secret0) = split::split_key_share(&shares[0]);
+        let (public1, secret1) = split::split_key_share(&shares[1]);
+        assert!(split::join_key_share(public0.clone(), secret1).is_err());
+        assert!(split::join_key_share(public1, secret0.clone()).is_err());
+
+        // Modified public half doesn't match the commitment
+        let mut modified = public0.clone();
+        modified.core.key_info.epoch += 1;
+        assert!(split::join_key_share(modified, secret0.clone()).is_err());
+
+        // Commitment matches, but secret doesn't fit public half
+        let mut forged = secret0.clone();
+        forged.x = shares[1].core.x.clone();
+        assert!(split::join_key_share(public0.clone(), forged).is_err());
+
+        assert!(split::join_key_share(public0, secret0).is_ok());
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
@@ -0,0 +1,99 @@
+#[generic_tests::define(attrs(test))]
+mod generic {
+    use generic_ec::Curve;
+
+    use cggmp21::key_share::versioned::{DetectedFormat, FormatVersion};
+    use cggmp21::key_share::{IncompleteKeyShare, Validate};
+    use cggmp21::{security_level::SecurityLevel128, KeyShare};
+
+    #[test]
+    fn current_format<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(Some(2), 3, false)
+            .expect("retrieve cached shares");
+        let share = &shares[0];
+
+        let serialized = serde_json::to_value(share).unwrap();
+        assert_eq!(serialized["core"]["version"], 1);
+        assert_eq!(
+            FormatVersion::detect(&serialized).unwrap(),
+            FormatVersion::CURRENT
+        );
+        assert_eq!(
+            FormatVersion::detect(&serialized["core"]).unwrap(),
+            FormatVersion::CURRENT
+        );
+
+        let mut cbor = vec![];
+        ciborium::into_writer(share, &mut cbor).unwrap();
+        let DetectedFormat(version) = ciborium::from_reader(cbor.as_slice()).unwrap();
+        assert_eq!(version, FormatVersion::CURRENT);
+    }
+
+    #[test]
+    fn rollback<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let share = &shares[0];
+
+        for version in [
+            FormatVersion::V0_1_1,
+            FormatVersion::V0_2_1,
+            FormatVersion::V0_2_3,
+            FormatVersion::V1,
+        ] {
+            let serialized = serde_json::to_vec(&share.versioned(version).unwrap()).unwrap();
+            assert_eq!(
+                FormatVersion::detect(&mut serde_json::Deserializer::from_slice(&serialized))
+                    .unwrap(),
+                version
+            );
+
+            // Key share is migrated back to the current format
+            let migrated: KeyShare<E, SecurityLevel128> =
+                serde_json::from_slice(&serialized).unwrap();
+            assert_eq!(
+                serde_json::to_vec(&migrated).unwrap(),
+                serde_json::to_vec(share).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn refreshed_share_cant_be_rolled_back<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+        let mut share = shares[0].core.clone();
+        share.key_info.epoch = 1;
+        let share: IncompleteKeyShare<E> = share.validate().unwrap();
+
+        assert!(share.versioned(FormatVersion::V0_2_3).is_err());
+        assert!(share.versioned(FormatVersion::V0_1_1).is_err());
+
+        let serialized = serde_json::to_vec(&share.versioned(FormatVersion::V1).unwrap()).unwrap();
+        let deserialized: IncompleteKeyShare<E> = serde_json::from_slice(&serialized).unwrap();
+        assert_eq!(deserialized.key_info.epoch, 1);
+    }
+
+    #[test]
+    fn unknown_version_is_rejected<E: Curve>() {
+        let shares = cggmp21_tests::CACHED_SHARES
+            .get_shares::<E, SecurityLevel128>(None, 3, false)
+            .expect("retrieve cached shares");
+
+        let mut serialized = serde_json::to_value(&shares[0].core).unwrap();
+        serialized["version"] = 2.into();
+
+        assert!(FormatVersion::detect(&serialized).is_err());
+        assert!(serde_json::from_value::<IncompleteKeyShare<E>>(serialized).is_err());
+    }
+
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256k1>)]
+    mod secp256k1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Secp256r1>)]
+    mod secp256r1 {}
+    #[instantiate_tests(<cggmp21::supported_curves::Stark>)]
+    mod stark {}
+}
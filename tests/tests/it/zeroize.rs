@@ -0,0 +1,137 @@
+//! Checks that secret big integers are wiped from memory
+//!
+//! Wiping is verified by inspecting the limbs buffer allocated by GMP. We can only do that while the
+//! buffer is still allocated, so the tests call `zeroize()` explicitly (which is what `Drop` does)
+//! and make sure the buffer hasn't been reallocated in the process.
+
+use cggmp21::key_share::split;
+use cggmp21::rug::Integer;
+use cggmp21::security_level::SecurityLevel128;
+use cggmp21::supported_curves::Secp256k1;
+use cggmp21::{ExecutionId, PregeneratedPrimes};
+use generic_ec::Scalar;
+use rand::Rng;
+use rand_dev::DevRng;
+use round_based::simulation::Simulation;
+use sha2::Sha256;
+use zeroize::Zeroize;
+
+/// Location and size in bytes of the limbs buffer allocated by the integer
+fn buffer(x: &Integer) -> (*const u8, usize) {
+    // Safety: `as_raw` returns a pointer to internals of `x` which is valid while `x` is borrowed
+    let raw = unsafe { &*x.as_raw() };
+    (raw.d.as_ptr().cast_const().cast(), x.capacity() / 8)
+}
+
+/// Asserts that `x` still owns the `buffer` and that it's filled with zeroes
+fn assert_wiped(x: &Integer, buffer_before: (*const u8, usize)) {
+    assert_eq!(*x, 0);
+    assert_eq!(buffer(x), buffer_before, "buffer was reallocated");
+
+    let (ptr, len) = buffer_before;
+    // Safety: the buffer is owned by `x` and was entirely overwritten by zeroes, so it's
+    // initialized
+    let bytes = unsafe { std::slice::from_raw_parts(ptr, len) };
+    assert!(!bytes.is_empty());
+    assert!(bytes.iter().all(|b| *b == 0), "buffer is not wiped");
+}
+
+#[test]
+fn aux_info() {
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 3, false)
+        .expect("retrieve cached shares");
+    let mut aux = shares[0].clone().into_inner().aux;
+    assert_ne!(aux.p, 0);
+    assert_ne!(aux.q, 0);
+    aux.precompute_crt(0).unwrap();
+    let crt = aux.parties[0].crt.as_ref().unwrap();
+    assert!(crt.crt_exp().is_some());
+    assert!(serde_json::to_value(crt).is_ok());
+
+    let (p, q) = (buffer(&aux.p), buffer(&aux.q));
+    let (crt_p, crt_q) = crt.primes();
+    let (crt_p, crt_q) = (buffer(crt_p), buffer(crt_q));
+    aux.zeroize();
+    assert_wiped(&aux.p, p);
+    assert_wiped(&aux.q, q);
+
+    // CRT parameters are wiped as well, so they can't be serialized anymore
+    let crt = aux.parties[0].crt.as_ref().unwrap();
+    let (wiped_p, wiped_q) = crt.primes();
+    assert_wiped(wiped_p, crt_p);
+    assert_wiped(wiped_q, crt_q);
+    assert!(crt.crt_exp().is_none());
+    assert!(serde_json::to_value(crt).is_err());
+}
+
+#[tokio::test]
+async fn presignature() {
+    let mut rng = DevRng::new();
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(None, 2, false)
+        .expect("retrieve cached shares");
+
+    let eid: [u8; 32] = rng.gen();
+    let eid = ExecutionId::new(&eid);
+    let participants: &[u16] = &[0, 1];
+
+    let mut simulation = Simulation::<cggmp21::signing::msg::Msg<Secp256k1, Sha256>>::new();
+    let outputs = shares.iter().zip(0..).map(|(share, i)| {
+        let party = simulation.add_party();
+        let mut party_rng = rng.fork();
+        async move {
+            cggmp21::signing(eid, i, participants, share)
+                .generate_presignature(&mut party_rng, party)
+                .await
+        }
+    });
+    let mut presignatures = futures::future::try_join_all(outputs)
+        .await
+        .expect("presigning failed");
+
+    let presig = &mut presignatures[0];
+    assert_ne!(*presig.k.as_ref(), Scalar::zero());
+    assert_ne!(*presig.chi.as_ref(), Scalar::zero());
+    let r = presig.R;
+
+    presig.zeroize();
+    assert_eq!(*presig.k.as_ref(), Scalar::zero());
+    assert_eq!(*presig.chi.as_ref(), Scalar::zero());
+    // Public component is retained
+    assert_eq!(presig.R, r);
+}
+
+#[test]
+fn secret_half_of_key_share() {
+    let shares = cggmp21_tests::CACHED_SHARES
+        .get_shares::<Secp256k1, SecurityLevel128>(Some(2), 3, false)
+        .expect("retrieve cached shares");
+    let (_public, mut secret) = split::split_key_share(&shares[0]);
+    assert_ne!(secret.p, 0);
+    assert_ne!(secret.q, 0);
+
+    let (p, q) = (buffer(&secret.p), buffer(&secret.q));
+    secret.zeroize();
+    assert_wiped(&secret.p, p);
+    assert_wiped(&secret.q, q);
+}
+
+#[test]
+fn pregenerated_primes() {
+    let (p, q) = cggmp21_tests::CACHED_PRIMES
+        .iter::<SecurityLevel128>()
+        .next()
+        .expect("no cached primes")
+        .split();
+    assert_ne!(p, 0);
+    assert_ne!(q, 0);
+
+    // Integers keep their buffers when moved in and out of `PregeneratedPrimes`
+    let (p_buffer, q_buffer) = (buffer(&p), buffer(&q));
+    let mut primes = PregeneratedPrimes::<SecurityLevel128>::new(p, q).unwrap();
+    primes.zeroize();
+    let (p, q) = primes.split();
+    assert_wiped(&p, p_buffer);
+    assert_wiped(&q, q_buffer);
+}